@@ -15,8 +15,15 @@ use sequencer::{
     error::{self, Error},
     logger::PanicLog,
     merkle_tree_manager::MerkleTreeManager,
+    monitoring::serve_monitoring,
+    profiler::Profiler,
     rpc::{cluster, external, internal},
+    shutdown::{shutdown, wait_for_signal},
     state::AppState,
+    task::{
+        backfill_transaction_periodically, prune_periodically,
+        submit_block_commitment_periodically, sync_block_history_periodically,
+    },
     types::*,
     util::initialize_logger,
 };
@@ -93,8 +100,13 @@ async fn start_sequencer(config_option: &mut ConfigOption) -> Result<(), Error>
     initialize_logger(&config)?;
 
     // Initialize the profiler.
-    // let profiler = Profiler::init("http://127.0.0.1:4040", "sequencer", 100)?;
-    let profiler = None;
+    let profiler = Profiler::new(config.profiler_settings());
+    if config.is_profiler_enabled {
+        // The node runs without profiling rather than not at all.
+        if let Err(error) = profiler.start(None).await {
+            tracing::error!("Failed to start the profiler: {:?}", error);
+        }
+    }
 
     // Initialize the database
     let kv_store = KvStoreBuilder::default()
@@ -131,7 +143,7 @@ async fn start_sequencer(config_option: &mut ConfigOption) -> Result<(), Error>
         .skde_params;
 
     let rpc_client = RpcClient::new().map_err(error::Error::RpcClient)?;
-    let merkle_tree_manager = MerkleTreeManager::init(&rpc_client).await;
+    let merkle_tree_manager = MerkleTreeManager::init(&rpc_client).await?;
     let app_state: AppState = AppState::new(
         config,
         seeder_client,
@@ -146,16 +158,28 @@ async fn start_sequencer(config_option: &mut ConfigOption) -> Result<(), Error>
     );
 
     initialize_clients(app_state.clone()).await?;
+    backfill_transaction_periodically(app_state.clone());
+    sync_block_history_periodically(app_state.clone());
+    prune_periodically(app_state.clone());
+    submit_block_commitment_periodically(app_state.clone());
+    initialize_monitoring_server(app_state.clone())?;
 
     let internal_handle = tokio::spawn(initialize_internal_rpc_server(app_state.clone()));
     let cluster_handle = tokio::spawn(initialize_cluster_rpc_server(app_state.clone()));
     let external_handle = tokio::spawn(initialize_external_rpc_server(app_state.clone()));
 
     let handles = vec![internal_handle, cluster_handle, external_handle];
-    let results = try_join_all(handles).await;
-    if let Err(e) = results {
-        tracing::error!("One of the RPC servers terminated unexpectedly: {:?}", e);
-        return Err(error::Error::RpcServerTerminated);
+    tokio::select! {
+        results = try_join_all(handles) => {
+            if let Err(e) = results {
+                tracing::error!("One of the RPC servers terminated unexpectedly: {:?}", e);
+                return Err(error::Error::RpcServerTerminated);
+            }
+        }
+        signal = wait_for_signal() => {
+            signal?;
+            shutdown(&app_state).await?;
+        }
     }
 
     Ok(())
@@ -200,13 +224,13 @@ async fn initialize_clients(app_state: AppState) -> Result<(), Error> {
                     liveness_info,
                 );
             }
-            SequencingInfoPayload::Local(_payload) => {
-                tracing::warn!(
-                    "Local LivenessClient not implemented for platform {:?} and service provider {:?}",
-                    platform,
-                    service_provider
+            SequencingInfoPayload::Local(liveness_info) => {
+                liveness::local::LivenessClient::initialize(
+                    app_state.clone(),
+                    *platform,
+                    *service_provider,
+                    liveness_info,
                 );
-                todo!("Implement 'LivenessClient' for local sequencing.");
             }
         }
     }
@@ -247,11 +271,31 @@ async fn initialize_internal_rpc_server(context: AppState) -> Result<(), Error>
         .register_rpc_method::<internal::AddSequencingInfo>()?
         .register_rpc_method::<internal::AddValidationInfo>()?
         .register_rpc_method::<internal::AddCluster>()?
+        .register_rpc_method::<internal::Deregister>()?
         .register_rpc_method::<internal::GetCluster>()?
         .register_rpc_method::<internal::GetClusterIdList>()?
+        .register_rpc_method::<internal::GetGossipStatus>()?
+        .register_rpc_method::<internal::SetRetentionPolicy>()?
+        .register_rpc_method::<internal::GetPruningStatus>()?
+        .register_rpc_method::<internal::PruneRollup>()?
+        .register_rpc_method::<internal::SetSubmissionPolicy>()?
+        .register_rpc_method::<internal::SetBlockCommitmentAggregationSize>()?
+        .register_rpc_method::<internal::SubmitBlockCommitment>()?
+        .register_rpc_method::<internal::GetSubmissionStatus>()?
+        .register_rpc_method::<internal::StartProfiler>()?
+        .register_rpc_method::<internal::StopProfiler>()?
+        .register_rpc_method::<internal::GetProfilerStatus>()?
+        .register_rpc_method::<internal::GetTaskStatus>()?
         .register_rpc_method::<internal::GetSequencingInfos>()?
         .register_rpc_method::<internal::GetSequencingInfo>()?
         .register_rpc_method::<internal::SetMaxGasLimit>()?
+        .register_rpc_method::<internal::SetLocalCluster>()?
+        .register_rpc_method::<internal::GetRollupMetadataFull>()?
+        .register_rpc_method::<internal::SetRollupBlockHeight>()?
+        .register_rpc_method::<internal::ResetTransactionOrder>()?
+        .register_rpc_method::<internal::ForceLeader>()?
+        .register_rpc_method::<external::Health>()?
+        .register_rpc_method::<external::Ready>()?
         .init(internal_rpc_url.clone())
         .await?;
 
@@ -273,7 +317,12 @@ async fn initialize_cluster_rpc_server(context: AppState) -> Result<(), Error> {
         .register_rpc_method::<cluster::FinalizeBlock>()?
         .register_rpc_method::<cluster::SyncBlock>()?
         .register_rpc_method::<cluster::SyncMaxGasLimit>()?
+        .register_rpc_method::<cluster::SyncBlockCommitmentAggregationSize>()?
+        .register_rpc_method::<cluster::GetTransactionsRange>()?
+        .register_rpc_method::<cluster::GetBlockRange>()?
         .register_rpc_method::<external::GetRawTransactionList>()?
+        .register_rpc_method::<external::Health>()?
+        .register_rpc_method::<external::Ready>()?
         .init(cluster_rpc_url.clone())
         .await?;
 
@@ -301,14 +350,21 @@ async fn initialize_external_rpc_server(context: AppState) -> Result<(), Error>
         .register_rpc_method::<external::GetRawTransactionWithTransactionHash>()?
         .register_rpc_method::<external::GetRawTransactionWithOrderCommitment>()?
         .register_rpc_method::<external::GetOrderCommitment>()?
+        .register_rpc_method::<external::GetOrderCommitmentEvidence>()?
+        .register_rpc_method::<external::VerifyOrderCommitment>()?
         .register_rpc_method::<external::SendRawTransaction>()?
+        .register_rpc_method::<external::SendRawTransactionBundle>()?
         .register_rpc_method::<external::GetRawTransactionList>()?
         .register_rpc_method::<external::GetEncryptedTransactionList>()?
         .register_rpc_method::<external::GetRollup>()?
         .register_rpc_method::<external::GetRollupMetadata>()?
         .register_rpc_method::<external::GetBlock>()?
+        .register_rpc_method::<external::GetBlockCommitmentProof>()?
         .register_rpc_method::<external::GetBlockHeight>()?
+        .register_rpc_method::<external::GetTransactionInclusionProof>()?
         .register_rpc_method::<external::GetVersion>()?
+        .register_rpc_method::<external::Health>()?
+        .register_rpc_method::<external::Ready>()?
         .init(external_rpc_url)
         .await?;
 
@@ -316,6 +372,18 @@ async fn initialize_external_rpc_server(context: AppState) -> Result<(), Error>
     Ok(())
 }
 
+fn initialize_monitoring_server(context: AppState) -> Result<(), Error> {
+    let monitoring_url = anywhere(&context.config().monitoring_port()?);
+
+    tokio::spawn(async move {
+        if let Err(error) = serve_monitoring(context, monitoring_url).await {
+            tracing::error!("The monitoring server terminated: {:?}", error);
+        }
+    });
+
+    Ok(())
+}
+
 pub fn anywhere(port: &str) -> String {
     format!("0.0.0.0:{}", port)
 }
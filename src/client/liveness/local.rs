@@ -1 +1,445 @@
+use std::{collections::BTreeMap, path::Path, str::FromStr, sync::Arc};
 
+use radius_sdk::signature::{Address, PrivateKeySigner};
+use tokio::time::{sleep, Duration};
+
+use super::seeder::SequencerRpcInfo;
+use crate::{error::Error, state::AppState, task::RetryPolicy, types::*};
+
+/// Interval at which the local cluster configuration file is reloaded.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Liveness client that reads cluster membership and rollups from a local
+/// file or from the `set_local_cluster` internal RPC instead of the liveness
+/// contract. Meant for running multi-node clusters without an L1 node.
+pub struct LivenessClient {
+    inner: Arc<LivenessClientInner>,
+}
+
+struct LivenessClientInner {
+    platform: Platform,
+    service_provider: ServiceProvider,
+    liveness_info: LivenessLocal,
+}
+
+impl Clone for LivenessClient {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl LivenessClient {
+    pub fn new(
+        platform: Platform,
+        service_provider: ServiceProvider,
+        liveness_info: LivenessLocal,
+    ) -> Self {
+        Self {
+            inner: Arc::new(LivenessClientInner {
+                platform,
+                service_provider,
+                liveness_info,
+            }),
+        }
+    }
+
+    pub fn initialize(
+        context: AppState,
+        platform: Platform,
+        service_provider: ServiceProvider,
+        liveness_info: LivenessLocal,
+    ) {
+        let supervisor = context.supervisor().clone();
+        let name = format!("local_liveness:{:?}:{:?}", platform, service_provider);
+
+        supervisor.spawn(name, RetryPolicy::forever(), move || {
+            Self::run_sync_loop(
+                context.clone(),
+                platform,
+                service_provider,
+                liveness_info.clone(),
+            )
+        });
+    }
+
+    async fn run_sync_loop(
+        context: AppState,
+        platform: Platform,
+        service_provider: ServiceProvider,
+        liveness_info: LivenessLocal,
+    ) -> Result<(), Error> {
+        let signing_key = &context.config().signing_key;
+        let signer =
+            PrivateKeySigner::from_str(platform.into(), signing_key).map_err(Error::Signature)?;
+
+        context
+            .add_signer(platform, signer)
+            .await
+            .map_err(Error::CachedKvStore)?;
+
+        let liveness_client = Self::new(platform, service_provider, liveness_info);
+
+        context
+            .add_liveness_client(platform, service_provider, liveness_client.clone())
+            .await
+            .map_err(Error::CachedKvStore)?;
+
+        tracing::info!(
+            "Initializing the local liveness client for {:?}, {:?}..",
+            platform,
+            service_provider
+        );
+
+        loop {
+            let result = liveness_client.sync(context.clone()).await;
+            if let Err(error) = result.as_ref() {
+                tracing::error!(
+                    "Failed to sync the local clusters for {:?}, {:?} - {:?}",
+                    platform,
+                    service_provider,
+                    error
+                );
+            }
+            context.supervisor().report(
+                &format!("local_liveness_sync:{:?}:{:?}", platform, service_provider),
+                &result,
+            );
+
+            if liveness_client.cluster_config_path().is_none() {
+                return Ok(());
+            }
+
+            sleep(RELOAD_INTERVAL).await;
+        }
+    }
+
+    /// Loads the configuration file (if any) and rebuilds every registered
+    /// cluster at its latest block height.
+    pub async fn sync(&self, context: AppState) -> Result<(), Error> {
+        if let Some(path) = self.cluster_config_path() {
+            let local_cluster_config = load_local_cluster_config(path)?;
+
+            for local_cluster_info in local_cluster_config.cluster_list.iter() {
+                self.add_local_cluster_info(local_cluster_info)?;
+            }
+        }
+
+        let cluster_id_list = ClusterIdList::get_or(
+            self.platform(),
+            self.service_provider(),
+            ClusterIdList::default,
+        )
+        .map_err(Error::Database)?;
+
+        for cluster_id in cluster_id_list.iter() {
+            let latest_cluster_block_height = LatestClusterBlockHeight::get_or(
+                self.platform(),
+                self.service_provider(),
+                cluster_id,
+                LatestClusterBlockHeight::default,
+            )
+            .map_err(Error::Database)?;
+
+            if let Err(error) = initialize_new_cluster(
+                context.clone(),
+                self,
+                cluster_id,
+                latest_cluster_block_height.get_block_height(),
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Skipping the local cluster - cluster_id: {:?} / error: {:?}",
+                    cluster_id,
+                    error
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stores the cluster description and registers the cluster ID.
+    pub fn add_local_cluster_info(
+        &self,
+        local_cluster_info: &LocalClusterInfo,
+    ) -> Result<(), Error> {
+        local_cluster_info
+            .put(
+                self.platform(),
+                self.service_provider(),
+                &local_cluster_info.cluster_id,
+            )
+            .map_err(Error::Database)?;
+
+        let mut cluster_id_list = ClusterIdList::get_mut_or(
+            self.platform(),
+            self.service_provider(),
+            ClusterIdList::default,
+        )
+        .map_err(Error::Database)?;
+        cluster_id_list.insert(&local_cluster_info.cluster_id);
+        cluster_id_list.update().map_err(Error::Database)?;
+
+        Ok(())
+    }
+
+    pub fn platform(&self) -> Platform {
+        self.inner.platform
+    }
+
+    pub fn service_provider(&self) -> ServiceProvider {
+        self.inner.service_provider
+    }
+
+    pub fn cluster_config_path(&self) -> Option<&Path> {
+        self.inner.liveness_info.cluster_config_path.as_deref()
+    }
+}
+
+pub fn load_local_cluster_config(path: impl AsRef<Path>) -> Result<LocalClusterConfig, Error> {
+    let path = path.as_ref();
+    let config_string =
+        std::fs::read_to_string(path).map_err(|error| Error::LivenessClient(error.into()))?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&config_string)
+            .map_err(|error| Error::LivenessClient(error.into())),
+        _others => {
+            toml::from_str(&config_string).map_err(|error| Error::LivenessClient(error.into()))
+        }
+    }
+}
+
+/// Writes the [`Cluster`] for `platform_block_height` from the stored
+/// [`LocalClusterInfo`] and creates or updates its rollups.
+pub async fn initialize_new_cluster(
+    context: AppState,
+    liveness_client: &LivenessClient,
+    cluster_id: &str,
+    platform_block_height: u64,
+) -> Result<(), Error> {
+    tracing::debug!(
+        "Initializing the local cluster - platform: {:?} / service provider: {:?} / cluster id: {:?} / platform_block_height: {:?}..",
+        liveness_client.platform(),
+        liveness_client.service_provider(),
+        cluster_id,
+        platform_block_height
+    );
+
+    let local_cluster_info = LocalClusterInfo::get(
+        liveness_client.platform(),
+        liveness_client.service_provider(),
+        cluster_id,
+    )
+    .map_err(|error| {
+        tracing::error!(
+            "Local cluster info not found - cluster_id: {:?} / error: {:?}",
+            cluster_id,
+            error
+        );
+        Error::ClusterNotFound
+    })?;
+
+    let platform = liveness_client.platform();
+
+    let sequencer_rpc_infos = local_cluster_info
+        .sequencer_list
+        .iter()
+        .map(|sequencer| {
+            Ok(SequencerRpcInfo {
+                address: address_from_str(platform, &sequencer.address)?,
+                external_rpc_url: sequencer.external_rpc_url.clone(),
+                cluster_rpc_url: sequencer.cluster_rpc_url.clone(),
+            })
+        })
+        .collect::<Result<Vec<SequencerRpcInfo>, Error>>()?
+        .into_iter()
+        .enumerate()
+        .collect::<BTreeMap<usize, SequencerRpcInfo>>();
+
+    for rollup_info in local_cluster_info.rollup_list.iter() {
+        update_or_create_rollup(
+            platform,
+            liveness_client.service_provider(),
+            cluster_id,
+            rollup_info,
+        )?;
+    }
+
+    let sequencer_address = context
+        .get_signer(platform)
+        .await
+        .map_err(Error::CachedKvStore)?
+        .address()
+        .clone();
+
+    let rollup_id_list = local_cluster_info
+        .rollup_list
+        .iter()
+        .map(|rollup_info| rollup_info.rollup_id.clone())
+        .collect();
+
+    // Local clusters are not bound to a chain, so there is no block margin to
+    // keep.
+    let cluster = Cluster::new(sequencer_rpc_infos, rollup_id_list, sequencer_address, 0);
+    cluster
+        .put(
+            platform,
+            liveness_client.service_provider(),
+            cluster_id,
+            platform_block_height,
+        )
+        .map_err(Error::Database)?;
+    context
+        .gossip()
+        .update_cluster_peer_list(
+            platform,
+            liveness_client.service_provider(),
+            cluster_id,
+            cluster.get_others_cluster_rpc_url_list(),
+        )
+        .await;
+
+    let mut latest_cluster_block_height = LatestClusterBlockHeight::get_mut_or(
+        platform,
+        liveness_client.service_provider(),
+        cluster_id,
+        LatestClusterBlockHeight::default,
+    )
+    .map_err(Error::Database)?;
+
+    if latest_cluster_block_height.get_block_height() < platform_block_height {
+        latest_cluster_block_height.set_block_height(platform_block_height);
+    }
+    latest_cluster_block_height
+        .update()
+        .map_err(Error::Database)?;
+
+    Ok(())
+}
+
+/// Checks that `next_block_creator_address` follows the leader rotation of a
+/// local cluster. Clusters without a local description or with the rotation
+/// disabled accept any leader.
+pub fn check_leader_rotation(
+    rollup: &Rollup,
+    rollup_block_height: u64,
+    next_block_creator_address: &Address,
+) -> Result<(), Error> {
+    let local_cluster_info =
+        match LocalClusterInfo::get(rollup.platform, rollup.service_provider, &rollup.cluster_id) {
+            Ok(local_cluster_info) => local_cluster_info,
+            Err(_) => return Ok(()),
+        };
+
+    if !local_cluster_info.leader_rotation {
+        return Ok(());
+    }
+
+    let expected_leader = local_cluster_info
+        .next_leader(rollup_block_height)
+        .ok_or(Error::EmptyLeader)?;
+    let expected_leader_address = address_from_str(rollup.platform, &expected_leader.address)?;
+
+    if expected_leader_address != *next_block_creator_address {
+        tracing::warn!(
+            "Leader rotation mismatch - rollup_id: {:?} / expected: {:?} / received: {:?}",
+            rollup.rollup_id,
+            expected_leader_address.as_hex_string(),
+            next_block_creator_address.as_hex_string()
+        );
+        return Err(Error::InvalidLeader);
+    }
+
+    Ok(())
+}
+
+fn update_or_create_rollup(
+    platform: Platform,
+    service_provider: ServiceProvider,
+    cluster_id: &str,
+    rollup_info: &LocalRollupInfo,
+) -> Result<(), Error> {
+    // PVDE transactions cannot be verified or decrypted by the sequencer.
+    if matches!(
+        EncryptedTransactionType::from(rollup_info.encrypted_transaction_type.clone()),
+        EncryptedTransactionType::Pvde
+    ) {
+        return Err(Error::UnsupportedEncryptedMempool);
+    }
+
+    let executor_address_list = rollup_info
+        .executor_address_list
+        .iter()
+        .map(|address| address_from_str(platform, address))
+        .collect::<Result<Vec<Address>, Error>>()?;
+
+    match Rollup::get_mut(&rollup_info.rollup_id) {
+        Ok(mut rollup) => {
+            rollup.set_executor_address_list(executor_address_list);
+
+            // The rollup is back after its cluster was deregistered.
+            if !rollup.is_active {
+                rollup.set_is_active(true);
+
+                let mut rollup_id_list =
+                    RollupIdList::get_mut_or(RollupIdList::default).map_err(Error::Database)?;
+                rollup_id_list.insert(&rollup.rollup_id);
+                rollup_id_list.update().map_err(Error::Database)?;
+            }
+            rollup.update().map_err(Error::Database)?;
+
+            Ok(())
+        }
+        Err(error) => {
+            if !error.is_none_type() {
+                return Err(Error::Database(error));
+            }
+
+            let validation_service_provider =
+                ValidationServiceProvider::from_str(&rollup_info.validation_service_provider)?;
+            let rollup_validation_info = RollupValidationInfo::new(
+                rollup_info.validation_platform,
+                validation_service_provider,
+                address_from_str(platform, &rollup_info.validation_service_manager_address)?,
+            );
+
+            let rollup = Rollup::new(
+                rollup_info.rollup_id.clone(),
+                RollupType::from_str(&rollup_info.rollup_type)?,
+                EncryptedTransactionType::from(rollup_info.encrypted_transaction_type.clone()),
+                address_from_str(platform, &rollup_info.owner)?,
+                rollup_validation_info,
+                OrderCommitmentType::from_str(&rollup_info.order_commitment_type)?,
+                executor_address_list,
+                cluster_id.to_owned(),
+                platform,
+                service_provider,
+            );
+
+            let mut rollup_id_list =
+                RollupIdList::get_mut_or(RollupIdList::default).map_err(Error::Database)?;
+            rollup_id_list.insert(&rollup.rollup_id);
+            rollup_id_list.update().map_err(Error::Database)?;
+
+            let rollup_metadata = RollupMetadata {
+                cluster_id: cluster_id.to_owned(),
+                ..Default::default()
+            };
+            rollup_metadata
+                .put(&rollup.rollup_id)
+                .map_err(Error::Database)?;
+
+            rollup.put(&rollup.rollup_id).map_err(Error::Database)?;
+
+            Ok(())
+        }
+    }
+}
+
+fn address_from_str(platform: Platform, address: &str) -> Result<Address, Error> {
+    Address::from_str(platform.into(), address).map_err(Error::Signature)
+}
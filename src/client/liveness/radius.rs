@@ -15,7 +15,15 @@ use radius_sdk::{
 use tokio::time::{sleep, Duration};
 
 use super::seeder::SequencerRpcInfo;
-use crate::{client::liveness::seeder::SeederClient, error::Error, state::AppState, types::*};
+use crate::{
+    client::liveness::seeder::SeederClient,
+    error::Error,
+    metrics::{LIVENESS_EVENT_LAG_BLOCKS, LIVENESS_LAST_EVENT_TIMESTAMP_SECONDS},
+    state::AppState,
+    task::RetryPolicy,
+    types::*,
+    util::current_timestamp,
+};
 
 pub struct LivenessClient {
     inner: Arc<LivenessClientInner>,
@@ -75,100 +83,99 @@ impl LivenessClient {
         service_provider: ServiceProvider,
         liveness_info: LivenessRadius,
     ) {
-        let handle = tokio::spawn({
-            let context_clone = context.clone();
-            let liveness_info_clone = liveness_info.clone();
+        let supervisor = context.supervisor().clone();
+        let name = format!("liveness_listener:{:?}:{:?}", platform, service_provider);
 
-            async move {
-                let signing_key = &context_clone.config().signing_key;
-                let signer = PrivateKeySigner::from_str(platform.into(), signing_key)
-                    .expect("Invalid signing key");
+        supervisor.spawn(name, RetryPolicy::forever(), move || {
+            Self::run_event_listener(
+                context.clone(),
+                platform,
+                service_provider,
+                liveness_info.clone(),
+            )
+        });
+    }
 
-                context_clone
-                    .add_signer(platform, signer)
-                    .await
-                    .expect("Failed to add signer");
+    /// Catches up the registered clusters and listens to the liveness events
+    /// until the subscription ends. Returns an error when the listener
+    /// should be restarted.
+    async fn run_event_listener(
+        context: AppState,
+        platform: Platform,
+        service_provider: ServiceProvider,
+        liveness_info: LivenessRadius,
+    ) -> Result<(), Error> {
+        let signing_key = &context.config().signing_key;
+        let signer =
+            PrivateKeySigner::from_str(platform.into(), signing_key).map_err(Error::Signature)?;
+
+        context
+            .add_signer(platform, signer)
+            .await
+            .map_err(Error::CachedKvStore)?;
+
+        let liveness_client = Self::new(
+            platform,
+            service_provider,
+            liveness_info,
+            signing_key,
+            context.seeder_client().clone(),
+        )?;
 
-                let liveness_client = Self::new(
-                    platform,
-                    service_provider,
-                    liveness_info_clone,
-                    signing_key,
-                    context_clone.seeder_client().clone(),
-                )
-                .expect("Failed to create liveness client");
-
-                let current_block_height = liveness_client
-                    .publisher()
-                    .get_block_number()
-                    .await
-                    .expect("Failed to get block number");
-
-                let block_margin: u64 = liveness_client
-                    .publisher()
-                    .get_block_margin()
-                    .await
-                    .expect("Failed to get block margin")
-                    .try_into()
-                    .expect("Failed to convert block margin");
-
-                let cluster_id_list = ClusterIdList::get_or(
-                    liveness_client.platform(),
-                    liveness_client.service_provider(),
-                    ClusterIdList::default,
-                )
-                .expect("Failed to get cluster id list");
+        let current_block_height = liveness_client
+            .publisher()
+            .get_block_number()
+            .await
+            .map_err(|error| Error::LivenessClient(error.into()))?;
 
-                for cluster_id in cluster_id_list.iter() {
-                    initialize_new_cluster(
-                        context_clone.clone(),
-                        &liveness_client,
-                        cluster_id,
-                        current_block_height,
-                        block_margin,
-                    )
-                    .await
-                    .unwrap();
-                }
+        let block_margin = liveness_client.block_margin().await?;
 
-                context_clone
-                    .add_liveness_client(platform, service_provider, liveness_client.clone())
-                    .await
-                    .expect("Failed to add liveness client");
+        let cluster_id_list = ClusterIdList::get_or(
+            liveness_client.platform(),
+            liveness_client.service_provider(),
+            ClusterIdList::default,
+        )
+        .map_err(Error::Database)?;
 
-                tracing::info!(
-                    "Initializing the liveness event listener for {:?}, {:?}..",
-                    platform,
-                    service_provider
-                );
+        for cluster_id in cluster_id_list.iter() {
+            initialize_new_cluster(
+                context.clone(),
+                &liveness_client,
+                cluster_id,
+                current_block_height,
+                block_margin,
+            )
+            .await
+            .map_err(Error::InitializeNewCluster)?;
+        }
 
-                let result = liveness_client
-                    .subscriber()
-                    .initialize_event_handler(callback, (context_clone, liveness_client.clone()))
-                    .await;
-
-                if let Err(error) = result {
-                    tracing::error!(
-                        "Failed to initialize the liveness event listener for {:?}, {:?} - {:?}",
-                        platform,
-                        service_provider,
-                        error
-                    );
-                }
-            }
-        });
+        context
+            .add_liveness_client(platform, service_provider, liveness_client.clone())
+            .await
+            .map_err(Error::CachedKvStore)?;
 
-        tokio::spawn(async move {
-            if handle.await.is_err() {
-                tracing::warn!(
-                    "Reconnecting the liveness event listener for {:?}, {:?}..",
-                    platform,
-                    service_provider
-                );
-                sleep(Duration::from_secs(5)).await;
-                Self::initialize(context, platform, service_provider, liveness_info);
-            }
-        });
+        tracing::info!(
+            "Initializing the liveness event listener for {:?}, {:?}..",
+            platform,
+            service_provider
+        );
+
+        liveness_client
+            .subscriber()
+            .initialize_event_handler(callback, (context, liveness_client.clone()))
+            .await
+            .map_err(|error| Error::LivenessClient(error.into()))?;
+
+        Ok(())
+    }
+
+    pub async fn block_margin(&self) -> Result<u64, Error> {
+        self.publisher()
+            .get_block_margin()
+            .await
+            .map_err(|error| Error::LivenessClient(error.into()))?
+            .try_into()
+            .map_err(|_| Error::Convert)
     }
 }
 
@@ -179,6 +186,31 @@ async fn callback(events: Events, (app_state, liveness_client): (AppState, Liven
         liveness_client.service_provider()
     );
 
+    let result = handle_event(events, &app_state, &liveness_client).await;
+    if let Err(error) = result.as_ref() {
+        tracing::error!(
+            "Failed to handle the liveness event - platform: {:?} / service provider: {:?} / error: {:?}",
+            liveness_client.platform(),
+            liveness_client.service_provider(),
+            error
+        );
+    }
+
+    app_state.supervisor().report(
+        &format!(
+            "liveness_callback:{:?}:{:?}",
+            liveness_client.platform(),
+            liveness_client.service_provider()
+        ),
+        &result,
+    );
+}
+
+async fn handle_event(
+    events: Events,
+    app_state: &AppState,
+    liveness_client: &LivenessClient,
+) -> Result<(), Error> {
     match events {
         Events::Block(block) => {
             tracing::debug!(
@@ -193,30 +225,58 @@ async fn callback(events: Events, (app_state, liveness_client): (AppState, Liven
                 liveness_client.service_provider(),
                 ClusterIdList::default,
             )
-            .expect("Failed to get cluster id list");
-
-            let block_margin = liveness_client
-                .publisher()
-                .get_block_margin()
-                .await
-                .expect("Failed to get block margin")
-                .try_into()
-                .expect("Failed to convert block margin");
+            .map_err(Error::Database)?;
+
+            let block_margin = liveness_client.block_margin().await?;
+
+            let platform = format!("{:?}", liveness_client.platform());
+            let service_provider = format!("{:?}", liveness_client.service_provider());
+            app_state.metrics().set_gauge(
+                LIVENESS_LAST_EVENT_TIMESTAMP_SECONDS,
+                &[
+                    ("platform", platform.as_str()),
+                    ("service_provider", service_provider.as_str()),
+                ],
+                current_timestamp() as f64,
+            );
 
             for cluster_id in cluster_id_list.iter() {
+                // Lag between the event and the cluster height processed so
+                // far, before this event is applied.
+                if let Ok(latest_cluster_block_height) = LatestClusterBlockHeight::get(
+                    liveness_client.platform(),
+                    liveness_client.service_provider(),
+                    cluster_id,
+                ) {
+                    app_state.metrics().set_gauge(
+                        LIVENESS_EVENT_LAG_BLOCKS,
+                        &[
+                            ("platform", platform.as_str()),
+                            ("service_provider", service_provider.as_str()),
+                            ("cluster_id", cluster_id.as_str()),
+                        ],
+                        block
+                            .number
+                            .saturating_sub(latest_cluster_block_height.get_block_height())
+                            as f64,
+                    );
+                }
+
                 initialize_new_cluster(
                     app_state.clone(),
-                    &liveness_client,
+                    liveness_client,
                     cluster_id,
                     block.number,
                     block_margin,
                 )
                 .await
-                .expect("Failed to initialize new cluster");
+                .map_err(Error::InitializeNewCluster)?;
             }
         }
         _others => {}
     }
+
+    Ok(())
 }
 
 pub async fn initialize_new_cluster(
@@ -241,7 +301,8 @@ pub async fn initialize_new_cluster(
         LatestClusterBlockHeight::default,
     )?;
 
-    let block_diff = platform_block_height - latest_cluster_block_height.get_block_height();
+    let block_diff =
+        platform_block_height.saturating_sub(latest_cluster_block_height.get_block_height());
     let block_diff = std::cmp::min(block_diff, block_margin);
 
     for offset in 0..block_diff {
@@ -279,6 +340,19 @@ pub async fn initialize_new_cluster(
                         block_height,
                     )?;
 
+                    // The latest cluster decides which peers stay.
+                    if offset == 0 {
+                        context
+                            .gossip()
+                            .update_cluster_peer_list(
+                                liveness_client.platform(),
+                                liveness_client.service_provider(),
+                                cluster_id,
+                                cluster.get_others_cluster_rpc_url_list(),
+                            )
+                            .await;
+                    }
+
                     tracing::debug!(
                         "Sync the cluster - platform: {:?} / service provider: {:?} / cluster id: {:?} / block height: {:?} - Done",
                         liveness_client.platform(),
@@ -382,13 +456,8 @@ async fn get_rollup_id_list(
         .await?;
 
     for rollup in rollup_list.iter() {
-        let validation_service_provider = ValidationServiceProvider::from_str(
-            &rollup.validationInfo.serviceProvider,
-        )
-        .expect(&format!(
-            "Unsupported validation service provider: {:?}",
-            &rollup.validationInfo.serviceProvider
-        ));
+        let validation_service_provider =
+            ValidationServiceProvider::from_str(&rollup.validationInfo.serviceProvider)?;
 
         update_or_create_rollup(
             liveness_client.platform(),
@@ -416,8 +485,17 @@ async fn update_or_create_rollup(
                 .executors
                 .iter()
                 .map(|addr| address_from_str(platform, addr.to_string()))
-                .collect();
+                .collect::<Result<Vec<Address>, Error>>()?;
             rollup.set_executor_address_list(executor_address_list);
+
+            // The rollup is back after its cluster was deregistered.
+            if !rollup.is_active {
+                rollup.set_is_active(true);
+
+                let mut rollup_id_list = RollupIdList::get_mut_or(RollupIdList::default)?;
+                rollup_id_list.insert(&rollup.rollup_id);
+                rollup_id_list.update()?;
+            }
             rollup.update()?;
 
             Ok(())
@@ -430,7 +508,7 @@ async fn update_or_create_rollup(
                         .validationInfo
                         .validationServiceManager
                         .to_string(),
-                );
+                )?;
 
                 let rollup_validation_info = RollupValidationInfo::new(
                     platform,
@@ -442,26 +520,17 @@ async fn update_or_create_rollup(
                     .executors
                     .iter()
                     .map(|addr| address_from_str(platform, addr.to_string()))
-                    .collect();
+                    .collect::<Result<Vec<Address>, Error>>()?;
 
-                let rollup_type = RollupType::from_str(&rollup_info.rollupType).expect(&format!(
-                    "Unsupported rollup type: {:?}",
-                    &rollup_info.rollupType
-                ));
-
-                let order_commitment_type = OrderCommitmentType::from_str(
-                    &rollup_info.orderCommitmentType,
-                )
-                .expect(&format!(
-                    "Unsupported order commitment type: {:?}",
-                    &rollup_info.orderCommitmentType
-                ));
+                let rollup_type = RollupType::from_str(&rollup_info.rollupType)?;
+                let order_commitment_type =
+                    OrderCommitmentType::from_str(&rollup_info.orderCommitmentType)?;
 
                 let rollup = Rollup::new(
                     rollup_info.id.clone(),
                     rollup_type,
                     EncryptedTransactionType::Skde,
-                    address_from_str(platform, rollup_info.owner.to_string()),
+                    address_from_str(platform, rollup_info.owner.to_string())?,
                     rollup_validation_info,
                     order_commitment_type,
                     executor_address_list,
@@ -488,8 +557,8 @@ async fn update_or_create_rollup(
     }
 }
 
-fn address_from_str(platform: Platform, address: String) -> Address {
-    Address::from_str(platform.into(), &address).expect("Invalid address")
+fn address_from_str(platform: Platform, address: String) -> Result<Address, Error> {
+    Address::from_str(platform.into(), &address).map_err(Error::Signature)
 }
 
 impl LivenessClient {
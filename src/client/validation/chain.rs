@@ -0,0 +1,147 @@
+use reqwest::Client;
+use serde_json::{json, Value};
+use tokio::time::Duration;
+
+use crate::error::Error;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Block a nonce is read at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockTag {
+    /// Counts the transactions mined so far.
+    Latest,
+    /// Also counts the transactions waiting in the mempool of the node.
+    Pending,
+}
+
+impl BlockTag {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Latest => "latest",
+            Self::Pending => "pending",
+        }
+    }
+}
+
+/// Reads the state of the operator transactions on the chain of a validation
+/// service, which the publishers do not expose.
+pub struct ChainClient {
+    rpc_url: String,
+    client: Client,
+}
+
+impl ChainClient {
+    pub fn new(rpc_url: impl AsRef<str>) -> Self {
+        Self {
+            rpc_url: rpc_url.as_ref().to_owned(),
+            client: Client::new(),
+        }
+    }
+
+    /// Returns the nonce of the next transaction from `address`.
+    pub async fn get_transaction_count(
+        &self,
+        address: &str,
+        block_tag: BlockTag,
+    ) -> Result<u64, Error> {
+        let transaction_count = self
+            .request(
+                "eth_getTransactionCount",
+                json!([address, block_tag.as_str()]),
+            )
+            .await?;
+
+        parse_quantity(&transaction_count)
+    }
+
+    /// Returns whether the transaction succeeded, or `None` while it is not
+    /// mined.
+    pub async fn get_transaction_status(
+        &self,
+        transaction_hash: &str,
+    ) -> Result<Option<bool>, Error> {
+        let receipt = self
+            .request("eth_getTransactionReceipt", json!([transaction_hash]))
+            .await?;
+
+        parse_receipt_status(&receipt)
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, Error> {
+        let mut response: Value = self
+            .client
+            .post(&self.rpc_url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+                "id": 0,
+            }))
+            .send()
+            .await
+            .map_err(|error| Error::ValidationClient(error.into()))?
+            .json()
+            .await
+            .map_err(|error| Error::ValidationClient(error.into()))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(Error::ValidationClient(
+                format!("{} failed: {}", method, error).into(),
+            ));
+        }
+
+        Ok(response
+            .get_mut("result")
+            .map(Value::take)
+            .unwrap_or(Value::Null))
+    }
+}
+
+fn parse_quantity(quantity: &Value) -> Result<u64, Error> {
+    quantity
+        .as_str()
+        .and_then(|quantity| quantity.strip_prefix("0x"))
+        .and_then(|quantity| u64::from_str_radix(quantity, 16).ok())
+        .ok_or_else(|| Error::ValidationClient(format!("Invalid quantity: {}", quantity).into()))
+}
+
+fn parse_receipt_status(receipt: &Value) -> Result<Option<bool>, Error> {
+    if receipt.is_null() {
+        return Ok(None);
+    }
+
+    let status = receipt.get("status").ok_or_else(|| {
+        Error::ValidationClient(format!("Receipt without a status: {}", receipt).into())
+    })?;
+
+    Ok(Some(parse_quantity(status)? == 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_quantities() {
+        assert_eq!(parse_quantity(&json!("0x0")).unwrap(), 0);
+        assert_eq!(parse_quantity(&json!("0x1a")).unwrap(), 26);
+        assert!(parse_quantity(&json!("1a")).is_err());
+        assert!(parse_quantity(&json!(26)).is_err());
+    }
+
+    #[test]
+    fn reads_the_status_of_a_receipt() {
+        assert_eq!(parse_receipt_status(&Value::Null).unwrap(), None);
+        assert_eq!(
+            parse_receipt_status(&json!({ "status": "0x1" })).unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            parse_receipt_status(&json!({ "status": "0x0" })).unwrap(),
+            Some(false)
+        );
+        assert!(parse_receipt_status(&json!({})).is_err());
+    }
+}
@@ -1,5 +1,11 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
+use ethers_core::{
+    abi::{self, Token},
+    k256::ecdsa::SigningKey,
+    types::U256,
+    utils::{hash_message, keccak256},
+};
 use radius_sdk::validation::eigenlayer::{
     publisher::Publisher,
     subscriber::Subscriber,
@@ -7,6 +13,7 @@ use radius_sdk::validation::eigenlayer::{
 };
 use tokio::time::{sleep, Duration};
 
+use super::validate_block_commitment;
 use crate::{error::Error, state::AppState, types::*};
 
 pub struct ValidationClient {
@@ -18,6 +25,7 @@ struct ValidationClientInner {
     validation_service_provider: ValidationServiceProvider,
     publisher: Publisher,
     subscriber: Subscriber,
+    operator_signing_key: SigningKey,
 }
 
 impl Clone for ValidationClient {
@@ -61,6 +69,13 @@ impl ValidationClient {
         )
         .map_err(|error| Error::ValidationClient(error.into()))?;
 
+        let operator_signing_key = const_hex::decode(signing_key.as_ref())
+            .map_err(|error| Error::ValidationClient(error.into()))
+            .and_then(|signing_key| {
+                SigningKey::from_slice(&signing_key)
+                    .map_err(|error| Error::ValidationClient(error.into()))
+            })?;
+
         let subscriber = Subscriber::new(
             eigen_layer_validation_info.validation_websocket_url,
             eigen_layer_validation_info.avs_contract_address,
@@ -72,6 +87,7 @@ impl ValidationClient {
             validation_service_provider,
             publisher,
             subscriber,
+            operator_signing_key,
         };
 
         Ok(Self {
@@ -79,6 +95,10 @@ impl ValidationClient {
         })
     }
 
+    fn operator_signing_key(&self) -> &SigningKey {
+        &self.inner.operator_signing_key
+    }
+
     pub fn initialize(
         context: AppState,
         platform: Platform,
@@ -136,26 +156,260 @@ impl ValidationClient {
     }
 }
 
+/// The signed message is `keccak256(abi.encode(task))` prefixed as an Ethereum
+/// signed message, and the signature is the 65-byte `r || s || v` form
+/// expected by `ECDSA.recover`.
+fn sign_task(
+    signing_key: &SigningKey,
+    task: &IValidationServiceManager::Task,
+) -> Result<Bytes, Error> {
+    let message_hash = keccak256(abi::encode(&[Token::Tuple(vec![
+        Token::Bytes(task.commitment.to_vec()),
+        Token::Uint(U256::from(task.blockNumber)),
+        Token::String(task.rollupId.clone()),
+        Token::String(task.clusterId.clone()),
+        Token::Uint(U256::from(task.taskCreatedBlock)),
+    ])]));
+    let digest = hash_message(message_hash);
+
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(digest.as_bytes())
+        .map_err(|error| Error::ValidationClient(error.into()))?;
+
+    let mut signature = signature.to_bytes().to_vec();
+    signature.push(recovery_id.to_byte() + 27);
+
+    Ok(Bytes::from(signature))
+}
+
+/// Answers `task` with `block_commitment`, the commitment rebuilt locally, so
+/// that a mismatch with the submitted commitment is disputed on chain. The
+/// response is signed as the operator and handed to `publish`, which submits
+/// it with `respond_to_task`.
+async fn respond_to_task<F, Fut>(
+    signing_key: &SigningKey,
+    task: IValidationServiceManager::Task,
+    block_commitment: &BlockCommitment,
+    publish: F,
+) -> Result<Fut::Output, Error>
+where
+    F: FnOnce(IValidationServiceManager::Task, Bytes) -> Fut,
+    Fut: Future,
+{
+    let block_commitment = block_commitment
+        .as_bytes()
+        .map_err(|error| Error::ValidationClient(error.into()))?;
+    let task = IValidationServiceManager::Task {
+        commitment: Bytes::from(block_commitment),
+        ..task
+    };
+    let signature = sign_task(signing_key, &task)?;
+
+    Ok(publish(task, signature).await)
+}
+
 async fn callback(event: Avs::NewTaskCreated, context: ValidationClient) {
-    let rollup = Rollup::get(&event.rollupId).ok();
-    if let Some(rollup) = rollup {
-        let block = Block::get(&rollup.rollup_id, event.task.blockNumber).unwrap();
-
-        if block.block_creator_address != context.publisher().address() {
-            let task = IValidationServiceManager::Task {
-                commitment: Bytes::from_iter(&[0u8; 32]),
-                blockNumber: 0,
-                rollupId: rollup.rollup_id,
-                clusterId: rollup.cluster_id,
-                taskCreatedBlock: event.taskCreatedBlock,
-            };
-
-            let transaction_hash = context
+    let rollup = match Rollup::get(&event.rollupId) {
+        Ok(rollup) => rollup,
+        Err(_) => return,
+    };
+    let rollup_block_height = event.task.blockNumber;
+
+    let (block, expected_block_commitment, is_valid) = match validate_block_commitment(
+        &rollup,
+        rollup_block_height,
+        event.task.commitment.as_ref(),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(error) => {
+            tracing::warn!(
+                "[EigenLayer] Skipping the task - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                rollup.rollup_id,
+                rollup_block_height,
+                error
+            );
+            return;
+        }
+    };
+
+    if block.block_creator_address == context.publisher().address() {
+        return;
+    }
+
+    tracing::info!(
+        "[EigenLayer] NewTaskCreated - rollup_id: {:?} / rollup_block_height: {:?} / is_valid: {:?}",
+        rollup.rollup_id,
+        rollup_block_height,
+        is_valid
+    );
+
+    // Respond with the commitment rebuilt locally, or the aggregate rebuilt
+    // locally.
+    let task = IValidationServiceManager::Task {
+        rollupId: rollup.rollup_id,
+        clusterId: rollup.cluster_id,
+        taskCreatedBlock: event.taskCreatedBlock,
+        ..event.task
+    };
+    let task_index = event.taskIndex;
+
+    match respond_to_task(
+        context.operator_signing_key(),
+        task,
+        &expected_block_commitment,
+        |task, signature| {
+            context
                 .publisher()
-                .respond_to_task(task, event.taskIndex, Bytes::from_iter(&[0_u8; 64]))
-                .await
-                .unwrap();
-            tracing::info!("[EigenLayer] respond_to_task: {:?}", transaction_hash);
+                .respond_to_task(task, task_index, signature)
+        },
+    )
+    .await
+    {
+        Ok(Ok(transaction_hash)) => {
+            tracing::info!("[EigenLayer] respond_to_task: {:?}", transaction_hash)
+        }
+        Ok(Err(error)) => tracing::error!("[EigenLayer] respond_to_task: {:?}", error),
+        Err(error) => {
+            tracing::error!("[EigenLayer] Failed to sign the task response: {:?}", error)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ethers_core::{
+        k256::ecdsa::{RecoveryId, Signature, VerifyingKey},
+        types::{RecoveryMessage, Signature as EthSignature},
+        utils::secret_key_to_address,
+    };
+    use tokio::sync::Mutex;
+
+    use super::*;
+
+    /// Records the responses instead of submitting them.
+    #[derive(Default)]
+    struct MockPublisher {
+        response_list: Mutex<Vec<(IValidationServiceManager::Task, Bytes)>>,
+    }
+
+    impl MockPublisher {
+        async fn respond_to_task(&self, task: IValidationServiceManager::Task, signature: Bytes) {
+            self.response_list.lock().await.push((task, signature));
+        }
+    }
+
+    fn task() -> IValidationServiceManager::Task {
+        IValidationServiceManager::Task {
+            commitment: Bytes::from(vec![7; 32]),
+            blockNumber: 42,
+            rollupId: "rollup_id".to_owned(),
+            clusterId: "cluster_id".to_owned(),
+            taskCreatedBlock: 1_000,
+        }
+    }
+
+    fn abi_encoded_task(task: &IValidationServiceManager::Task) -> Vec<u8> {
+        abi::encode(&[Token::Tuple(vec![
+            Token::Bytes(task.commitment.to_vec()),
+            Token::Uint(U256::from(task.blockNumber)),
+            Token::String(task.rollupId.clone()),
+            Token::String(task.clusterId.clone()),
+            Token::Uint(U256::from(task.taskCreatedBlock)),
+        ])])
+    }
+
+    #[test]
+    fn recovers_the_operator_with_k256() {
+        let signing_key = SigningKey::from_slice(&[1; 32]).unwrap();
+        let task = task();
+
+        let signature = sign_task(&signing_key, &task).unwrap();
+        assert_eq!(signature.len(), 65);
+        assert!(signature[64] == 27 || signature[64] == 28);
+
+        // `ECDSA.recover(MessageHashUtils.toEthSignedMessageHash(keccak256(abi.
+        // encode(task))))`
+        let mut prefixed_message = b"\x19Ethereum Signed Message:\n32".to_vec();
+        prefixed_message.extend_from_slice(&keccak256(abi_encoded_task(&task)));
+        let digest = keccak256(prefixed_message);
+
+        let recovered_key = VerifyingKey::recover_from_prehash(
+            &digest,
+            &Signature::from_slice(&signature[..64]).unwrap(),
+            RecoveryId::from_byte(signature[64] - 27).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(&recovered_key, signing_key.verifying_key());
+    }
+
+    #[tokio::test]
+    async fn responds_with_the_local_commitment_signed_by_the_operator() {
+        let signing_key = SigningKey::from_slice(&[3; 32]).unwrap();
+        let publisher = MockPublisher::default();
+
+        // The block commitment rebuilt locally differs from the submitted one.
+        let merkle_tree = MerkleTree::new();
+        merkle_tree.add_data("0x01").await;
+        merkle_tree.add_data("0x02").await;
+        merkle_tree.finalize_tree().await;
+        let merkle_root = merkle_tree.get_merkle_root().await;
+        let block_commitment = BlockCommitment::from(merkle_root);
+
+        respond_to_task(
+            &signing_key,
+            task(),
+            &block_commitment,
+            |task, signature| publisher.respond_to_task(task, signature),
+        )
+        .await
+        .unwrap();
+
+        let response_list = publisher.response_list.lock().await;
+        assert_eq!(response_list.len(), 1);
+        let (submitted_task, signature) = &response_list[0];
+        assert_eq!(submitted_task.commitment.to_vec(), merkle_root.to_vec());
+        assert_eq!(submitted_task.blockNumber, 42);
+        assert_eq!(submitted_task.rollupId, "rollup_id");
+        assert_eq!(submitted_task.clusterId, "cluster_id");
+        assert_eq!(submitted_task.taskCreatedBlock, 1_000);
+
+        // The contract checks the response with
+        // `ECDSA.recover(MessageHashUtils.toEthSignedMessageHash(keccak256(abi.
+        // encode(task))), signature)`, i.e. an EIP-191 signature over the hash
+        // of the ABI-encoded task, which `RecoveryMessage::Data` prefixes the
+        // same way.
+        let recovered_address = EthSignature::try_from(signature.as_ref())
+            .unwrap()
+            .recover(RecoveryMessage::Data(
+                keccak256(abi_encoded_task(submitted_task)).to_vec(),
+            ))
+            .unwrap();
+        assert_eq!(recovered_address, secret_key_to_address(&signing_key));
+    }
+
+    #[test]
+    fn recovers_the_operator_address() {
+        let signing_key = SigningKey::from_slice(&[2; 32]).unwrap();
+        let task = task();
+
+        let signature =
+            EthSignature::try_from(sign_task(&signing_key, &task).unwrap().as_ref()).unwrap();
+        let recovered_address = signature
+            .recover(RecoveryMessage::Data(
+                keccak256(abi_encoded_task(&task)).to_vec(),
+            ))
+            .unwrap();
+        assert_eq!(recovered_address, secret_key_to_address(&signing_key));
+
+        let mut other_task = task.clone();
+        other_task.blockNumber += 1;
+        let recovered_address = signature
+            .recover(RecoveryMessage::Data(
+                keccak256(abi_encoded_task(&other_task)).to_vec(),
+            ))
+            .unwrap();
+        assert_ne!(recovered_address, secret_key_to_address(&signing_key));
+    }
+}
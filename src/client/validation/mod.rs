@@ -1,2 +1,171 @@
+pub mod chain;
 pub mod eigenlayer;
 pub mod symbiotic;
+
+use tokio::time::{sleep, Duration};
+
+use crate::{error::Error, task::aggregate_block_commitment, types::*};
+
+/// Number of times a validation task waits for the block to be built locally.
+const BLOCK_WAIT_ATTEMPTS: u32 = 10;
+const BLOCK_WAIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Compares the block commitment submitted on chain with the commitment of the
+/// block rebuilt on this node. Returns the local block, the commitment this
+/// node expects on chain at `rollup_block_height` and the result.
+///
+/// The submitted value is either the commitment of the block itself or, if
+/// the block closes an aggregated range of the rollup, the aggregate of the
+/// range. Followers build the aggregate over their own blocks on demand. The
+/// aggregate is accepted only if the [`BlockCommitmentProof`] of every block
+/// of the range proves its local commitment against it.
+///
+/// The task may be created before this node finishes building the block, so
+/// the block is awaited for a while before giving up. A mismatch is reported
+/// as an error log so that operators are alerted.
+pub async fn validate_block_commitment(
+    rollup: &Rollup,
+    rollup_block_height: u64,
+    block_commitment: &[u8],
+) -> Result<(Block, BlockCommitment, bool), Error> {
+    let rollup_id = rollup.rollup_id.as_str();
+    let block = wait_for_block(rollup_id, rollup_block_height).await?;
+
+    let local_block_commitment = block
+        .block_commitment
+        .as_bytes()
+        .map_err(|_| Error::Deserialize)?;
+    if local_block_commitment == block_commitment {
+        let expected_block_commitment = block.block_commitment.clone();
+        return Ok((block, expected_block_commitment, true));
+    }
+
+    let submitted_block_commitment =
+        BlockCommitment::from(const_hex::encode_prefixed(block_commitment));
+    let (expected_block_commitment, is_valid) =
+        match get_or_build_aggregate_proof_list(rollup, rollup_block_height).await? {
+            Some(block_commitment_proof_list) => {
+                let is_valid = verify_aggregate_block_commitment(
+                    rollup_id,
+                    &block_commitment_proof_list,
+                    &submitted_block_commitment,
+                )?;
+
+                (
+                    block_commitment_proof_list[0].aggregate_commitment.clone(),
+                    is_valid,
+                )
+            }
+            None => (block.block_commitment.clone(), false),
+        };
+
+    if !is_valid {
+        tracing::error!(
+            "Block commitment mismatch - rollup_id: {:?} / rollup_block_height: {:?} / on-chain: {:?} / local: {:?}",
+            rollup_id,
+            rollup_block_height,
+            submitted_block_commitment.as_hex_string(),
+            expected_block_commitment.as_hex_string()
+        );
+    }
+
+    Ok((block, expected_block_commitment, is_valid))
+}
+
+async fn wait_for_block(rollup_id: &str, rollup_block_height: u64) -> Result<Block, Error> {
+    let mut attempt = 1;
+    loop {
+        match Block::get(rollup_id, rollup_block_height) {
+            Ok(block) => return Ok(block),
+            Err(error) if error.is_none_type() && attempt < BLOCK_WAIT_ATTEMPTS => {
+                attempt += 1;
+                sleep(BLOCK_WAIT_INTERVAL).await;
+            }
+            Err(error) if error.is_none_type() => return Err(Error::BlockNotFound),
+            Err(error) => return Err(Error::Database(error)),
+        }
+    }
+}
+
+/// Returns the proofs of the aggregated range closed at `rollup_block_height`,
+/// building the aggregate over the local blocks if the rollup aggregates
+/// there and this node has not built it yet.
+async fn get_or_build_aggregate_proof_list(
+    rollup: &Rollup,
+    rollup_block_height: u64,
+) -> Result<Option<Vec<BlockCommitmentProof>>, Error> {
+    if let Some(block_commitment_proof_list) =
+        get_aggregate_proof_list(&rollup.rollup_id, rollup_block_height)?
+    {
+        return Ok(Some(block_commitment_proof_list));
+    }
+
+    let (from_rollup_block_height, to_rollup_block_height) =
+        match rollup.block_commitment_aggregation_range(rollup_block_height) {
+            Some(aggregation_range) => aggregation_range,
+            None => return Ok(None),
+        };
+
+    for rollup_block_height in from_rollup_block_height..to_rollup_block_height {
+        wait_for_block(&rollup.rollup_id, rollup_block_height).await?;
+    }
+    aggregate_block_commitment(
+        &rollup.rollup_id,
+        from_rollup_block_height,
+        to_rollup_block_height,
+    )
+    .await?;
+
+    get_aggregate_proof_list(&rollup.rollup_id, rollup_block_height)
+}
+
+/// Returns the proofs of every block of the aggregated range closed at
+/// `rollup_block_height`, or `None` if no aggregate was built there.
+fn get_aggregate_proof_list(
+    rollup_id: &str,
+    rollup_block_height: u64,
+) -> Result<Option<Vec<BlockCommitmentProof>>, Error> {
+    let block_commitment_proof = match BlockCommitmentProof::get(rollup_id, rollup_block_height) {
+        Ok(block_commitment_proof) => block_commitment_proof,
+        Err(error) if error.is_none_type() => return Ok(None),
+        Err(error) => return Err(Error::Database(error)),
+    };
+    if block_commitment_proof.to_rollup_block_height != rollup_block_height {
+        return Ok(None);
+    }
+
+    (block_commitment_proof.from_rollup_block_height..=rollup_block_height)
+        .map(|rollup_block_height| {
+            BlockCommitmentProof::get(rollup_id, rollup_block_height)
+                .map_err(|_| Error::BlockNotFound)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Checks that the local commitment of every block of the range is included
+/// in the submitted aggregate.
+fn verify_aggregate_block_commitment(
+    rollup_id: &str,
+    block_commitment_proof_list: &[BlockCommitmentProof],
+    submitted_block_commitment: &BlockCommitment,
+) -> Result<bool, Error> {
+    for block_commitment_proof in block_commitment_proof_list {
+        let block = Block::get(rollup_id, block_commitment_proof.rollup_block_height)
+            .map_err(|_| Error::BlockNotFound)?;
+
+        if block.block_commitment != block_commitment_proof.block_commitment
+            || !block_commitment_proof.verify(submitted_block_commitment)
+        {
+            tracing::error!(
+                "Block commitment not included in the aggregate - rollup_id: {:?} / rollup_block_height: {:?}",
+                rollup_id,
+                block_commitment_proof.rollup_block_height
+            );
+
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
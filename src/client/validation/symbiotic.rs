@@ -5,6 +5,7 @@ use radius_sdk::validation::symbiotic::{
 };
 use tokio::time::{sleep, Duration};
 
+use super::validate_block_commitment;
 use crate::{error::Error, state::AppState, types::*};
 
 pub struct ValidationClient {
@@ -133,33 +134,65 @@ impl ValidationClient {
 }
 
 async fn callback(event: ValidationServiceManager::NewTaskCreated, context: ValidationClient) {
-    let rollup = Rollup::get(&event.rollupId).ok();
-    if let Some(rollup) = rollup {
-        let block = Block::get(&rollup.rollup_id, event.blockNumber.try_into().unwrap()).unwrap();
-
-        tracing::info!("[Symbiotic] NewTaskCreated: clusterId: {:?} / rollupId: {:?} / referenceTaskIndex: {:?} / blockNumber: {:?} / blockCommitment: {:?}", event.clusterId, event.rollupId, event.referenceTaskIndex, event.blockNumber, event.blockCommitment);
-
-        if block.block_creator_address != context.publisher().address() {
-            for _ in 0..10 {
-                match context
-                    .publisher()
-                    .respond_to_task(
-                        &rollup.cluster_id,
-                        &rollup.rollup_id,
-                        event.referenceTaskIndex.try_into().unwrap(),
-                        true,
-                    )
-                    .await
-                    .map_err(|error| error.to_string())
-                {
-                    Ok(transaction_hash) => {
-                        tracing::info!("[Symbiotic] respond_to_task: {:?}", transaction_hash);
-                        break;
-                    }
-                    Err(error) => {
-                        tracing::warn!("[Symbiotic] respond_to_task: {:?}", error);
-                        sleep(Duration::from_secs(1)).await;
-                    }
+    let rollup = match Rollup::get(&event.rollupId) {
+        Ok(rollup) => rollup,
+        Err(_) => return,
+    };
+
+    tracing::info!("[Symbiotic] NewTaskCreated: clusterId: {:?} / rollupId: {:?} / referenceTaskIndex: {:?} / blockNumber: {:?} / blockCommitment: {:?}", event.clusterId, event.rollupId, event.referenceTaskIndex, event.blockNumber, event.blockCommitment);
+
+    let (rollup_block_height, reference_task_index) = match (
+        event.blockNumber.try_into(),
+        event.referenceTaskIndex.try_into(),
+    ) {
+        (Ok(rollup_block_height), Ok(reference_task_index)) => {
+            (rollup_block_height, reference_task_index)
+        }
+        _others => {
+            tracing::warn!("[Symbiotic] Invalid task - rollupId: {:?}", event.rollupId);
+            return;
+        }
+    };
+
+    let (block, _expected_block_commitment, is_valid) = match validate_block_commitment(
+        &rollup,
+        rollup_block_height,
+        event.blockCommitment.as_ref(),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(error) => {
+            tracing::warn!(
+                "[Symbiotic] Skipping the task - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                rollup.rollup_id,
+                rollup_block_height,
+                error
+            );
+            return;
+        }
+    };
+
+    if block.block_creator_address != context.publisher().address() {
+        for _ in 0..10 {
+            match context
+                .publisher()
+                .respond_to_task(
+                    &rollup.cluster_id,
+                    &rollup.rollup_id,
+                    reference_task_index,
+                    is_valid,
+                )
+                .await
+                .map_err(|error| error.to_string())
+            {
+                Ok(transaction_hash) => {
+                    tracing::info!("[Symbiotic] respond_to_task: {:?}", transaction_hash);
+                    break;
+                }
+                Err(error) => {
+                    tracing::warn!("[Symbiotic] respond_to_task: {:?}", error);
+                    sleep(Duration::from_secs(1)).await;
                 }
             }
         }
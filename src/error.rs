@@ -22,12 +22,15 @@ pub enum Error {
     Profiler(crate::profiler::ProfilerError),
 
     MerkleTreeDoesNotExist(String),
+    TaskPanicked(String),
     InitializeNewCluster(Box<dyn std::error::Error>),
     EmptyLeader,
+    InvalidLeader,
     EmptyLeaderClusterRpcUrl,
     InvalidPlatformBlockHeight,
     ClusterNotFound,
     RollupNotFound,
+    RollupInactive,
     SignerNotFound,
     SequencerInfoNotFound,
     ExecutorAddressNotFound,
@@ -40,6 +43,7 @@ pub enum Error {
     UnsupportedOrderCommitmentType,
     InvalidURL(reqwest::Error),
     HealthCheck(reqwest::Error),
+    Unhealthy,
     NotExistRollupMetadata,
     MutexError,
     NoEndpointsAvailable,
@@ -51,7 +55,21 @@ pub enum Error {
     InvalidSignature,
     InvalidTransaction,
     ExceedMaxGasLimit,
+    EmptyTransactionBundle,
+    NestedTransactionBundle,
+    InvalidTransactionOrder,
+    BlockCommitmentMismatch,
+    BlockNotFound,
+    PreMerklePathMismatch,
+    OrderCommitmentMismatch,
+    LeaderUnavailable,
+    RollupBusy,
+    OrderCommitmentAlreadyIssued,
+    RollupBlockHeightAlreadyBuilt,
+    TransactionBufferFull,
     RpcServerTerminated,
+    ShuttingDown,
+    ShutdownIncomplete,
     DatabaseVersionMismatch,
     Parse,
 }
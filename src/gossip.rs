@@ -0,0 +1,696 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{
+        mpsc::{self, error::TrySendError},
+        Mutex,
+    },
+    time::{sleep, Duration, Instant},
+};
+
+use crate::{
+    metrics::{GOSSIP_DELIVERY_FAILURES_TOTAL, GOSSIP_DROPPED_MESSAGES_TOTAL},
+    state::AppState,
+    types::{Platform, ServiceProvider},
+    util::current_timestamp,
+};
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+const MAX_DELIVERY_ATTEMPTS: u32 = 10;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const PEER_QUEUE_CAPACITY: usize = 1024;
+const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u64 = 3;
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// JSON-RPC error codes for a request the peer cannot handle at all, which it
+/// therefore rejects every time.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Handler errors, reported as the message of the JSON-RPC error object, that
+/// a peer repeats for the same message and which are therefore not retried.
+const NON_RETRYABLE_ERROR_LIST: [&str; 3] = [
+    "BlockHeightMismatch",
+    "InvalidSignature",
+    "InvalidTransaction",
+];
+
+/// Identifies a gossip message so that a message already waiting in a peer
+/// queue is not enqueued twice. `transaction_order` is `None` for block
+/// messages and numbers the updates of settings gossiped to the cluster.
+#[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+pub struct GossipKey {
+    pub method: String,
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+    pub transaction_order: Option<u64>,
+}
+
+impl GossipKey {
+    pub fn new(
+        method: impl AsRef<str>,
+        rollup_id: impl AsRef<str>,
+        rollup_block_height: u64,
+        transaction_order: Option<u64>,
+    ) -> Self {
+        Self {
+            method: method.as_ref().to_owned(),
+            rollup_id: rollup_id.as_ref().to_owned(),
+            rollup_block_height,
+            transaction_order,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct GossipMessage {
+    key: GossipKey,
+    parameter: serde_json::Value,
+}
+
+/// Delivery status of the outbound queue of one peer.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PeerGossipStatus {
+    pub cluster_rpc_url: String,
+    /// Messages enqueued but not yet acknowledged, i.e. how far the peer lags
+    /// behind this node.
+    pub pending_message_count: u64,
+    pub acknowledged_message_count: u64,
+    pub dropped_message_count: u64,
+    pub consecutive_failure_count: u64,
+    /// Whether messages to the peer are dropped without being sent until the
+    /// circuit breaker cooldown is over.
+    pub is_circuit_open: bool,
+    pub last_acknowledged_key: Option<GossipKey>,
+    /// Unix timestamp in seconds of the last acknowledgement.
+    pub last_acknowledged_at: Option<u64>,
+}
+
+struct Peer {
+    /// Tells the worker of this peer apart from the one of an earlier peer
+    /// with the same URL that was removed.
+    id: u64,
+    sender: mpsc::Sender<GossipMessage>,
+    pending_key_set: HashSet<GossipKey>,
+    circuit_breaker: CircuitBreaker,
+    status: PeerGossipStatus,
+}
+
+impl Peer {
+    fn record_dropped_message(&mut self, cluster_rpc_url: &str, context: &AppState) {
+        self.status.dropped_message_count += 1;
+        context
+            .metrics()
+            .increment_counter(GOSSIP_DROPPED_MESSAGES_TOTAL, &[("peer", cluster_rpc_url)]);
+    }
+}
+
+/// Stops sending to a peer after `CIRCUIT_BREAKER_FAILURE_THRESHOLD`
+/// consecutive undelivered messages, for `CIRCUIT_BREAKER_COOLDOWN`. The next
+/// message after the cooldown probes the peer again.
+#[derive(Clone, Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failure_count: u64,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn is_open(&self, now: Instant) -> bool {
+        self.open_until
+            .map(|open_until| now < open_until)
+            .unwrap_or(false)
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failure_count = 0;
+        self.open_until = None;
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failure_count += 1;
+
+        if self.consecutive_failure_count >= CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            self.open_until = Some(now + CIRCUIT_BREAKER_COOLDOWN);
+        }
+    }
+}
+
+/// Error object of a JSON-RPC response.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+impl JsonRpcError {
+    /// Returns whether redelivering the message may succeed.
+    fn is_retryable(&self) -> bool {
+        match self.code {
+            PARSE_ERROR | INVALID_REQUEST | METHOD_NOT_FOUND | INVALID_PARAMS => false,
+            _others => !NON_RETRYABLE_ERROR_LIST.contains(&self.message.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    jsonrpc: String,
+    error: Option<JsonRpcError>,
+}
+
+/// Reason a delivery attempt failed.
+#[derive(Debug)]
+enum DeliveryError {
+    Transport(reqwest::Error),
+    /// The peer answered with a body that is not a JSON-RPC response.
+    InvalidResponse,
+    /// The peer answered with a JSON-RPC error object.
+    Peer(JsonRpcError),
+}
+
+impl DeliveryError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) | Self::InvalidResponse => true,
+            Self::Peer(error) => error.is_retryable(),
+        }
+    }
+}
+
+/// Reads a JSON-RPC response. A response without an error object
+/// acknowledges the message.
+fn parse_response(response: &[u8]) -> Result<(), DeliveryError> {
+    let response: JsonRpcResponse =
+        serde_json::from_slice(response).map_err(|_| DeliveryError::InvalidResponse)?;
+    if response.jsonrpc != "2.0" {
+        return Err(DeliveryError::InvalidResponse);
+    }
+
+    match response.error {
+        Some(error) => Err(DeliveryError::Peer(error)),
+        None => Ok(()),
+    }
+}
+
+/// Outcome of delivering one message to a peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Delivery {
+    Acknowledged,
+    /// The peer answered with an error that a redelivery would repeat.
+    Rejected,
+    /// Every attempt failed.
+    Failed,
+}
+
+/// Reliable delivery of `sync_*` messages to the other cluster members.
+///
+/// Each peer has its own bounded, ordered outbound queue drained by a worker
+/// task. A message counts as acknowledged once the peer answers the RPC
+/// without an error and is retried with exponential backoff until then, up to
+/// `MAX_DELIVERY_ATTEMPTS`, unless the peer rejects it for good. Messages are
+/// dropped while the queue is full or the circuit breaker of the peer is open.
+/// A peer is removed, along with the messages still queued for it, once it is
+/// no longer a member of any cluster.
+pub struct Gossip {
+    inner: Arc<Mutex<HashMap<String, Peer>>>,
+    cluster_peer_map: Arc<Mutex<BTreeMap<(Platform, ServiceProvider, String), HashSet<String>>>>,
+    next_peer_id: Arc<AtomicU64>,
+    client: Client,
+}
+
+impl Clone for Gossip {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            cluster_peer_map: self.cluster_peer_map.clone(),
+            next_peer_id: self.next_peer_id.clone(),
+            client: self.client.clone(),
+        }
+    }
+}
+
+impl Default for Gossip {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HashMap::default())),
+            cluster_peer_map: Arc::new(Mutex::new(BTreeMap::default())),
+            next_peer_id: Arc::new(AtomicU64::new(0)),
+            client: Client::new(),
+        }
+    }
+}
+
+impl Gossip {
+    /// Enqueues `parameter` for every peer in `cluster_rpc_url_list`.
+    pub async fn broadcast<P>(
+        context: &AppState,
+        cluster_rpc_url_list: Vec<String>,
+        key: GossipKey,
+        parameter: &P,
+    ) where
+        P: Serialize,
+    {
+        let parameter = match serde_json::to_value(parameter) {
+            Ok(parameter) => parameter,
+            Err(error) => {
+                tracing::error!(
+                    "Failed to serialize the gossip message - key: {:?} / error: {:?}",
+                    key,
+                    error
+                );
+                return;
+            }
+        };
+
+        let gossip = context.gossip();
+        let mut peer_map = gossip.inner.lock().await;
+
+        for cluster_rpc_url in cluster_rpc_url_list {
+            let peer = peer_map
+                .entry(cluster_rpc_url.clone())
+                .or_insert_with(|| Self::spawn_peer(context.clone(), cluster_rpc_url.clone()));
+
+            // A message already waiting in the queue is sent once.
+            if peer.pending_key_set.contains(&key) {
+                continue;
+            }
+
+            if peer.circuit_breaker.is_open(Instant::now()) {
+                peer.record_dropped_message(&cluster_rpc_url, context);
+                continue;
+            }
+
+            let message = GossipMessage {
+                key: key.clone(),
+                parameter: parameter.clone(),
+            };
+            match peer.sender.try_send(message) {
+                Ok(()) => {
+                    peer.pending_key_set.insert(key.clone());
+                    peer.status.pending_message_count += 1;
+                }
+                Err(TrySendError::Full(_)) => {
+                    tracing::warn!(
+                        "Gossip queue is full, dropping the message - peer: {:?} / key: {:?}",
+                        cluster_rpc_url,
+                        key
+                    );
+                    peer.record_dropped_message(&cluster_rpc_url, context);
+                }
+                Err(TrySendError::Closed(_)) => {}
+            }
+        }
+    }
+
+    /// Records the peers of a cluster after its membership changed and
+    /// removes the peers that left it and belong to no other cluster.
+    pub async fn update_cluster_peer_list(
+        &self,
+        platform: Platform,
+        service_provider: ServiceProvider,
+        cluster_id: &str,
+        cluster_rpc_url_list: Vec<String>,
+    ) {
+        let mut cluster_peer_map = self.cluster_peer_map.lock().await;
+        let previous_cluster_rpc_url_set = cluster_peer_map
+            .insert(
+                (platform, service_provider, cluster_id.to_owned()),
+                cluster_rpc_url_list.into_iter().collect(),
+            )
+            .unwrap_or_default();
+
+        let mut peer_map = self.inner.lock().await;
+        for cluster_rpc_url in previous_cluster_rpc_url_set {
+            let is_member = cluster_peer_map
+                .values()
+                .any(|cluster_rpc_url_set| cluster_rpc_url_set.contains(&cluster_rpc_url));
+            if is_member {
+                continue;
+            }
+
+            if let Some(peer) = peer_map.remove(&cluster_rpc_url) {
+                tracing::info!(
+                    "Removed a peer that left the cluster - peer: {:?} / pending_message_count: {:?}",
+                    cluster_rpc_url,
+                    peer.status.pending_message_count
+                );
+            }
+        }
+    }
+
+    pub async fn peer_status_list(&self) -> Vec<PeerGossipStatus> {
+        let peer_map = self.inner.lock().await;
+        let now = Instant::now();
+
+        peer_map
+            .values()
+            .map(|peer| PeerGossipStatus {
+                is_circuit_open: peer.circuit_breaker.is_open(now),
+                ..peer.status.clone()
+            })
+            .collect()
+    }
+
+    /// Waits until every peer queue is empty. Returns `false` if messages
+    /// are still pending after `timeout`.
+    pub async fn wait_for_drain(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let pending_message_count: u64 = self
+                .peer_status_list()
+                .await
+                .iter()
+                .map(|status| status.pending_message_count)
+                .sum();
+
+            if pending_message_count == 0 {
+                return true;
+            }
+
+            if Instant::now() >= deadline {
+                tracing::warn!("Gossip messages still pending: {:?}", pending_message_count);
+                return false;
+            }
+
+            sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    }
+
+    fn spawn_peer(context: AppState, cluster_rpc_url: String) -> Peer {
+        let (sender, mut receiver) = mpsc::channel::<GossipMessage>(PEER_QUEUE_CAPACITY);
+        let id = context
+            .gossip()
+            .next_peer_id
+            .fetch_add(1, Ordering::Relaxed);
+
+        tokio::spawn({
+            let cluster_rpc_url = cluster_rpc_url.clone();
+
+            async move {
+                while let Some(message) = receiver.recv().await {
+                    // Messages queued before the circuit opened are dropped
+                    // without waiting for the peer, and so are the messages
+                    // of a peer that left the cluster.
+                    let is_circuit_open = match context
+                        .gossip()
+                        .inner
+                        .lock()
+                        .await
+                        .get(&cluster_rpc_url)
+                        .filter(|peer| peer.id == id)
+                    {
+                        Some(peer) => peer.circuit_breaker.is_open(Instant::now()),
+                        None => continue,
+                    };
+                    let delivery = if is_circuit_open {
+                        None
+                    } else {
+                        Some(Self::deliver(&context, &cluster_rpc_url, &message).await)
+                    };
+
+                    let mut peer_map = context.gossip().inner.lock().await;
+                    if let Some(peer) = peer_map
+                        .get_mut(&cluster_rpc_url)
+                        .filter(|peer| peer.id == id)
+                    {
+                        peer.pending_key_set.remove(&message.key);
+                        peer.status.pending_message_count =
+                            peer.status.pending_message_count.saturating_sub(1);
+
+                        match delivery {
+                            Some(Delivery::Acknowledged) => {
+                                peer.circuit_breaker.record_success();
+                                peer.status.acknowledged_message_count += 1;
+                                peer.status.last_acknowledged_key = Some(message.key);
+                                peer.status.last_acknowledged_at = Some(current_timestamp());
+                            }
+                            // The peer answered, so it is reachable.
+                            Some(Delivery::Rejected) => {
+                                peer.circuit_breaker.record_success();
+                                peer.record_dropped_message(&cluster_rpc_url, &context);
+                            }
+                            Some(Delivery::Failed) => {
+                                peer.circuit_breaker.record_failure(Instant::now());
+                                peer.record_dropped_message(&cluster_rpc_url, &context);
+                            }
+                            None => peer.record_dropped_message(&cluster_rpc_url, &context),
+                        }
+                        peer.status.consecutive_failure_count =
+                            peer.circuit_breaker.consecutive_failure_count;
+                    }
+                }
+            }
+        });
+
+        Peer {
+            id,
+            sender,
+            pending_key_set: HashSet::default(),
+            circuit_breaker: CircuitBreaker::default(),
+            status: PeerGossipStatus {
+                cluster_rpc_url,
+                ..Default::default()
+            },
+        }
+    }
+
+    async fn deliver(
+        context: &AppState,
+        cluster_rpc_url: &str,
+        message: &GossipMessage,
+    ) -> Delivery {
+        let mut backoff = INITIAL_BACKOFF;
+
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            match Self::request(context, cluster_rpc_url, message).await {
+                Ok(()) => return Delivery::Acknowledged,
+                Err(error) if !error.is_retryable() => {
+                    tracing::warn!(
+                        "Gossip message rejected by the peer - peer: {:?} / key: {:?} / error: {:?}",
+                        cluster_rpc_url,
+                        message.key,
+                        error
+                    );
+
+                    return Delivery::Rejected;
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        "Failed to deliver the gossip message - peer: {:?} / key: {:?} / attempt: {:?} / error: {:?}",
+                        cluster_rpc_url,
+                        message.key,
+                        attempt,
+                        error
+                    );
+                    context.metrics().increment_counter(
+                        GOSSIP_DELIVERY_FAILURES_TOTAL,
+                        &[("peer", cluster_rpc_url)],
+                    );
+                }
+            }
+
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+
+        tracing::error!(
+            "Dropping the gossip message - peer: {:?} / key: {:?}",
+            cluster_rpc_url,
+            message.key
+        );
+
+        Delivery::Failed
+    }
+
+    async fn request(
+        context: &AppState,
+        cluster_rpc_url: &str,
+        message: &GossipMessage,
+    ) -> Result<(), DeliveryError> {
+        let response = context
+            .gossip()
+            .client
+            .post(cluster_rpc_url)
+            .timeout(DELIVERY_TIMEOUT)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": message.key.method,
+                "params": message.parameter,
+                "id": 0,
+            }))
+            .send()
+            .await
+            .map_err(DeliveryError::Transport)?
+            .bytes()
+            .await
+            .map_err(DeliveryError::Transport)?;
+
+        parse_response(&response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_response(code: i64, message: &str) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": { "code": code, "message": message },
+            "id": 0,
+        }))
+        .unwrap()
+    }
+
+    fn is_retryable(response: &[u8]) -> bool {
+        parse_response(response).unwrap_err().is_retryable()
+    }
+
+    #[test]
+    fn a_result_acknowledges_the_message() {
+        let response =
+            serde_json::to_vec(&serde_json::json!({ "jsonrpc": "2.0", "result": null, "id": 0 }))
+                .unwrap();
+
+        parse_response(&response).unwrap();
+    }
+
+    #[test]
+    fn deterministic_rejections_are_not_retried() {
+        assert!(!is_retryable(&error_response(
+            -32603,
+            "BlockHeightMismatch"
+        )));
+        assert!(!is_retryable(&error_response(-32603, "InvalidSignature")));
+        assert!(!is_retryable(&error_response(-32603, "InvalidTransaction")));
+        assert!(!is_retryable(&error_response(
+            METHOD_NOT_FOUND,
+            "Method not found"
+        )));
+        assert!(!is_retryable(&error_response(
+            INVALID_PARAMS,
+            "Invalid params"
+        )));
+    }
+
+    #[test]
+    fn transient_errors_are_retried() {
+        assert!(is_retryable(&error_response(-32603, "RollupNotFound")));
+        // Only the exact error is deterministic, not every message naming it.
+        assert!(is_retryable(&error_response(
+            -32603,
+            "Database(KvStoreError) while checking InvalidSignature"
+        )));
+        assert!(is_retryable(b"<html>502 Bad Gateway</html>"));
+        assert!(is_retryable(br#"{"result": null}"#));
+    }
+
+    fn peer(id: u64) -> Peer {
+        let (sender, _receiver) = mpsc::channel(1);
+
+        Peer {
+            id,
+            sender,
+            pending_key_set: HashSet::default(),
+            circuit_breaker: CircuitBreaker::default(),
+            status: PeerGossipStatus::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn removes_the_peers_that_left_every_cluster() {
+        let gossip = Gossip::default();
+        let url = |name: &str| format!("http://{}", name);
+        for (id, name) in ["a", "b", "c"].into_iter().enumerate() {
+            gossip.inner.lock().await.insert(url(name), peer(id as u64));
+        }
+
+        gossip
+            .update_cluster_peer_list(
+                Platform::Local,
+                ServiceProvider::Radius,
+                "cluster_0",
+                vec![url("a"), url("b")],
+            )
+            .await;
+        gossip
+            .update_cluster_peer_list(
+                Platform::Local,
+                ServiceProvider::Radius,
+                "cluster_1",
+                vec![url("b")],
+            )
+            .await;
+        gossip
+            .update_cluster_peer_list(
+                Platform::Local,
+                ServiceProvider::Radius,
+                "cluster_0",
+                Vec::new(),
+            )
+            .await;
+
+        let mut peer_list: Vec<String> = gossip.inner.lock().await.keys().cloned().collect();
+        peer_list.sort();
+        // `b` is still a member of `cluster_1`, and `c` never was a member of
+        // a known cluster.
+        assert_eq!(peer_list, vec![url("b"), url("c")]);
+    }
+
+    #[test]
+    fn circuit_opens_after_consecutive_failures() {
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::default();
+
+        for _ in 1..CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            circuit_breaker.record_failure(now);
+            assert!(!circuit_breaker.is_open(now));
+        }
+
+        circuit_breaker.record_failure(now);
+        assert!(circuit_breaker.is_open(now));
+        assert!(circuit_breaker.is_open(now + CIRCUIT_BREAKER_COOLDOWN / 2));
+        assert!(!circuit_breaker.is_open(now + CIRCUIT_BREAKER_COOLDOWN));
+    }
+
+    #[test]
+    fn a_success_closes_the_circuit() {
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::default();
+        for _ in 0..CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            circuit_breaker.record_failure(now);
+        }
+
+        circuit_breaker.record_success();
+
+        assert!(!circuit_breaker.is_open(now));
+        assert_eq!(circuit_breaker.consecutive_failure_count, 0);
+    }
+
+    #[test]
+    fn a_failure_after_the_cooldown_reopens_the_circuit() {
+        let now = Instant::now();
+        let mut circuit_breaker = CircuitBreaker::default();
+        for _ in 0..CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            circuit_breaker.record_failure(now);
+        }
+
+        let after_cooldown = now + CIRCUIT_BREAKER_COOLDOWN;
+        circuit_breaker.record_failure(after_cooldown);
+
+        assert!(circuit_breaker.is_open(after_cooldown));
+    }
+}
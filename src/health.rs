@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::{timeout, Duration};
+
+use crate::{
+    client::{liveness, validation},
+    state::AppState,
+    types::*,
+    util::current_timestamp,
+};
+
+/// Time allowed for the distributed key generation node to answer.
+const DISTRIBUTED_KEY_GENERATION_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub const KVSTORE: &str = "kvstore";
+pub const LIVENESS_CLIENTS: &str = "liveness_clients";
+pub const DISTRIBUTED_KEY_GENERATION: &str = "distributed_key_generation";
+pub const MERKLE_TREES: &str = "merkle_trees";
+pub const VALIDATION_CLIENTS: &str = "validation_clients";
+
+/// Reported as long as the process answers requests.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HealthStatus {
+    pub is_healthy: bool,
+    pub code_version: String,
+    /// Unix timestamp in seconds at which the status was taken.
+    pub timestamp: u64,
+}
+
+impl HealthStatus {
+    pub fn current() -> Self {
+        Self {
+            is_healthy: true,
+            code_version: CURRENT_CODE_VERSION.to_owned(),
+            timestamp: current_timestamp(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SubsystemStatus {
+    pub is_ready: bool,
+    /// What is missing when the subsystem is not ready.
+    pub detail: Option<String>,
+}
+
+impl SubsystemStatus {
+    fn ready() -> Self {
+        Self {
+            is_ready: true,
+            detail: None,
+        }
+    }
+
+    fn not_ready(detail: impl Into<String>) -> Self {
+        Self {
+            is_ready: false,
+            detail: Some(detail.into()),
+        }
+    }
+
+    /// Ready if nothing is missing, otherwise lists the missing items.
+    fn from_missing_list(missing_list: Vec<String>) -> Self {
+        if missing_list.is_empty() {
+            Self::ready()
+        } else {
+            Self::not_ready(format!("missing: {}", missing_list.join(", ")))
+        }
+    }
+}
+
+/// The node accepts traffic only if every subsystem is ready.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ReadinessStatus {
+    pub is_ready: bool,
+    pub subsystem_list: BTreeMap<String, SubsystemStatus>,
+}
+
+impl ReadinessStatus {
+    pub async fn check(context: &AppState) -> Self {
+        let mut subsystem_list = BTreeMap::new();
+
+        subsystem_list.insert(KVSTORE.to_owned(), check_kvstore());
+        subsystem_list.insert(
+            LIVENESS_CLIENTS.to_owned(),
+            check_liveness_clients(context).await,
+        );
+        subsystem_list.insert(
+            DISTRIBUTED_KEY_GENERATION.to_owned(),
+            check_distributed_key_generation(context).await,
+        );
+        subsystem_list.insert(MERKLE_TREES.to_owned(), check_merkle_trees(context).await);
+        subsystem_list.insert(
+            VALIDATION_CLIENTS.to_owned(),
+            check_validation_clients(context).await,
+        );
+
+        Self {
+            is_ready: subsystem_list.values().all(|status| status.is_ready),
+            subsystem_list,
+        }
+    }
+}
+
+fn check_kvstore() -> SubsystemStatus {
+    match Version::get() {
+        Ok(_) => SubsystemStatus::ready(),
+        Err(error) => SubsystemStatus::not_ready(format!("{:?}", error)),
+    }
+}
+
+/// A liveness client is registered right before it subscribes to the
+/// liveness events, and is registered again whenever it reconnects.
+async fn check_liveness_clients(context: &AppState) -> SubsystemStatus {
+    let sequencing_info_list = match SequencingInfoList::get_or(SequencingInfoList::default) {
+        Ok(sequencing_info_list) => sequencing_info_list,
+        Err(error) => return SubsystemStatus::not_ready(format!("{:?}", error)),
+    };
+
+    let mut missing_list = Vec::new();
+    for (platform, service_provider) in sequencing_info_list.iter() {
+        let is_registered = match SequencingInfoPayload::get(*platform, *service_provider) {
+            Ok(SequencingInfoPayload::Ethereum(_)) => context
+                .get_liveness_client::<liveness::radius::LivenessClient>(
+                    *platform,
+                    *service_provider,
+                )
+                .await
+                .is_ok(),
+            Ok(SequencingInfoPayload::Local(_)) => context
+                .get_liveness_client::<liveness::local::LivenessClient>(
+                    *platform,
+                    *service_provider,
+                )
+                .await
+                .is_ok(),
+            Err(_) => false,
+        };
+
+        if !is_registered {
+            missing_list.push(format!("{:?}/{:?}", platform, service_provider));
+        }
+    }
+
+    SubsystemStatus::from_missing_list(missing_list)
+}
+
+async fn check_distributed_key_generation(context: &AppState) -> SubsystemStatus {
+    match timeout(
+        DISTRIBUTED_KEY_GENERATION_TIMEOUT,
+        context
+            .distributed_key_generation_client()
+            .get_skde_params(),
+    )
+    .await
+    {
+        Ok(Ok(_)) => SubsystemStatus::ready(),
+        Ok(Err(error)) => SubsystemStatus::not_ready(format!("{:?}", error)),
+        Err(_) => SubsystemStatus::not_ready("timed out"),
+    }
+}
+
+async fn check_merkle_trees(context: &AppState) -> SubsystemStatus {
+    let rollup_id_list = match RollupIdList::get_or(RollupIdList::default) {
+        Ok(rollup_id_list) => rollup_id_list,
+        Err(error) => return SubsystemStatus::not_ready(format!("{:?}", error)),
+    };
+
+    let mut missing_list = Vec::new();
+    for rollup_id in rollup_id_list.iter() {
+        if context.merkle_tree_manager().get(rollup_id).await.is_err() {
+            missing_list.push(rollup_id.clone());
+        }
+    }
+
+    SubsystemStatus::from_missing_list(missing_list)
+}
+
+async fn check_validation_clients(context: &AppState) -> SubsystemStatus {
+    let validation_service_providers =
+        match ValidationServiceProviders::get_or(ValidationServiceProviders::default) {
+            Ok(validation_service_providers) => validation_service_providers,
+            Err(error) => return SubsystemStatus::not_ready(format!("{:?}", error)),
+        };
+
+    let mut missing_list = Vec::new();
+    for (platform, provider) in validation_service_providers.iter() {
+        let is_registered = match ValidationInfo::get(*platform, *provider) {
+            Ok(ValidationInfo::EigenLayer(_)) => context
+                .get_validation_client::<validation::eigenlayer::ValidationClient>(
+                    *platform, *provider,
+                )
+                .await
+                .is_ok(),
+            Ok(ValidationInfo::Symbiotic(_)) => context
+                .get_validation_client::<validation::symbiotic::ValidationClient>(
+                    *platform, *provider,
+                )
+                .await
+                .is_ok(),
+            Err(_) => false,
+        };
+
+        if !is_registered {
+            missing_list.push(format!("{:?}/{:?}", platform, provider));
+        }
+    }
+
+    SubsystemStatus::from_missing_list(missing_list)
+}
@@ -1,10 +1,16 @@
 pub mod client;
 pub mod error;
+pub mod gossip;
+pub mod health;
 pub mod logger;
+pub mod metrics;
+pub mod monitoring;
 pub mod profiler;
 pub mod rpc;
+pub mod shutdown;
 pub mod state;
 pub mod task;
+pub mod transaction_buffer;
 pub mod types;
 pub extern crate skde;
 pub mod merkle_tree_manager;
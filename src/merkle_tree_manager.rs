@@ -26,100 +26,200 @@ impl Default for MerkleTreeManager {
 }
 
 impl MerkleTreeManager {
-    pub async fn init(rpc_client: &RpcClient) -> Self {
+    pub async fn init(rpc_client: &RpcClient) -> Result<Self, Error> {
         let merkle_tree_manager = Self::default();
 
-        let rollup_id_list = RollupIdList::get_or(RollupIdList::default).unwrap();
+        let rollup_id_list =
+            RollupIdList::get_or(RollupIdList::default).map_err(Error::Database)?;
         for rollup_id in rollup_id_list.iter() {
-            let merkle_tree = MerkleTree::new();
-
-            if let Some(rollup_metadata) = RollupMetadata::get(rollup_id).ok() {
-                if rollup_metadata.transaction_order > 0 {
-                    tracing::info!(
-                        "Building merkle tree for rollup - rollup_id: {:?} / rollup_block_height: {:?} / transaction_order: {:?}",
+            // Only the leader adds leaves, so followers start from an empty tree.
+            let merkle_tree = match RollupMetadata::get(rollup_id).ok() {
+                Some(rollup_metadata)
+                    if rollup_metadata.is_leader && rollup_metadata.transaction_order > 0 =>
+                {
+                    let merkle_tree = MerkleTree::restore(
                         rollup_id,
                         rollup_metadata.rollup_block_height,
-                        rollup_metadata.transaction_order
-                    );
-                    let rollup = Rollup::get(rollup_id).unwrap();
-                    let latest_cluster_block_height = LatestClusterBlockHeight::get_or(
-                        rollup.platform,
-                        rollup.service_provider,
-                        &rollup.cluster_id,
-                        LatestClusterBlockHeight::default,
+                        rollup_metadata.transaction_order,
                     )
-                    .unwrap();
+                    .unwrap_or_else(|| {
+                        tracing::warn!(
+                            "Merkle tree checkpoint is missing or stale - rollup_id: {:?} / rollup_block_height: {:?}",
+                            rollup_id,
+                            rollup_metadata.rollup_block_height,
+                        );
 
-                    let cluster = Cluster::get(
-                        rollup.platform,
-                        rollup.service_provider,
-                        &rollup.cluster_id,
-                        latest_cluster_block_height.get_block_height(),
-                    )
-                    .unwrap();
+                        MerkleTree::new()
+                    });
+
+                    Self::rebuild(rpc_client, rollup_id, &rollup_metadata, merkle_tree).await?
+                }
+                _ => MerkleTree::new(),
+            };
+
+            merkle_tree_manager.insert(rollup_id, merkle_tree).await;
+        }
 
-                    for index in 0..rollup_metadata.transaction_order {
-                        let get_raw_transaction_result = RawTransactionModel::get(
+        Ok(merkle_tree_manager)
+    }
+
+    /// Adds the stored transactions missing from `merkle_tree`, fetching the
+    /// ones not stored locally from the cluster, and checkpoints the result.
+    async fn rebuild(
+        rpc_client: &RpcClient,
+        rollup_id: &str,
+        rollup_metadata: &RollupMetadata,
+        merkle_tree: MerkleTree,
+    ) -> Result<MerkleTree, Error> {
+        let leaf_count = merkle_tree.leaf_count().await;
+        if leaf_count == rollup_metadata.transaction_order {
+            return Ok(merkle_tree);
+        }
+
+        tracing::info!(
+            "Building merkle tree for rollup - rollup_id: {:?} / rollup_block_height: {:?} / leaf_count: {:?} / transaction_order: {:?}",
+            rollup_id,
+            rollup_metadata.rollup_block_height,
+            leaf_count,
+            rollup_metadata.transaction_order
+        );
+
+        let rollup = Rollup::get(rollup_id).map_err(|_| Error::RollupNotFound)?;
+        let latest_cluster_block_height = LatestClusterBlockHeight::get_or(
+            rollup.platform,
+            rollup.service_provider,
+            &rollup.cluster_id,
+            LatestClusterBlockHeight::default,
+        )
+        .map_err(Error::Database)?;
+
+        let cluster = Cluster::get(
+            rollup.platform,
+            rollup.service_provider,
+            &rollup.cluster_id,
+            latest_cluster_block_height.get_block_height(),
+        )
+        .map_err(|_| Error::ClusterNotFound)?;
+
+        // An encrypted bundle is stored at the first order it covers, so the
+        // leaves of the following orders are its inner transaction hashes.
+        let mut encrypted_leaf_list: HashMap<u64, RawTransactionHash> = HashMap::new();
+
+        for index in leaf_count..rollup_metadata.transaction_order {
+            if let Ok(encrypted_transaction) = EncryptedTransactionModel::get(
+                rollup_id,
+                rollup_metadata.rollup_block_height,
+                index,
+            ) {
+                for (offset, raw_transaction_hash) in encrypted_transaction
+                    .raw_transaction_hash_list()
+                    .into_iter()
+                    .enumerate()
+                {
+                    encrypted_leaf_list.insert(index + offset as u64, raw_transaction_hash);
+                }
+            }
+
+            let raw_transaction_hash = match RawTransactionModel::get(
+                rollup_id,
+                rollup_metadata.rollup_block_height,
+                index,
+            ) {
+                Ok((raw_transaction, _)) => raw_transaction.raw_transaction_hash()?,
+                Err(_) => match encrypted_leaf_list.remove(&index) {
+                    Some(raw_transaction_hash) => raw_transaction_hash,
+                    None => {
+                        tracing::warn!(
+                            "Failed to get raw transaction - rollup_id: {:?} / rollup_block_height: {:?} / index: {:?}",
                             rollup_id,
                             rollup_metadata.rollup_block_height,
-                            index,
+                            index
                         );
 
-                        let raw_transaction_hash = match get_raw_transaction_result {
-                            Ok((raw_transaction, _)) => raw_transaction.raw_transaction_hash(),
-                            Err(_) => {
-                                tracing::warn!(
-                                "Failed to get raw transaction - rollup_id: {:?} / rollup_block_height: {:?} / index: {:?}",
+                        fetch_raw_transaction_info(
+                            rpc_client,
+                            &cluster,
+                            rollup_id,
+                            rollup_metadata.rollup_block_height,
+                            index,
+                        )
+                        .await
+                        .map_err(|error| {
+                            tracing::warn!(
+                                "Failed to fetch raw transaction - rollup_id: {:?} / rollup_block_height: {:?} / index: {:?} / error: {:?}",
                                 rollup_id,
                                 rollup_metadata.rollup_block_height,
-                                index
+                                index,
+                                error
                             );
 
-                                let raw_transaction_hash = match fetch_raw_transaction_info(
-                                    rpc_client,
-                                    &cluster,
-                                    &rollup_id,
-                                    rollup_metadata.rollup_block_height,
-                                    index,
-                                )
-                                .await
-                                {
-                                    Ok((raw_transaction, _)) => {
-                                        raw_transaction.raw_transaction_hash()
-                                    }
-                                    Err(error) => {
-                                        tracing::warn!(
-                                        "Failed to fetch raw transaction - rollup_id: {:?} / rollup_block_height: {:?} / index: {:?} / error: {:?}",
-                                        rollup_id,
-                                        rollup_metadata.rollup_block_height,
-                                        index,
-                                        error
-                                    );
-
-                                        let encrypted_transaction = EncryptedTransactionModel::get(
-                                            rollup_id,
-                                            rollup_metadata.rollup_block_height,
-                                            index,
-                                        )
-                                        .unwrap();
-
-                                        encrypted_transaction.raw_transaction_hash()
-                                    }
-                                };
-
-                                raw_transaction_hash
-                            }
-                        };
-
-                        merkle_tree.add_data(raw_transaction_hash.as_ref()).await;
+                            Error::RpcClient(error)
+                        })?
+                        .0
+                        .raw_transaction_hash()?
                     }
-                }
+                },
+            };
+
+            merkle_tree.add_data(raw_transaction_hash.as_ref()).await;
+        }
+
+        merkle_tree
+            .checkpoint(rollup_id, rollup_metadata.rollup_block_height)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(merkle_tree)
+    }
+
+    /// Checkpoints the tree of a leader once every `interval` transactions,
+    /// never if `interval` is zero. A failure is only logged since the missing
+    /// leaves are replayed from the stored transactions on startup.
+    pub async fn checkpoint_if_due(
+        merkle_tree: &MerkleTree,
+        rollup_id: &str,
+        rollup_block_height: u64,
+        transaction_count: u64,
+        interval: u64,
+    ) {
+        if interval == 0 || transaction_count % interval != 0 {
+            return;
+        }
+
+        if let Err(error) = merkle_tree.checkpoint(rollup_id, rollup_block_height).await {
+            tracing::warn!(
+                "Failed to checkpoint the merkle tree - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                rollup_id,
+                rollup_block_height,
+                error
+            );
+        }
+    }
+
+    /// Checkpoints the tree of every rollup this node leads at its current
+    /// block height.
+    pub async fn checkpoint_all(&self) -> Result<(), Error> {
+        let merkle_tree_list: Vec<(String, MerkleTree)> = self
+            .inner
+            .lock()
+            .await
+            .iter()
+            .map(|(rollup_id, merkle_tree)| (rollup_id.clone(), merkle_tree.clone()))
+            .collect();
+
+        for (rollup_id, merkle_tree) in merkle_tree_list {
+            let rollup_metadata = RollupMetadata::get(&rollup_id).map_err(Error::Database)?;
+            if !rollup_metadata.is_leader {
+                continue;
             }
 
-            merkle_tree_manager.insert(rollup_id, merkle_tree).await;
+            merkle_tree
+                .checkpoint(&rollup_id, rollup_metadata.rollup_block_height)
+                .await
+                .map_err(Error::Database)?;
         }
 
-        merkle_tree_manager
+        Ok(())
     }
 
     pub async fn insert(&self, rollup_id: &str, merkle_tree: MerkleTree) {
@@ -127,6 +227,11 @@ impl MerkleTreeManager {
         lock.insert(rollup_id.to_owned(), merkle_tree);
     }
 
+    pub async fn remove(&self, rollup_id: &str) {
+        let mut lock = self.inner.lock().await;
+        lock.remove(rollup_id);
+    }
+
     pub async fn get(&self, rollup_id: &str) -> Result<MerkleTree, Error> {
         let lock = self.inner.lock().await;
         let merkle_tree = lock
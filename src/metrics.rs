@@ -0,0 +1,419 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Arc, time::Duration};
+
+use dashmap::DashMap;
+
+use crate::types::{RollupIdList, RollupMetadata};
+
+pub const TRANSACTIONS_TOTAL: &str = "sequencer_transactions_total";
+pub const ORDER_COMMITMENT_ISSUANCE_SECONDS: &str = "sequencer_order_commitment_issuance_seconds";
+pub const DECRYPTION_SECONDS: &str = "sequencer_decryption_seconds";
+pub const BLOCK_BUILD_SECONDS: &str = "sequencer_block_build_seconds";
+pub const GOSSIP_DELIVERY_FAILURES_TOTAL: &str = "sequencer_gossip_delivery_failures_total";
+pub const GOSSIP_DROPPED_MESSAGES_TOTAL: &str = "sequencer_gossip_dropped_messages_total";
+pub const ROLLUP_BLOCK_HEIGHT: &str = "sequencer_rollup_block_height";
+pub const ROLLUP_TRANSACTION_ORDER: &str = "sequencer_rollup_transaction_order";
+pub const ROLLUP_CURRENT_GAS: &str = "sequencer_rollup_current_gas";
+pub const ROLLUP_MAX_GAS_LIMIT: &str = "sequencer_rollup_max_gas_limit";
+pub const ROLLUP_IS_LEADER: &str = "sequencer_rollup_is_leader";
+pub const LIVENESS_EVENT_LAG_BLOCKS: &str = "sequencer_liveness_event_lag_blocks";
+pub const LIVENESS_LAST_EVENT_TIMESTAMP_SECONDS: &str =
+    "sequencer_liveness_last_event_timestamp_seconds";
+
+/// Label of the transactions sent for rollups this node does not sequence, so
+/// that callers cannot create a series per made-up rollup ID.
+const UNKNOWN_ROLLUP_ID: &str = "unknown";
+
+/// Upper bounds in seconds of the latency histogram buckets.
+const HISTOGRAM_BUCKET_LIST: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+const METRIC_DESCRIPTION_LIST: [(&str, MetricType, &str); 13] = [
+    (
+        TRANSACTIONS_TOTAL,
+        MetricType::Counter,
+        "Transactions received through the external RPC by rollup, type and result.",
+    ),
+    (
+        ORDER_COMMITMENT_ISSUANCE_SECONDS,
+        MetricType::Histogram,
+        "Time taken to issue an order commitment.",
+    ),
+    (
+        DECRYPTION_SECONDS,
+        MetricType::Histogram,
+        "Time taken to decrypt an encrypted transaction by decryption key ID.",
+    ),
+    (
+        BLOCK_BUILD_SECONDS,
+        MetricType::Histogram,
+        "Time taken to build a block by rollup and role.",
+    ),
+    (
+        GOSSIP_DELIVERY_FAILURES_TOTAL,
+        MetricType::Counter,
+        "Failed attempts to deliver a sync message by peer.",
+    ),
+    (
+        GOSSIP_DROPPED_MESSAGES_TOTAL,
+        MetricType::Counter,
+        "Sync messages dropped after exhausting the delivery attempts by peer.",
+    ),
+    (
+        ROLLUP_BLOCK_HEIGHT,
+        MetricType::Gauge,
+        "Rollup block height being sequenced.",
+    ),
+    (
+        ROLLUP_TRANSACTION_ORDER,
+        MetricType::Gauge,
+        "Next transaction order of the current rollup block.",
+    ),
+    (
+        ROLLUP_CURRENT_GAS,
+        MetricType::Gauge,
+        "Gas used by the current rollup block.",
+    ),
+    (
+        ROLLUP_MAX_GAS_LIMIT,
+        MetricType::Gauge,
+        "Gas limit of a rollup block, 0 if unlimited.",
+    ),
+    (
+        ROLLUP_IS_LEADER,
+        MetricType::Gauge,
+        "1 if this node is the leader of the rollup.",
+    ),
+    (
+        LIVENESS_EVENT_LAG_BLOCKS,
+        MetricType::Gauge,
+        "Platform blocks between a liveness event and the last processed cluster height.",
+    ),
+    (
+        LIVENESS_LAST_EVENT_TIMESTAMP_SECONDS,
+        MetricType::Gauge,
+        "Unix timestamp of the last liveness event received.",
+    ),
+];
+
+/// Metric name and sorted label pairs.
+type MetricKey = (&'static str, Vec<(&'static str, String)>);
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    bucket_count_list: [u64; HISTOGRAM_BUCKET_LIST.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bucket_count) in HISTOGRAM_BUCKET_LIST
+            .iter()
+            .zip(self.bucket_count_list.iter_mut())
+        {
+            if value <= *bucket {
+                *bucket_count += 1;
+            }
+        }
+
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// In-process metric registry exposed in the Prometheus text format.
+pub struct Metrics {
+    inner: Arc<MetricsInner>,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    counter_map: DashMap<MetricKey, u64>,
+    gauge_map: DashMap<MetricKey, f64>,
+    histogram_map: DashMap<MetricKey, Histogram>,
+}
+
+impl Clone for Metrics {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(MetricsInner::default()),
+        }
+    }
+}
+
+impl Metrics {
+    pub fn increment_counter(&self, name: &'static str, label_list: &[(&'static str, &str)]) {
+        *self
+            .inner
+            .counter_map
+            .entry(metric_key(name, label_list))
+            .or_default() += 1;
+    }
+
+    pub fn set_gauge(&self, name: &'static str, label_list: &[(&'static str, &str)], value: f64) {
+        self.inner
+            .gauge_map
+            .insert(metric_key(name, label_list), value);
+    }
+
+    pub fn observe_duration(
+        &self,
+        name: &'static str,
+        label_list: &[(&'static str, &str)],
+        duration: Duration,
+    ) {
+        self.inner
+            .histogram_map
+            .entry(metric_key(name, label_list))
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Counts a transaction received through the external RPC. Rollup IDs
+    /// missing from the [`RollupIdList`] are counted as `unknown`.
+    pub fn record_transaction(&self, rollup_id: &str, transaction_type: &str, is_accepted: bool) {
+        let is_registered = RollupIdList::get_or(RollupIdList::default)
+            .map(|rollup_id_list| rollup_id_list.contains(rollup_id))
+            .unwrap_or(false);
+        let rollup_id = if is_registered {
+            rollup_id
+        } else {
+            UNKNOWN_ROLLUP_ID
+        };
+        let result = if is_accepted { "accepted" } else { "rejected" };
+
+        self.increment_counter(
+            TRANSACTIONS_TOTAL,
+            &[
+                ("rollup_id", rollup_id),
+                ("type", transaction_type),
+                ("result", result),
+            ],
+        );
+    }
+
+    pub fn record_rollup_metadata(&self, rollup_id: &str, rollup_metadata: &RollupMetadata) {
+        let label_list = [("rollup_id", rollup_id)];
+
+        self.set_gauge(
+            ROLLUP_BLOCK_HEIGHT,
+            &label_list,
+            rollup_metadata.rollup_block_height as f64,
+        );
+        self.set_gauge(
+            ROLLUP_TRANSACTION_ORDER,
+            &label_list,
+            rollup_metadata.transaction_order as f64,
+        );
+        self.set_gauge(
+            ROLLUP_CURRENT_GAS,
+            &label_list,
+            rollup_metadata.current_gas as f64,
+        );
+        self.set_gauge(
+            ROLLUP_MAX_GAS_LIMIT,
+            &label_list,
+            rollup_metadata.max_gas_limit as f64,
+        );
+        self.set_gauge(
+            ROLLUP_IS_LEADER,
+            &label_list,
+            if rollup_metadata.is_leader { 1.0 } else { 0.0 },
+        );
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut sample_map: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+
+        for entry in self.inner.counter_map.iter() {
+            let (name, label_list) = entry.key();
+            sample_map.entry(name).or_default().push(format!(
+                "{}{} {}",
+                name,
+                format_label_list(label_list, None),
+                entry.value()
+            ));
+        }
+
+        for entry in self.inner.gauge_map.iter() {
+            let (name, label_list) = entry.key();
+            sample_map.entry(name).or_default().push(format!(
+                "{}{} {}",
+                name,
+                format_label_list(label_list, None),
+                entry.value()
+            ));
+        }
+
+        for entry in self.inner.histogram_map.iter() {
+            let (name, label_list) = entry.key();
+            let histogram = entry.value();
+            let sample_list = sample_map.entry(name).or_default();
+
+            for (bucket, bucket_count) in HISTOGRAM_BUCKET_LIST
+                .iter()
+                .zip(histogram.bucket_count_list.iter())
+            {
+                sample_list.push(format!(
+                    "{}_bucket{} {}",
+                    name,
+                    format_label_list(label_list, Some(&bucket.to_string())),
+                    bucket_count
+                ));
+            }
+            sample_list.push(format!(
+                "{}_bucket{} {}",
+                name,
+                format_label_list(label_list, Some("+Inf")),
+                histogram.count
+            ));
+            sample_list.push(format!(
+                "{}_sum{} {}",
+                name,
+                format_label_list(label_list, None),
+                histogram.sum
+            ));
+            sample_list.push(format!(
+                "{}_count{} {}",
+                name,
+                format_label_list(label_list, None),
+                histogram.count
+            ));
+        }
+
+        let mut output = String::new();
+        for (name, metric_type, help) in METRIC_DESCRIPTION_LIST.iter() {
+            if let Some(sample_list) = sample_map.get_mut(name) {
+                sample_list.sort();
+
+                let _ = writeln!(output, "# HELP {} {}", name, help);
+                let _ = writeln!(output, "# TYPE {} {}", name, metric_type.as_str());
+                for sample in sample_list.iter() {
+                    let _ = writeln!(output, "{}", sample);
+                }
+            }
+        }
+
+        output
+    }
+}
+
+fn metric_key(name: &'static str, label_list: &[(&'static str, &str)]) -> MetricKey {
+    let mut label_list: Vec<(&'static str, String)> = label_list
+        .iter()
+        .map(|(label, value)| (*label, value.to_string()))
+        .collect();
+    label_list.sort();
+
+    (name, label_list)
+}
+
+fn format_label_list(label_list: &[(&'static str, String)], bucket: Option<&str>) -> String {
+    let mut formatted_label_list: Vec<String> = label_list
+        .iter()
+        .map(|(label, value)| format!("{}=\"{}\"", label, escape_label_value(value)))
+        .collect();
+
+    if let Some(bucket) = bucket {
+        formatted_label_list.push(format!("le=\"{}\"", bucket));
+    }
+
+    if formatted_label_list.is_empty() {
+        return String::new();
+    }
+
+    format!("{{{}}}", formatted_label_list.join(","))
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_gauges_with_sorted_labels() {
+        let metrics = Metrics::default();
+        metrics.increment_counter(
+            TRANSACTIONS_TOTAL,
+            &[("type", "raw"), ("rollup_id", "rollup_a")],
+        );
+        metrics.increment_counter(
+            TRANSACTIONS_TOTAL,
+            &[("rollup_id", "rollup_a"), ("type", "raw")],
+        );
+        metrics.set_gauge(ROLLUP_BLOCK_HEIGHT, &[("rollup_id", "rollup_a")], 7.0);
+
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE sequencer_transactions_total counter\n"));
+        assert!(output
+            .contains("sequencer_transactions_total{rollup_id=\"rollup_a\",type=\"raw\"} 2\n"));
+        assert!(output.contains("# TYPE sequencer_rollup_block_height gauge\n"));
+        assert!(output.contains("sequencer_rollup_block_height{rollup_id=\"rollup_a\"} 7\n"));
+        // Metrics without samples are left out.
+        assert!(!output.contains(BLOCK_BUILD_SECONDS));
+    }
+
+    #[test]
+    fn renders_cumulative_histogram_buckets() {
+        let metrics = Metrics::default();
+        metrics.observe_duration(BLOCK_BUILD_SECONDS, &[], Duration::from_millis(20));
+        metrics.observe_duration(BLOCK_BUILD_SECONDS, &[], Duration::from_secs(20));
+
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE sequencer_block_build_seconds histogram\n"));
+        assert!(output.contains("sequencer_block_build_seconds_bucket{le=\"0.01\"} 0\n"));
+        assert!(output.contains("sequencer_block_build_seconds_bucket{le=\"0.025\"} 1\n"));
+        assert!(output.contains("sequencer_block_build_seconds_bucket{le=\"10\"} 1\n"));
+        assert!(output.contains("sequencer_block_build_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(output.contains("sequencer_block_build_seconds_sum 20.02\n"));
+        assert!(output.contains("sequencer_block_build_seconds_count 2\n"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        let metrics = Metrics::default();
+        metrics.increment_counter(
+            GOSSIP_DELIVERY_FAILURES_TOTAL,
+            &[("peer", "http://\"peer\"\\\n")],
+        );
+
+        assert!(metrics.render().contains(
+            "sequencer_gossip_delivery_failures_total{peer=\"http://\\\"peer\\\"\\\\\\n\"} 1\n"
+        ));
+    }
+}
@@ -93,6 +93,8 @@ fn migrate_rollup(kv_store: &KvStore, rollup_id: &str) -> Result<(), Error> {
             validation_info: old_rollup.validation_info,
             executor_address_list: old_rollup.executor_address_list,
             max_gas_limit: 0,
+            is_active: true,
+            block_commitment_aggregation_size: None,
         };
 
         kv_store
@@ -0,0 +1,146 @@
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::{timeout, Duration},
+};
+
+use crate::{
+    error::Error,
+    health::{HealthStatus, ReadinessStatus},
+    state::AppState,
+    types::*,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// Serves on `address`:
+/// - `GET /metrics`: the metrics in the Prometheus text format.
+/// - `GET /health`: `200` as long as the process is up.
+/// - `GET /ready`: `200` if every subsystem is ready and `503` otherwise, with
+///   the [`ReadinessStatus`] as the JSON body.
+///
+/// The endpoint is a plain HTTP/1.1 listener separate from the JSON-RPC
+/// servers so that scrapers and orchestrators do not need to speak JSON-RPC.
+/// The same statuses are available on every RPC server through the `health`
+/// and `ready` methods.
+pub async fn serve_monitoring(context: AppState, address: String) -> Result<(), Error> {
+    let listener = TcpListener::bind(&address).await.map_err(Error::Syscall)?;
+
+    tracing::info!("Successfully started the monitoring server: {}", address);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                tracing::warn!("Failed to accept a monitoring connection: {:?}", error);
+                continue;
+            }
+        };
+
+        let context = context.clone();
+        tokio::spawn(async move {
+            if let Err(error) = handle_connection(&context, stream).await {
+                tracing::debug!("Monitoring connection closed: {:?}", error);
+            }
+        });
+    }
+}
+
+async fn handle_connection(context: &AppState, mut stream: TcpStream) -> std::io::Result<()> {
+    let request = match timeout(REQUEST_TIMEOUT, read_request_head(&mut stream)).await {
+        Ok(request) => request?,
+        Err(_) => return Ok(()),
+    };
+
+    let request_line = request.lines().next().unwrap_or_default();
+    let mut request_line = request_line.split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default();
+
+    let response = match (method, path) {
+        ("GET", "/metrics") => {
+            collect_rollup_metadata(context);
+
+            http_response(
+                "200 OK",
+                "text/plain; version=0.0.4; charset=utf-8",
+                &context.metrics().render(),
+            )
+        }
+        ("GET", "/health") => http_response(
+            "200 OK",
+            "application/json",
+            &serde_json::to_string(&HealthStatus::current()).unwrap_or_default(),
+        ),
+        ("GET", "/ready") => {
+            let readiness_status = ReadinessStatus::check(context).await;
+            let status = if readiness_status.is_ready {
+                "200 OK"
+            } else {
+                "503 Service Unavailable"
+            };
+
+            http_response(
+                status,
+                "application/json",
+                &serde_json::to_string(&readiness_status).unwrap_or_default(),
+            )
+        }
+        _ => http_response("404 Not Found", "text/plain; charset=utf-8", "Not Found\n"),
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0_u8; 1024];
+
+    loop {
+        let read_size = stream.read(&mut chunk).await?;
+        if read_size == 0 {
+            break;
+        }
+
+        buffer.extend_from_slice(&chunk[..read_size]);
+        if buffer.windows(4).any(|window| window == b"\r\n\r\n") || buffer.len() >= MAX_REQUEST_SIZE
+        {
+            break;
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Refreshes the per-rollup gauges from the stored [`RollupMetadata`] right
+/// before rendering, so that the values are never older than the scrape.
+fn collect_rollup_metadata(context: &AppState) {
+    let rollup_id_list = match RollupIdList::get_or(RollupIdList::default) {
+        Ok(rollup_id_list) => rollup_id_list,
+        Err(error) => {
+            tracing::warn!("Failed to get the rollup ID list: {:?}", error);
+            return;
+        }
+    };
+
+    for rollup_id in rollup_id_list.iter() {
+        if let Ok(rollup_metadata) = RollupMetadata::get(rollup_id) {
+            context
+                .metrics()
+                .record_rollup_metadata(rollup_id, &rollup_metadata);
+        }
+    }
+}
+
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
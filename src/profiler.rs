@@ -1,29 +1,179 @@
-use std::sync::Arc;
+use std::{fmt::Write, fs, path::PathBuf, str::FromStr, sync::Arc};
 
+use pprof2::{ProfilerGuard, ProfilerGuardBuilder};
 use pyroscope::{pyroscope::PyroscopeAgentRunning, PyroscopeAgent, PyroscopeError};
 use pyroscope_pprofrs::{pprof_backend, PprofConfig};
+use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
+use crate::util::current_timestamp;
+
+/// Libraries skipped while unwinding so that samples taken inside the signal
+/// handler do not deadlock.
+const BLOCK_LIST: [&str; 4] = ["libc", "libgcc", "pthread", "vdso"];
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfilerMode {
+    /// Continuously pushes the profiles to a Pyroscope server.
+    #[default]
+    Pyroscope,
+    /// Samples locally and writes the collapsed stacks (`.folded`) to a file
+    /// in the profile directory when the profiler stops. Needs no server.
+    Folded,
+}
+
+impl FromStr for ProfilerMode {
+    type Err = ProfilerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pyroscope" | "Pyroscope" => Ok(Self::Pyroscope),
+            "folded" | "Folded" => Ok(Self::Folded),
+            _others => Err(ProfilerError::UnsupportedMode),
+        }
+    }
+}
+
+/// Settings used every time the profiler is started.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProfilerSettings {
+    pub mode: ProfilerMode,
+    pub server_url: String,
+    pub application_name: String,
+    /// Sampling frequency in Hertz.
+    pub sample_rate: u32,
+    /// `(key, value)` tags attached to every sample, e.g. the rollup ID or
+    /// the node address.
+    pub tag_list: Vec<(String, String)>,
+    /// Directory receiving the profiles written in [`ProfilerMode::Folded`].
+    pub profile_path: PathBuf,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProfilerStatus {
+    pub is_running: bool,
+    pub mode: Option<ProfilerMode>,
+    /// Unix timestamp in seconds at which the running session started.
+    pub started_at: Option<u64>,
+    /// The last profile written in [`ProfilerMode::Folded`].
+    pub last_profile_path: Option<PathBuf>,
+}
+
 pub struct Profiler {
     inner: Arc<Mutex<ProfilerInner>>,
 }
 
 struct ProfilerInner {
-    agent: Option<PyroscopeAgent<PyroscopeAgentRunning>>,
+    settings: ProfilerSettings,
+    session: Option<ProfilingSession>,
+    started_at: Option<u64>,
+    last_profile_path: Option<PathBuf>,
+}
+
+enum ProfilingSession {
+    Pyroscope(PyroscopeAgent<PyroscopeAgentRunning>),
+    Folded(ProfilerGuard<'static>),
 }
 
 impl Drop for ProfilerInner {
     fn drop(&mut self) {
-        if let Some(agent) = self.agent.take() {
-            let agent_ready = agent.stop().map_err(ProfilerError::Stop).unwrap();
-            agent_ready.shutdown();
+        if self.session.is_none() {
+            return;
+        }
+
+        if let Err(error) = self.stop_session() {
+            tracing::error!("Failed to stop the profiler: {:?}", error);
         }
     }
 }
 
-impl From<PyroscopeAgent<PyroscopeAgentRunning>> for ProfilerInner {
-    fn from(value: PyroscopeAgent<PyroscopeAgentRunning>) -> Self {
-        Self { agent: Some(value) }
+impl ProfilerInner {
+    fn status(&self) -> ProfilerStatus {
+        ProfilerStatus {
+            is_running: self.session.is_some(),
+            mode: self.session.as_ref().map(|session| match session {
+                ProfilingSession::Pyroscope(_) => ProfilerMode::Pyroscope,
+                ProfilingSession::Folded(_) => ProfilerMode::Folded,
+            }),
+            started_at: self.started_at,
+            last_profile_path: self.last_profile_path.clone(),
+        }
+    }
+
+    fn start_session(&mut self, mode: ProfilerMode) -> Result<(), ProfilerError> {
+        if self.session.is_some() {
+            return Err(ProfilerError::AlreadyRunning);
+        }
+
+        let session = match mode {
+            ProfilerMode::Pyroscope => {
+                let tag_list = self
+                    .settings
+                    .tag_list
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect();
+
+                let agent = PyroscopeAgent::builder(
+                    &self.settings.server_url,
+                    &self.settings.application_name,
+                )
+                .backend(pprof_backend(
+                    PprofConfig::new().sample_rate(self.settings.sample_rate),
+                ))
+                .tags(tag_list)
+                .build()
+                .map_err(ProfilerError::Initialize)?;
+
+                ProfilingSession::Pyroscope(agent.start().map_err(ProfilerError::Start)?)
+            }
+            ProfilerMode::Folded => {
+                let guard = ProfilerGuardBuilder::default()
+                    .frequency(self.settings.sample_rate as i32)
+                    .blocklist(&BLOCK_LIST)
+                    .build()
+                    .map_err(ProfilerError::Pprof)?;
+
+                ProfilingSession::Folded(guard)
+            }
+        };
+
+        self.session = Some(session);
+        self.started_at = Some(current_timestamp());
+
+        Ok(())
+    }
+
+    fn stop_session(&mut self) -> Result<(), ProfilerError> {
+        let started_at = self.started_at.take().unwrap_or_default();
+
+        match self.session.take() {
+            Some(ProfilingSession::Pyroscope(agent)) => {
+                let agent_ready = agent.stop().map_err(ProfilerError::Stop)?;
+                agent_ready.shutdown();
+            }
+            Some(ProfilingSession::Folded(guard)) => {
+                let report = guard.report().build().map_err(ProfilerError::Pprof)?;
+                let profile_path = self.settings.profile_path.join(format!(
+                    "{}-{}-{}.folded",
+                    self.settings.application_name,
+                    started_at,
+                    current_timestamp()
+                ));
+
+                fs::create_dir_all(&self.settings.profile_path)
+                    .map_err(ProfilerError::WriteProfile)?;
+                fs::write(&profile_path, collapse_stack_list(&report))
+                    .map_err(ProfilerError::WriteProfile)?;
+
+                tracing::info!("Profile written to {:?}", profile_path);
+                self.last_profile_path = Some(profile_path);
+            }
+            None => return Err(ProfilerError::NotRunning),
+        }
+
+        Ok(())
     }
 }
 
@@ -36,40 +186,50 @@ impl Clone for Profiler {
 }
 
 impl Profiler {
-    /// Initialize the profiler agent.
-    ///
-    /// # Parameters
-    ///
-    /// - `server_url`: The explorer URL for the dashboard.
-    /// - `application_name`: The application name that you are running the
-    ///   profiler in.
-    /// - `sample_rate`: Sampling frequency in Hertz.
-    ///
-    /// # Examples
+    /// Creates a stopped profiler. Call [`Profiler::start`] to begin
+    /// sampling.
+    pub fn new(settings: ProfilerSettings) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ProfilerInner {
+                settings,
+                session: None,
+                started_at: None,
+                last_profile_path: None,
+            })),
+        }
+    }
+
+    /// Starts sampling in `mode`, or in the configured mode if `None`.
     ///
-    /// ```rust
-    /// // Initialize the profiler server at "http://127.0.0.1:4040" for the "sequencer" with sampling rate of 100 Hertz.
-    /// let profiler = Profiler::init("http://127.0.0.1:4040", "sequencer", 100)?;
-    /// ```
-    pub fn init(
-        server_url: &str,
-        application_name: &str,
-        sample_rate: u32,
-    ) -> Result<Self, ProfilerError> {
-        let agent = PyroscopeAgent::builder(server_url, application_name)
-            .backend(pprof_backend(PprofConfig::new().sample_rate(sample_rate)))
-            .build()
-            .map_err(ProfilerError::Initialize)?;
-
-        let agent_running = agent.start().map_err(ProfilerError::Start)?;
-
-        Ok(Self {
-            inner: Arc::new(Mutex::new(agent_running.into())),
-        })
+    /// Only one session can run at a time because both modes share the
+    /// process-wide sampler.
+    pub async fn start(&self, mode: Option<ProfilerMode>) -> Result<ProfilerStatus, ProfilerError> {
+        let mut inner = self.inner.lock().await;
+        let mode = mode.unwrap_or(inner.settings.mode);
+
+        inner.start_session(mode)?;
+        tracing::info!("Profiler started - mode: {:?}", mode);
+
+        Ok(inner.status())
+    }
+
+    /// Stops the running session. In [`ProfilerMode::Folded`] the collected
+    /// profile is written before returning.
+    pub async fn stop(&self) -> Result<ProfilerStatus, ProfilerError> {
+        let mut inner = self.inner.lock().await;
+
+        inner.stop_session()?;
+        tracing::info!("Profiler stopped");
+
+        Ok(inner.status())
+    }
+
+    pub async fn status(&self) -> ProfilerStatus {
+        self.inner.lock().await.status()
     }
 
     /// Use tag wrapper to scope the function(s) you want to measure
-    /// the performance of.
+    /// the performance of. Only available in [`ProfilerMode::Pyroscope`].
     ///
     /// # Examples
     ///
@@ -111,7 +271,7 @@ impl Profiler {
     > {
         let profiler = self.inner.lock().await;
 
-        if let Some(agent) = profiler.agent.as_ref() {
+        if let Some(ProfilingSession::Pyroscope(agent)) = profiler.session.as_ref() {
             Ok(agent.tag_wrapper())
         } else {
             Err(ProfilerError::TagWrapper)
@@ -119,11 +279,40 @@ impl Profiler {
     }
 }
 
+/// Formats the report as collapsed stacks (`thread;root;..;leaf count`),
+/// readable by flamegraph tools and Pyroscope.
+fn collapse_stack_list(report: &pprof2::Report) -> String {
+    let mut output = String::new();
+
+    for (frames, count) in report.data.iter() {
+        let thread = if frames.thread_name.is_empty() {
+            frames.thread_id.to_string()
+        } else {
+            frames.thread_name.clone()
+        };
+
+        let _ = write!(output, "{}", thread);
+        for frame in frames.frames.iter().rev() {
+            for symbol in frame.iter().rev() {
+                let _ = write!(output, ";{}", symbol.name());
+            }
+        }
+        let _ = writeln!(output, " {}", count);
+    }
+
+    output
+}
+
 #[derive(Debug)]
 pub enum ProfilerError {
     Initialize(PyroscopeError),
     Start(PyroscopeError),
     Stop(PyroscopeError),
+    Pprof(pprof2::Error),
+    WriteProfile(std::io::Error),
+    UnsupportedMode,
+    AlreadyRunning,
+    NotRunning,
     TagWrapper,
 }
 
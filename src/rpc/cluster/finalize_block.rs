@@ -3,7 +3,10 @@ use std::str::FromStr;
 use ethers_core::types::{Signature as EthSignature, H256};
 use radius_sdk::{signature::ChainType, validation::symbiotic::types::Keccak256};
 
-use crate::{rpc::prelude::*, task::build_block};
+use crate::{
+    rpc::prelude::*,
+    task::{build_block, replay_buffered_transaction},
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct FinalizeBlock {
@@ -96,6 +99,10 @@ impl RpcParameter<AppState> for FinalizeBlock {
 
         // Check the executor address
         let rollup = Rollup::get(&self.finalize_block_message.rollup_id)?;
+        if !rollup.is_active {
+            return Err(Error::RollupInactive)?;
+        }
+
         let signer_address = self.get_executor_address(rollup.platform.into())?;
 
         rollup
@@ -110,6 +117,12 @@ impl RpcParameter<AppState> for FinalizeBlock {
                 Error::ExecutorAddressNotFound
             })?;
 
+        liveness::local::check_leader_rotation(
+            &rollup,
+            self.finalize_block_message.rollup_block_height,
+            &self.finalize_block_message.next_block_creator_address,
+        )?;
+
         let cluster = Cluster::get(
             rollup.platform,
             rollup.service_provider,
@@ -123,17 +136,11 @@ impl RpcParameter<AppState> for FinalizeBlock {
             self.finalize_block_message.platform_block_height,
             cluster.err());
 
-            let liveness_client: liveness::radius::LivenessClient = context
-                .get_liveness_client::<liveness::radius::LivenessClient>(
-                    rollup.platform,
-                    rollup.service_provider,
-                )
-                .await?;
-
             Cluster::sync_cluster(
                 context.clone(),
+                rollup.platform,
+                rollup.service_provider,
                 &rollup.cluster_id,
-                &liveness_client,
                 self.finalize_block_message.platform_block_height,
             )
             .await?
@@ -144,6 +151,11 @@ impl RpcParameter<AppState> for FinalizeBlock {
             .finalize_block(context.clone(), &cluster, &rollup)
             .await?;
 
+        replay_buffered_transaction(
+            context.clone(),
+            self.finalize_block_message.rollup_id.clone(),
+        );
+
         build_block(
             context,
             cluster,
@@ -0,0 +1,45 @@
+use crate::rpc::prelude::*;
+
+/// Maximum number of blocks returned by a single range request.
+pub const MAX_BLOCK_RANGE: u64 = 16;
+
+/// Returns the blocks stored for `[from_rollup_block_height,
+/// to_rollup_block_height)` so that a newly joined sequencer can download the
+/// history of a rollup. Heights not stored on this node are skipped.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetBlockRange {
+    pub rollup_id: String,
+    pub from_rollup_block_height: u64,
+    pub to_rollup_block_height: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetBlockRangeResponse {
+    pub block_list: Vec<Block>,
+}
+
+impl RpcParameter<AppState> for GetBlockRange {
+    type Response = GetBlockRangeResponse;
+
+    fn method() -> &'static str {
+        "get_block_range"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let to_rollup_block_height = self.to_rollup_block_height.min(
+            self.from_rollup_block_height
+                .saturating_add(MAX_BLOCK_RANGE),
+        );
+
+        let mut block_list = Vec::new();
+        for rollup_block_height in self.from_rollup_block_height..to_rollup_block_height {
+            match Block::get(&self.rollup_id, rollup_block_height) {
+                Ok(block) => block_list.push(block),
+                Err(error) if error.is_none_type() => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Ok(GetBlockRangeResponse { block_list })
+    }
+}
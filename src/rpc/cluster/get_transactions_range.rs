@@ -0,0 +1,85 @@
+use crate::rpc::prelude::*;
+
+/// Maximum number of transactions returned by a single range request.
+pub const MAX_TRANSACTIONS_RANGE: u64 = 256;
+
+/// Returns the transactions stored for `[from_transaction_order,
+/// to_transaction_order)` of a rollup block so that a follower can fill the
+/// gaps left by missed sync messages.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionsRange {
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+    pub from_transaction_order: u64,
+    pub to_transaction_order: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransactionRangeEntry {
+    pub transaction_order: u64,
+    pub raw_transaction: Option<RawTransaction>,
+    pub is_direct_sent: bool,
+    pub encrypted_transaction: Option<EncryptedTransaction>,
+    pub order_commitment: Option<OrderCommitment>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionsRangeResponse {
+    pub transaction_list: Vec<TransactionRangeEntry>,
+}
+
+impl RpcParameter<AppState> for GetTransactionsRange {
+    type Response = GetTransactionsRangeResponse;
+
+    fn method() -> &'static str {
+        "get_transactions_range"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let to_transaction_order = self.to_transaction_order.min(
+            self.from_transaction_order
+                .saturating_add(MAX_TRANSACTIONS_RANGE),
+        );
+
+        let mut transaction_list = Vec::new();
+        for transaction_order in self.from_transaction_order..to_transaction_order {
+            let (raw_transaction, is_direct_sent) = match RawTransactionModel::get(
+                &self.rollup_id,
+                self.rollup_block_height,
+                transaction_order,
+            ) {
+                Ok((raw_transaction, is_direct_sent)) => (Some(raw_transaction), is_direct_sent),
+                Err(error) if error.is_none_type() => (None, false),
+                Err(error) => return Err(error.into()),
+            };
+
+            let encrypted_transaction = match EncryptedTransactionModel::get(
+                &self.rollup_id,
+                self.rollup_block_height,
+                transaction_order,
+            ) {
+                Ok(encrypted_transaction) => Some(encrypted_transaction),
+                Err(error) if error.is_none_type() => None,
+                Err(error) => return Err(error.into()),
+            };
+
+            if raw_transaction.is_none() && encrypted_transaction.is_none() {
+                continue;
+            }
+
+            let order_commitment =
+                OrderCommitment::get(&self.rollup_id, self.rollup_block_height, transaction_order)
+                    .ok();
+
+            transaction_list.push(TransactionRangeEntry {
+                transaction_order,
+                raw_transaction,
+                is_direct_sent,
+                encrypted_transaction,
+                order_commitment,
+            });
+        }
+
+        Ok(GetTransactionsRangeResponse { transaction_list })
+    }
+}
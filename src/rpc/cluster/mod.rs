@@ -1,11 +1,17 @@
 mod finalize_block;
+mod get_block_range;
+mod get_transactions_range;
 mod sync_block;
+mod sync_block_commitment_aggregation_size;
 mod sync_encrypted_transaction;
 mod sync_max_gas_limit;
 mod sync_raw_transaction;
 
 pub use finalize_block::*;
+pub use get_block_range::*;
+pub use get_transactions_range::*;
 pub use sync_block::SyncBlock;
+pub use sync_block_commitment_aggregation_size::*;
 pub use sync_encrypted_transaction::*;
 pub use sync_max_gas_limit::*;
 pub use sync_raw_transaction::*;
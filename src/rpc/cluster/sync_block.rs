@@ -1,6 +1,6 @@
 use crate::{
     rpc::{cluster::FinalizeBlockMessage, prelude::*},
-    task::follow_block,
+    task::{follow_block, replay_buffered_transaction},
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -47,17 +47,11 @@ impl RpcParameter<AppState> for SyncBlock {
             self.finalize_block_message.platform_block_height,
             cluster.err());
 
-            let liveness_client: liveness::radius::LivenessClient = context
-                .get_liveness_client::<liveness::radius::LivenessClient>(
-                    rollup.platform,
-                    rollup.service_provider,
-                )
-                .await?;
-
             Cluster::sync_cluster(
                 context.clone(),
+                rollup.platform,
+                rollup.service_provider,
                 &rollup.cluster_id,
-                &liveness_client,
                 self.finalize_block_message.platform_block_height,
             )
             .await?
@@ -85,6 +79,12 @@ impl RpcParameter<AppState> for SyncBlock {
 
         match RollupMetadata::get_mut(&self.finalize_block_message.rollup_id) {
             Ok(mut rollup_metadata) => {
+                // Acknowledge a redelivered block without resetting the
+                // transactions already received for the next block.
+                if rollup_metadata.rollup_block_height >= next_rollup_block_height {
+                    return Ok(());
+                }
+
                 rollup_metadata.rollup_block_height = next_rollup_block_height;
                 rollup_metadata.transaction_order = 0;
                 rollup_metadata.platform_block_height =
@@ -126,6 +126,11 @@ impl RpcParameter<AppState> for SyncBlock {
             }
         }
 
+        replay_buffered_transaction(
+            context.clone(),
+            self.finalize_block_message.rollup_id.clone(),
+        );
+
         follow_block(
             context.clone(),
             cluster,
@@ -0,0 +1,56 @@
+use crate::rpc::prelude::*;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SyncBlockCommitmentAggregationSize {
+    pub message: SyncBlockCommitmentAggregationSizeMessage,
+    pub signature: Signature,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SyncBlockCommitmentAggregationSizeMessage {
+    pub rollup_id: String,
+    pub block_commitment_aggregation_size: Option<u64>,
+}
+
+impl RpcParameter<AppState> for SyncBlockCommitmentAggregationSize {
+    type Response = ();
+
+    fn method() -> &'static str {
+        "sync_block_commitment_aggregation_size"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        tracing::debug!(
+            "Sync block commitment aggregation size - rollup id: {:?}, aggregation size: {:?}",
+            self.message.rollup_id,
+            self.message.block_commitment_aggregation_size
+        );
+
+        let rollup_metadata = RollupMetadata::get(&self.message.rollup_id)?;
+        let mut locked_rollup = Rollup::get_mut(&self.message.rollup_id)?;
+        let cluster = Cluster::get(
+            locked_rollup.platform,
+            locked_rollup.service_provider,
+            &locked_rollup.cluster_id,
+            rollup_metadata.platform_block_height,
+        )?;
+        let sequencer_address_list = cluster.get_sequencer_address_list();
+
+        let chain_type = locked_rollup.platform.into();
+        for sequencer_address in sequencer_address_list {
+            let verify_result =
+                self.signature
+                    .verify_message(chain_type, &self.message, sequencer_address);
+
+            if verify_result.is_ok() {
+                locked_rollup.block_commitment_aggregation_size =
+                    self.message.block_commitment_aggregation_size;
+                locked_rollup.update()?;
+
+                return Ok(());
+            }
+        }
+
+        Err(Error::InvalidSignature)?
+    }
+}
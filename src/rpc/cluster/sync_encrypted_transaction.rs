@@ -49,33 +49,68 @@ impl RpcParameter<AppState> for SyncEncryptedTransaction {
             return Err(Error::BlockHeightMismatch.into());
         }
 
-        let transaction_hash = self.message.encrypted_transaction.raw_transaction_hash();
-
-        EncryptedTransactionModel::put_with_transaction_hash(
-            &self.message.rollup_id,
-            &transaction_hash,
-            &self.message.encrypted_transaction,
-        )?;
-
-        EncryptedTransactionModel::put(
+        // Acknowledge a redelivered message without applying it twice.
+        if EncryptedTransactionModel::get(
             &self.message.rollup_id,
             self.message.rollup_block_height,
             self.message.transaction_order,
-            &self.message.encrypted_transaction,
-        )?;
+        )
+        .is_ok()
+        {
+            return Ok(());
+        }
 
-        self.message.order_commitment.put(
-            &self.message.rollup_id,
-            self.message.rollup_block_height,
-            self.message.transaction_order,
-        )?;
+        self.message.store()?;
+
+        // A bundle takes one order per inner transaction.
+        let last_transaction_order =
+            self.message.transaction_order + self.message.transaction_count().saturating_sub(1);
 
         rollup_metadata.current_gas += transaction_gas_limit;
-        if rollup_metadata.transaction_order < self.message.transaction_order {
-            rollup_metadata.transaction_order = self.message.transaction_order;
+        if rollup_metadata.transaction_order < last_transaction_order {
+            rollup_metadata.transaction_order = last_transaction_order;
         }
         rollup_metadata.update()?;
 
         Ok(())
     }
 }
+
+impl SyncEncryptedTransactionMessage {
+    /// Returns the number of orders the transaction takes.
+    pub fn transaction_count(&self) -> u64 {
+        self.encrypted_transaction.raw_transaction_hash_list().len() as u64
+    }
+
+    /// Stores the transaction under its order and the hash of every
+    /// transaction it carries, along with the order commitment of every
+    /// order it takes. The transaction backfill stores through here too.
+    pub fn store(&self) -> Result<(), Error> {
+        for transaction_hash in self.encrypted_transaction.raw_transaction_hash_list() {
+            EncryptedTransactionModel::put_with_transaction_hash(
+                &self.rollup_id,
+                &transaction_hash,
+                &self.encrypted_transaction,
+            )
+            .map_err(Error::Database)?;
+        }
+
+        EncryptedTransactionModel::put(
+            &self.rollup_id,
+            self.rollup_block_height,
+            self.transaction_order,
+            &self.encrypted_transaction,
+        )
+        .map_err(Error::Database)?;
+
+        let transaction_count = self.transaction_count().max(1);
+        for transaction_order in self.transaction_order..self.transaction_order + transaction_count
+        {
+            self.order_commitment
+                .put(&self.rollup_id, self.rollup_block_height, transaction_order)
+                .map_err(Error::Database)?;
+        }
+
+        Ok(())
+    }
+}
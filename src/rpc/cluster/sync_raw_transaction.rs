@@ -51,31 +51,19 @@ impl RpcParameter<AppState> for SyncRawTransaction {
             return Err(Error::BlockHeightMismatch.into());
         }
 
-        let transaction_hash = self.message.raw_transaction.raw_transaction_hash();
-
-        RawTransactionModel::put_with_transaction_hash(
-            &self.message.rollup_id,
-            &transaction_hash,
-            self.message.raw_transaction.clone(),
-            self.message.is_direct_sent,
-        )?;
-
-        RawTransactionModel::put(
+        // Acknowledge a redelivered message without applying it twice.
+        if RawTransactionModel::get(
             &self.message.rollup_id,
             self.message.rollup_block_height,
             self.message.transaction_order,
-            self.message.raw_transaction.clone(),
-            self.message.is_direct_sent,
-        )?;
-
-        if let Some(order_commitment) = self.message.order_commitment {
-            order_commitment.put(
-                &self.message.rollup_id,
-                self.message.rollup_block_height,
-                self.message.transaction_order,
-            )?;
+        )
+        .is_ok()
+        {
+            return Ok(());
         }
 
+        self.message.store()?;
+
         rollup_metadata.current_gas += transaction_gas_limit;
         if rollup_metadata.transaction_order < self.message.transaction_order {
             rollup_metadata.transaction_order = self.message.transaction_order;
@@ -85,3 +73,40 @@ impl RpcParameter<AppState> for SyncRawTransaction {
         Ok(())
     }
 }
+
+impl SyncRawTransactionMessage {
+    /// Stores the transaction under its order and its hash along with its
+    /// order commitment. The transaction backfill stores through here too.
+    pub fn store(&self) -> Result<(), Error> {
+        let transaction_hash = self.raw_transaction.raw_transaction_hash()?;
+
+        RawTransactionModel::put_with_transaction_hash(
+            &self.rollup_id,
+            &transaction_hash,
+            self.raw_transaction.clone(),
+            self.is_direct_sent,
+        )
+        .map_err(Error::Database)?;
+
+        RawTransactionModel::put(
+            &self.rollup_id,
+            self.rollup_block_height,
+            self.transaction_order,
+            self.raw_transaction.clone(),
+            self.is_direct_sent,
+        )
+        .map_err(Error::Database)?;
+
+        if let Some(order_commitment) = self.order_commitment.as_ref() {
+            order_commitment
+                .put(
+                    &self.rollup_id,
+                    self.rollup_block_height,
+                    self.transaction_order,
+                )
+                .map_err(Error::Database)?;
+        }
+
+        Ok(())
+    }
+}
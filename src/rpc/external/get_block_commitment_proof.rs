@@ -0,0 +1,26 @@
+use crate::rpc::prelude::*;
+
+/// Returns the proof that the commitment of a block is part of the aggregate
+/// commitment submitted for its range. The proof can be checked with
+/// [`BlockCommitmentProof::verify`] against the aggregate registered on the
+/// validation service.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetBlockCommitmentProof {
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+}
+
+impl RpcParameter<AppState> for GetBlockCommitmentProof {
+    type Response = BlockCommitmentProof;
+
+    fn method() -> &'static str {
+        "get_block_commitment_proof"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let block_commitment_proof =
+            BlockCommitmentProof::get(&self.rollup_id, self.rollup_block_height)?;
+
+        Ok(block_commitment_proof)
+    }
+}
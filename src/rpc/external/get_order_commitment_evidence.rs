@@ -0,0 +1,32 @@
+use crate::rpc::prelude::*;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetOrderCommitmentEvidence {
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetOrderCommitmentEvidenceResponse {
+    pub evidence_list: Vec<OrderCommitmentEvidence>,
+}
+
+impl RpcParameter<AppState> for GetOrderCommitmentEvidence {
+    type Response = GetOrderCommitmentEvidenceResponse;
+
+    fn method() -> &'static str {
+        "get_order_commitment_evidence"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let order_commitment_evidence_list = OrderCommitmentEvidenceList::get_or(
+            &self.rollup_id,
+            self.rollup_block_height,
+            OrderCommitmentEvidenceList::default,
+        )?;
+
+        Ok(GetOrderCommitmentEvidenceResponse {
+            evidence_list: order_commitment_evidence_list.evidence_list,
+        })
+    }
+}
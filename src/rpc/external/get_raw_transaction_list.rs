@@ -21,12 +21,17 @@ impl RpcParameter<AppState> for GetRawTransactionList {
     async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
         let block = Block::get(&self.rollup_id, self.rollup_block_height)?;
 
+        // Bundles are flattened in place so that their inner transactions stay
+        // contiguous and in order.
         let raw_transaction_list: Vec<String> = block
             .raw_transaction_list
             .into_iter()
-            .map(|transaction| match transaction {
-                RawTransaction::Eth(EthRawTransaction(data)) => data,
-                RawTransaction::EthBundle(EthRawBundleTransaction(data)) => data,
+            .flat_map(|transaction| match transaction {
+                RawTransaction::Eth(EthRawTransaction(data)) => vec![data],
+                RawTransaction::EthBundle(EthRawBundleTransaction(data)) => data
+                    .into_iter()
+                    .map(|EthRawTransaction(data)| data)
+                    .collect(),
             })
             .collect();
 
@@ -0,0 +1,97 @@
+use crate::rpc::prelude::*;
+
+/// Returns the merkle proof that the transaction at `transaction_order` is
+/// part of the committed block. The proof can be checked with
+/// [`verify_transaction_inclusion_proof`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionInclusionProof {
+    pub rollup_id: String,
+    pub block_height: u64,
+    pub transaction_order: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionInclusionProofResponse {
+    pub raw_transaction_hash: RawTransactionHash,
+    pub leaf: String,
+
+    #[serde(
+        serialize_with = "serialize_merkle_path",
+        deserialize_with = "deserialize_merkle_path"
+    )]
+    pub merkle_path: Vec<[u8; 32]>,
+    pub block_commitment: BlockCommitment,
+}
+
+impl RpcParameter<AppState> for GetTransactionInclusionProof {
+    type Response = GetTransactionInclusionProofResponse;
+
+    fn method() -> &'static str {
+        "get_transaction_inclusion_proof"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let block = Block::get(&self.rollup_id, self.block_height)?;
+
+        let raw_transaction_hash = block
+            .raw_transaction_list
+            .get(self.transaction_order as usize)
+            .ok_or(Error::InvalidTransactionOrder)?
+            .raw_transaction_hash()?;
+
+        // Rebuild the block tree the same way the block builder does.
+        let merkle_tree = MerkleTree::new();
+        for raw_transaction in block.raw_transaction_list.iter() {
+            merkle_tree
+                .add_data(raw_transaction.raw_transaction_hash()?.as_ref())
+                .await;
+        }
+        merkle_tree.finalize_tree().await;
+
+        let merkle_root = merkle_tree.get_merkle_root().await;
+        if BlockCommitment::from(merkle_root) != block.block_commitment {
+            tracing::error!(
+                "Block commitment mismatch - rollup_id: {:?} / block_height: {:?}",
+                self.rollup_id,
+                self.block_height
+            );
+            return Err(Error::BlockCommitmentMismatch)?;
+        }
+
+        let merkle_path = merkle_tree
+            .get_merkle_path(self.transaction_order as usize)
+            .await;
+
+        Ok(GetTransactionInclusionProofResponse {
+            leaf: const_hex::encode_prefixed(MerkleTree::leaf_hash(raw_transaction_hash.as_ref())),
+            raw_transaction_hash,
+            merkle_path,
+            block_commitment: block.block_commitment,
+        })
+    }
+}
+
+/// Verifies an inclusion proof returned by `get_transaction_inclusion_proof`
+/// against the block commitment the caller trusts.
+pub fn verify_transaction_inclusion_proof(
+    raw_transaction_hash: &RawTransactionHash,
+    transaction_order: u64,
+    merkle_path: &[[u8; 32]],
+    block_commitment: &BlockCommitment,
+) -> bool {
+    let merkle_root: [u8; 32] = match block_commitment
+        .as_bytes()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+    {
+        Some(merkle_root) => merkle_root,
+        None => return false,
+    };
+
+    MerkleTree::verify_merkle_path(
+        MerkleTree::leaf_hash(raw_transaction_hash.as_ref()),
+        transaction_order as usize,
+        merkle_path,
+        merkle_root,
+    )
+}
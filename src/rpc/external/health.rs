@@ -0,0 +1,18 @@
+use crate::{health::HealthStatus, rpc::prelude::*};
+
+/// Answers as long as the process is up. Registered on every RPC server so
+/// that each listener can be probed on its own.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Health {}
+
+impl RpcParameter<AppState> for Health {
+    type Response = HealthStatus;
+
+    fn method() -> &'static str {
+        "health"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        Ok(HealthStatus::current())
+    }
+}
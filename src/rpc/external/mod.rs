@@ -1,29 +1,43 @@
 mod get_block;
+mod get_block_commitment_proof;
 mod get_block_height;
 mod get_encrypted_transaction_list;
 mod get_encrypted_transaction_with_order_commitment;
 mod get_encrypted_transaction_with_transaction_hash;
 mod get_order_commitment;
+mod get_order_commitment_evidence;
 mod get_raw_transaction_list;
 mod get_raw_transaction_with_order_commitment;
 mod get_raw_transaction_with_transaction_hash;
 mod get_rollup;
 mod get_rollup_metadata;
+mod get_transaction_inclusion_proof;
 mod get_version;
+mod health;
+mod ready;
 mod send_encrypted_transaction;
 mod send_raw_transaction;
+mod send_raw_transaction_bundle;
+mod verify_order_commitment;
 
 pub use get_block::*;
+pub use get_block_commitment_proof::*;
 pub use get_block_height::*;
 pub use get_encrypted_transaction_list::*;
 pub use get_encrypted_transaction_with_order_commitment::*;
 pub use get_encrypted_transaction_with_transaction_hash::*;
 pub use get_order_commitment::*;
+pub use get_order_commitment_evidence::*;
 pub use get_raw_transaction_list::*;
 pub use get_raw_transaction_with_order_commitment::*;
 pub use get_raw_transaction_with_transaction_hash::*;
 pub use get_rollup::*;
 pub use get_rollup_metadata::*;
+pub use get_transaction_inclusion_proof::*;
 pub use get_version::*;
+pub use health::*;
+pub use ready::*;
 pub use send_encrypted_transaction::*;
 pub use send_raw_transaction::*;
+pub use send_raw_transaction_bundle::*;
+pub use verify_order_commitment::*;
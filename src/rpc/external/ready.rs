@@ -0,0 +1,18 @@
+use crate::{health::ReadinessStatus, rpc::prelude::*};
+
+/// Reports whether the node is ready to accept traffic with a breakdown per
+/// subsystem. Registered on every RPC server.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Ready {}
+
+impl RpcParameter<AppState> for Ready {
+    type Response = ReadinessStatus;
+
+    fn method() -> &'static str {
+        "ready"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        Ok(ReadinessStatus::check(&context).await)
+    }
+}
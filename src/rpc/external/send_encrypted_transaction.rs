@@ -1,8 +1,18 @@
+use std::time::Instant;
+
+use radius_sdk::signature::PrivateKeySigner;
+
 use crate::{
+    gossip::{Gossip, GossipKey},
+    merkle_tree_manager::MerkleTreeManager,
+    metrics::ORDER_COMMITMENT_ISSUANCE_SECONDS,
     rpc::{
         cluster::{SyncEncryptedTransaction, SyncEncryptedTransactionMessage},
+        external::{order_transaction_bundle, sync_transaction_bundle, BundleTransaction},
         prelude::*,
     },
+    task::buffer_if_leader_unavailable,
+    transaction_buffer::BufferedTransaction,
     types::*,
 };
 
@@ -20,7 +30,28 @@ impl RpcParameter<AppState> for SendEncryptedTransaction {
     }
 
     async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let rollup_id = self.rollup_id.clone();
+        let result = self.send_encrypted_transaction(context.clone()).await;
+
+        context
+            .metrics()
+            .record_transaction(&rollup_id, "encrypted", result.is_ok());
+
+        result
+    }
+}
+
+impl SendEncryptedTransaction {
+    async fn send_encrypted_transaction(
+        self,
+        context: AppState,
+    ) -> Result<OrderCommitment, RpcError> {
+        let _guard = context.shutdown().enter_transaction(&self.rollup_id)?;
+
         let rollup = Rollup::get(&self.rollup_id)?;
+        if !rollup.is_active {
+            return Err(Error::RollupInactive)?;
+        }
 
         // 1. Check supported encrypted transaction
         check_supported_encrypted_transaction(&rollup, &self.encrypted_transaction)?;
@@ -38,8 +69,36 @@ impl RpcParameter<AppState> for SendEncryptedTransaction {
         let rollup_block_height = rollup_metadata.rollup_block_height;
 
         if rollup_metadata.is_leader {
+            // The inner transactions of a bundle take consecutive orders.
+            if let TransactionData::EthBundle(_) = self.encrypted_transaction.transaction_data() {
+                let transaction_list = vec![BundleTransaction::Encrypted(
+                    self.encrypted_transaction.clone(),
+                )];
+                let (transaction_order, order_commitment) = order_transaction_bundle(
+                    &context,
+                    &rollup,
+                    &mut rollup_metadata,
+                    &transaction_list,
+                )
+                .await?;
+                drop(rollup_metadata);
+
+                sync_transaction_bundle(
+                    cluster,
+                    context.clone(),
+                    rollup.platform,
+                    self.rollup_id.clone(),
+                    rollup_block_height,
+                    transaction_order,
+                    transaction_list,
+                    order_commitment.clone(),
+                );
+
+                return Ok(order_commitment);
+            }
+
             let transaction_order = rollup_metadata.transaction_order;
-            let transaction_hash = self.encrypted_transaction.raw_transaction_hash();
+            let transaction_hash = self.encrypted_transaction.raw_transaction_hash()?;
 
             if rollup_metadata.max_gas_limit != 0
                 && rollup_metadata.current_gas + transaction_gas_limit
@@ -67,6 +126,14 @@ impl RpcParameter<AppState> for SendEncryptedTransaction {
             rollup_metadata.current_gas += transaction_gas_limit;
             rollup_metadata.transaction_order += 1;
             rollup_metadata.update()?;
+            MerkleTreeManager::checkpoint_if_due(
+                &merkle_tree,
+                &self.rollup_id,
+                rollup_block_height,
+                transaction_order + 1,
+                context.config().merkle_tree_checkpoint_interval,
+            )
+            .await;
             drop(merkle_tree);
 
             let order_commitment = issue_order_commitment(
@@ -106,7 +173,7 @@ impl RpcParameter<AppState> for SendEncryptedTransaction {
             match context
                 .rpc_client()
                 .request(
-                    leader_external_rpc_url,
+                    leader_external_rpc_url.clone(),
                     SendEncryptedTransaction::method(),
                     &self,
                     Id::Null,
@@ -119,6 +186,18 @@ impl RpcParameter<AppState> for SendEncryptedTransaction {
                         "Send encrypted transaction - leader external rpc error: {:?}",
                         error
                     );
+
+                    if buffer_if_leader_unavailable(
+                        &context,
+                        &leader_external_rpc_url,
+                        &self.rollup_id,
+                        BufferedTransaction::Encrypted(self.encrypted_transaction),
+                    )
+                    .await?
+                    {
+                        return Err(Error::LeaderUnavailable)?;
+                    }
+
                     Err(error.into())
                 }
             }
@@ -126,18 +205,22 @@ impl RpcParameter<AppState> for SendEncryptedTransaction {
     }
 }
 
-fn check_supported_encrypted_transaction(
+/// Checks that the rollup accepts the kind of encrypted transaction.
+pub fn check_supported_encrypted_transaction(
     rollup: &Rollup,
     encrypted_transaction: &EncryptedTransaction,
 ) -> Result<(), Error> {
     match rollup.encrypted_transaction_type {
-        EncryptedTransactionType::Pvde => {}
         EncryptedTransactionType::Skde => {
             if !matches!(encrypted_transaction, EncryptedTransaction::Skde(_)) {
                 return Err(Error::UnsupportedEncryptedMempool);
             }
         }
-        EncryptedTransactionType::NotSupport => return Err(Error::UnsupportedEncryptedMempool),
+        // PVDE rollups are refused until the sequencer verifies the PVDE
+        // puzzle and encryption proofs that clients attach.
+        EncryptedTransactionType::Pvde | EncryptedTransactionType::NotSupport => {
+            return Err(Error::UnsupportedEncryptedMempool)
+        }
     };
 
     Ok(())
@@ -158,6 +241,12 @@ pub fn sync_encrypted_transaction(
         let other_cluster_rpc_url_list: Vec<String> = cluster.get_others_cluster_rpc_url_list();
 
         if !other_cluster_rpc_url_list.is_empty() {
+            let key = GossipKey::new(
+                SyncEncryptedTransaction::method(),
+                &rollup_id,
+                rollup_block_height,
+                Some(transaction_order),
+            );
             let message = SyncEncryptedTransactionMessage {
                 rollup_id,
                 rollup_block_height,
@@ -165,24 +254,22 @@ pub fn sync_encrypted_transaction(
                 encrypted_transaction,
                 order_commitment,
             };
-            let signature = context
-                .get_signer(platform)
-                .await
-                .unwrap()
-                .sign_message(&message)
-                .unwrap();
+            let signature = match context.get_signer(platform).await {
+                Ok(signer) => match signer.sign_message(&message) {
+                    Ok(signature) => signature,
+                    Err(error) => {
+                        tracing::error!("Failed to sign the sync message: {:?}", error);
+                        return;
+                    }
+                },
+                Err(error) => {
+                    tracing::error!("Signer not found: {:?}", error);
+                    return;
+                }
+            };
             let rpc_self = SyncEncryptedTransaction { message, signature };
 
-            context
-                .rpc_client()
-                .multicast(
-                    other_cluster_rpc_url_list,
-                    SyncEncryptedTransaction::method(),
-                    &rpc_self,
-                    Id::Null,
-                )
-                .await
-                .unwrap();
+            Gossip::broadcast(&context, other_cluster_rpc_url_list, key, &rpc_self).await;
         }
     });
 }
@@ -198,12 +285,39 @@ pub async fn issue_order_commitment(
     transaction_order: u64,
     pre_merkle_path: Vec<[u8; 32]>,
 ) -> Result<OrderCommitment, RpcError> {
-    match order_commitment_type {
-        OrderCommitmentType::TransactionHash => Ok(OrderCommitment::Single(
-            SingleOrderCommitment::TransactionHash(TransactionHashOrderCommitment::new(
-                transaction_hash.as_string(),
-            )),
-        )),
+    Ok(OrderCommitment::Single(
+        issue_single_order_commitment(
+            context,
+            platform,
+            rollup_id,
+            order_commitment_type,
+            transaction_hash,
+            rollup_block_height,
+            transaction_order,
+            pre_merkle_path,
+        )
+        .await?,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn issue_single_order_commitment(
+    context: AppState,
+    platform: Platform,
+    rollup_id: String,
+    order_commitment_type: OrderCommitmentType,
+    transaction_hash: RawTransactionHash,
+    rollup_block_height: u64,
+    transaction_order: u64,
+    pre_merkle_path: Vec<[u8; 32]>,
+) -> Result<SingleOrderCommitment, RpcError> {
+    let started_at = Instant::now();
+    let metric_rollup_id = rollup_id.clone();
+
+    let order_commitment = match order_commitment_type {
+        OrderCommitmentType::TransactionHash => SingleOrderCommitment::TransactionHash(
+            TransactionHashOrderCommitment::new(transaction_hash.as_string()),
+        ),
         OrderCommitmentType::Sign => {
             let signer = context.get_signer(platform).await?;
             let order_commitment_data = OrderCommitmentData {
@@ -211,16 +325,175 @@ pub async fn issue_order_commitment(
                 block_height: rollup_block_height,
                 transaction_hash: transaction_hash.as_string(),
                 transaction_order,
-                pre_merkle_path: pre_merkle_path,
-            };
-            let order_commitment = SignOrderCommitment {
-                data: order_commitment_data.clone(),
-                signature: signer.sign_message(&order_commitment_data)?.as_hex_string(),
+                pre_merkle_path,
             };
 
-            Ok(OrderCommitment::Single(SingleOrderCommitment::Sign(
-                order_commitment,
-            )))
+            sign_order_commitment_data(&signer, order_commitment_data)?
+        }
+    };
+
+    context.metrics().observe_duration(
+        ORDER_COMMITMENT_ISSUANCE_SECONDS,
+        &[("rollup_id", metric_rollup_id.as_str())],
+        started_at.elapsed(),
+    );
+
+    Ok(order_commitment)
+}
+
+/// Adds a merkle leaf for every transaction of a bundle, the first one at
+/// `first_transaction_order`, and issues the order commitment of every leaf.
+/// The leader signs the list as a whole so that the bundle cannot be split.
+#[allow(clippy::too_many_arguments)]
+pub async fn commit_transaction_bundle(
+    signer: &PrivateKeySigner,
+    merkle_tree: &MerkleTree,
+    rollup_id: &str,
+    order_commitment_type: OrderCommitmentType,
+    rollup_block_height: u64,
+    first_transaction_order: u64,
+    transaction_hash_list: &[RawTransactionHash],
+) -> Result<BundleOrderCommitment, Error> {
+    let mut order_commitment_list = Vec::with_capacity(transaction_hash_list.len());
+    for (index, transaction_hash) in transaction_hash_list.iter().enumerate() {
+        let (_, pre_merkle_path) = merkle_tree.add_data(transaction_hash.as_ref()).await;
+
+        let order_commitment = match order_commitment_type {
+            OrderCommitmentType::TransactionHash => SingleOrderCommitment::TransactionHash(
+                TransactionHashOrderCommitment::new(transaction_hash.clone().as_string()),
+            ),
+            OrderCommitmentType::Sign => sign_order_commitment_data(
+                signer,
+                OrderCommitmentData {
+                    rollup_id: rollup_id.to_owned(),
+                    block_height: rollup_block_height,
+                    transaction_hash: transaction_hash.clone().as_string(),
+                    transaction_order: first_transaction_order + index as u64,
+                    pre_merkle_path,
+                },
+            )?,
+        };
+
+        order_commitment_list.push(order_commitment);
+    }
+
+    let signature = signer
+        .sign_message(&order_commitment_list)
+        .map_err(Error::Signature)?;
+
+    Ok(BundleOrderCommitment {
+        order_commitment_list,
+        signature,
+    })
+}
+
+fn sign_order_commitment_data(
+    signer: &PrivateKeySigner,
+    order_commitment_data: OrderCommitmentData,
+) -> Result<SingleOrderCommitment, Error> {
+    let signature = signer
+        .sign_message(&order_commitment_data)
+        .map_err(Error::Signature)?
+        .as_hex_string();
+
+    Ok(SingleOrderCommitment::Sign(SignOrderCommitment {
+        data: order_commitment_data,
+        signature,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers_core::types as eth_types;
+    use radius_sdk::signature::ChainType;
+
+    use super::*;
+
+    const LEADER_SIGNING_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn encrypted_bundle_transaction(transaction_count: u8) -> EncryptedTransaction {
+        let open_data_list = (1..=transaction_count)
+            .map(|byte| {
+                EthOpenData::from(eth_types::Transaction {
+                    hash: eth_types::H256::from([byte; 32]),
+                    gas: eth_types::U256::from(21_000),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        EncryptedTransaction::Skde(SkdeEncryptedTransaction::new(
+            TransactionData::from(EthBundleTransactionData::new(
+                EncryptedData::from("0x00".to_owned()),
+                EthBundleOpenData::new(open_data_list),
+            )),
+            0,
+        ))
+    }
+
+    #[tokio::test]
+    async fn commits_every_transaction_of_an_encrypted_bundle_at_its_own_order() {
+        let leader = PrivateKeySigner::from_str(ChainType::Ethereum, LEADER_SIGNING_KEY).unwrap();
+        let encrypted_transaction = encrypted_bundle_transaction(3);
+        let transaction_hash_list = encrypted_transaction.raw_transaction_hash_list();
+        assert_eq!(transaction_hash_list.len(), 3);
+
+        // Two transactions are ordered before the bundle.
+        let merkle_tree = MerkleTree::new();
+        let expected_merkle_tree = MerkleTree::new();
+        for byte in [0xa, 0xb] {
+            let transaction_hash = RawTransactionHash::from([byte; 32]);
+            merkle_tree.add_data(transaction_hash.as_ref()).await;
+            expected_merkle_tree
+                .add_data(transaction_hash.as_ref())
+                .await;
+        }
+
+        let bundle_order_commitment = commit_transaction_bundle(
+            &leader,
+            &merkle_tree,
+            "rollup_id",
+            OrderCommitmentType::Sign,
+            7,
+            2,
+            &transaction_hash_list,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(merkle_tree.leaf_count().await, 5);
+        bundle_order_commitment
+            .signature
+            .verify_message(
+                ChainType::Ethereum,
+                &bundle_order_commitment.order_commitment_list,
+                leader.address(),
+            )
+            .unwrap();
+
+        let order_commitment = OrderCommitment::Bundle(bundle_order_commitment);
+        for (index, transaction_hash) in transaction_hash_list.into_iter().enumerate() {
+            let transaction_order = 2 + index as u64;
+            let (_, pre_merkle_path) = expected_merkle_tree
+                .add_data(transaction_hash.as_ref())
+                .await;
+
+            let sign_order_commitment_list =
+                order_commitment.sign_order_commitment_list(transaction_order);
+            assert_eq!(sign_order_commitment_list.len(), 1);
+
+            let sign_order_commitment = sign_order_commitment_list[0];
+            assert_eq!(sign_order_commitment.data.block_height, 7);
+            assert_eq!(
+                sign_order_commitment.data.transaction_hash,
+                transaction_hash.as_string()
+            );
+            assert_eq!(sign_order_commitment.data.pre_merkle_path, pre_merkle_path);
+            sign_order_commitment
+                .verify(ChainType::Ethereum, leader.address(), None)
+                .await
+                .unwrap();
         }
     }
 }
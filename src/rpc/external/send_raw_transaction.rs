@@ -1,9 +1,16 @@
 use crate::{
+    gossip::{Gossip, GossipKey},
+    merkle_tree_manager::MerkleTreeManager,
     rpc::{
         cluster::{SyncRawTransaction, SyncRawTransactionMessage},
-        external::issue_order_commitment,
+        external::{
+            issue_order_commitment, order_transaction_bundle, sync_transaction_bundle,
+            BundleTransaction,
+        },
         prelude::*,
     },
+    task::buffer_if_leader_unavailable,
+    transaction_buffer::BufferedTransaction,
     types::*,
 };
 
@@ -21,6 +28,21 @@ impl RpcParameter<AppState> for SendRawTransaction {
     }
 
     async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let rollup_id = self.rollup_id.clone();
+        let result = self.send_raw_transaction(context.clone()).await;
+
+        context
+            .metrics()
+            .record_transaction(&rollup_id, "raw", result.is_ok());
+
+        result
+    }
+}
+
+impl SendRawTransaction {
+    async fn send_raw_transaction(self, context: AppState) -> Result<OrderCommitment, RpcError> {
+        let _guard = context.shutdown().enter_transaction(&self.rollup_id)?;
+
         // tracing::info!(
         //     "Send raw transaction: rollup_id: {:?}, raw_transaction: {:?}",
         //     self.rollup_id,
@@ -30,6 +52,9 @@ impl RpcParameter<AppState> for SendRawTransaction {
         let transaction_gas_limit = self.raw_transaction.get_transaction_gas_limit()?;
 
         let rollup = Rollup::get(&self.rollup_id)?;
+        if !rollup.is_active {
+            return Err(Error::RollupInactive)?;
+        }
         let mut rollup_metadata = RollupMetadata::get_mut(&self.rollup_id)?;
         let cluster = Cluster::get(
             rollup.platform,
@@ -46,8 +71,41 @@ impl RpcParameter<AppState> for SendRawTransaction {
         );
 
         if rollup_metadata.is_leader {
+            // The transactions of a bundle take consecutive orders.
+            if let RawTransaction::EthBundle(eth_raw_bundle_transaction) = &self.raw_transaction {
+                let transaction_list: Vec<BundleTransaction> = eth_raw_bundle_transaction
+                    .0
+                    .iter()
+                    .cloned()
+                    .map(|eth_raw_transaction| {
+                        BundleTransaction::Raw(RawTransaction::Eth(eth_raw_transaction))
+                    })
+                    .collect();
+                let (transaction_order, order_commitment) = order_transaction_bundle(
+                    &context,
+                    &rollup,
+                    &mut rollup_metadata,
+                    &transaction_list,
+                )
+                .await?;
+                drop(rollup_metadata);
+
+                sync_transaction_bundle(
+                    cluster,
+                    context.clone(),
+                    rollup.platform,
+                    self.rollup_id.clone(),
+                    rollup_block_height,
+                    transaction_order,
+                    transaction_list,
+                    order_commitment.clone(),
+                );
+
+                return Ok(order_commitment);
+            }
+
             let transaction_order = rollup_metadata.transaction_order;
-            let transaction_hash = self.raw_transaction.raw_transaction_hash();
+            let transaction_hash = self.raw_transaction.raw_transaction_hash()?;
 
             if rollup_metadata.max_gas_limit != 0
                 && rollup_metadata.current_gas + transaction_gas_limit
@@ -84,6 +142,14 @@ impl RpcParameter<AppState> for SendRawTransaction {
             rollup_metadata.current_gas += transaction_gas_limit;
             rollup_metadata.transaction_order += 1;
             rollup_metadata.update()?;
+            MerkleTreeManager::checkpoint_if_due(
+                &merkle_tree,
+                &self.rollup_id,
+                rollup_block_height,
+                transaction_order + 1,
+                context.config().merkle_tree_checkpoint_interval,
+            )
+            .await;
             drop(merkle_tree);
 
             let order_commitment = issue_order_commitment(
@@ -118,13 +184,13 @@ impl RpcParameter<AppState> for SendRawTransaction {
                 order_commitment.clone()
             );
 
-            match rollup.order_commitment_type {
-                OrderCommitmentType::TransactionHash => Ok(OrderCommitment::Single(
-                    SingleOrderCommitment::TransactionHash(TransactionHashOrderCommitment::new(
-                        transaction_hash.as_string(),
+            match (rollup.order_commitment_type, &order_commitment) {
+                (OrderCommitmentType::TransactionHash, OrderCommitment::Single(_)) => Ok(
+                    OrderCommitment::Single(SingleOrderCommitment::TransactionHash(
+                        TransactionHashOrderCommitment::new(transaction_hash.as_string()),
                     )),
-                )),
-                OrderCommitmentType::Sign => Ok(order_commitment),
+                ),
+                _others => Ok(order_commitment),
             }
         } else {
             let leader_external_rpc_url = rollup_metadata
@@ -137,7 +203,7 @@ impl RpcParameter<AppState> for SendRawTransaction {
             match context
                 .rpc_client()
                 .request(
-                    leader_external_rpc_url,
+                    leader_external_rpc_url.clone(),
                     SendRawTransaction::method(),
                     &self,
                     Id::Null,
@@ -150,6 +216,18 @@ impl RpcParameter<AppState> for SendRawTransaction {
                         "Send raw transaction - leader external rpc error: {:?}",
                         error
                     );
+
+                    if buffer_if_leader_unavailable(
+                        &context,
+                        &leader_external_rpc_url,
+                        &self.rollup_id,
+                        BufferedTransaction::Raw(self.raw_transaction),
+                    )
+                    .await?
+                    {
+                        return Err(Error::LeaderUnavailable)?;
+                    }
+
                     Err(error.into())
                 }
             }
@@ -173,6 +251,12 @@ pub fn sync_raw_transaction(
         let other_cluster_rpc_url_list: Vec<String> = cluster.get_others_cluster_rpc_url_list();
 
         if !other_cluster_rpc_url_list.is_empty() {
+            let key = GossipKey::new(
+                SyncRawTransaction::method(),
+                &rollup_id,
+                rollup_block_height,
+                Some(transaction_order),
+            );
             let message = SyncRawTransactionMessage {
                 rollup_id,
                 rollup_block_height,
@@ -181,24 +265,22 @@ pub fn sync_raw_transaction(
                 order_commitment: Some(order_commitment),
                 is_direct_sent,
             };
-            let signature = context
-                .get_signer(platform)
-                .await
-                .unwrap()
-                .sign_message(&message)
-                .unwrap();
+            let signature = match context.get_signer(platform).await {
+                Ok(signer) => match signer.sign_message(&message) {
+                    Ok(signature) => signature,
+                    Err(error) => {
+                        tracing::error!("Failed to sign the sync message: {:?}", error);
+                        return;
+                    }
+                },
+                Err(error) => {
+                    tracing::error!("Signer not found: {:?}", error);
+                    return;
+                }
+            };
             let rpc_self = SyncRawTransaction { message, signature };
 
-            context
-                .rpc_client()
-                .multicast(
-                    other_cluster_rpc_url_list,
-                    SyncRawTransaction::method(),
-                    &rpc_self,
-                    Id::Null,
-                )
-                .await
-                .unwrap();
+            Gossip::broadcast(&context, other_cluster_rpc_url_list, key, &rpc_self).await;
         }
     });
 }
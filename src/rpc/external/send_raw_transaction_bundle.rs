@@ -0,0 +1,547 @@
+use std::time::Instant;
+
+use radius_sdk::kvstore::Lock;
+
+use crate::{
+    merkle_tree_manager::MerkleTreeManager,
+    metrics::ORDER_COMMITMENT_ISSUANCE_SECONDS,
+    rpc::{
+        cluster::{SyncEncryptedTransactionMessage, SyncRawTransactionMessage},
+        external::{
+            check_supported_encrypted_transaction, commit_transaction_bundle,
+            sync_encrypted_transaction, sync_raw_transaction,
+        },
+        prelude::*,
+    },
+    types::*,
+};
+
+/// Orders a list of raw and encrypted transactions as one unit. Every
+/// transaction takes its own order, the orders are consecutive and the
+/// leader signs a single [`BundleOrderCommitment`] covering all of them.
+/// Nothing is kept unless the whole bundle is valid, fits in the remaining gas
+/// of the block and its order commitment has been issued.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SendRawTransactionBundle {
+    pub rollup_id: String,
+    pub transaction_list: Vec<BundleTransaction>,
+}
+
+impl RpcParameter<AppState> for SendRawTransactionBundle {
+    type Response = OrderCommitment;
+
+    fn method() -> &'static str {
+        "send_raw_transaction_bundle"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let rollup_id = self.rollup_id.clone();
+        let result = self.send_raw_transaction_bundle(context.clone()).await;
+
+        context
+            .metrics()
+            .record_transaction(&rollup_id, "bundle", result.is_ok());
+
+        result
+    }
+}
+
+impl SendRawTransactionBundle {
+    /// Checks the shape of the bundle. Bundles cannot be empty or nested,
+    /// whether the inner bundle is raw or encrypted.
+    fn check_transaction_list(&self) -> Result<(), Error> {
+        if self.transaction_list.is_empty() {
+            return Err(Error::EmptyTransactionBundle);
+        }
+
+        for transaction in self.transaction_list.iter() {
+            match transaction {
+                BundleTransaction::Raw(RawTransaction::Eth(_)) => {}
+                BundleTransaction::Raw(RawTransaction::EthBundle(_)) => {
+                    return Err(Error::NestedTransactionBundle)
+                }
+                BundleTransaction::Encrypted(encrypted_transaction) => {
+                    if let TransactionData::EthBundle(_) = encrypted_transaction.transaction_data()
+                    {
+                        return Err(Error::NestedTransactionBundle);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_raw_transaction_bundle(
+        self,
+        context: AppState,
+    ) -> Result<OrderCommitment, RpcError> {
+        let _guard = context.shutdown().enter_transaction(&self.rollup_id)?;
+
+        // Validate the whole bundle before touching the state so that a
+        // malformed transaction rejects all of it.
+        self.check_transaction_list()?;
+
+        let rollup = Rollup::get(&self.rollup_id)?;
+        if !rollup.is_active {
+            return Err(Error::RollupInactive)?;
+        }
+        for transaction in self.transaction_list.iter() {
+            if let BundleTransaction::Encrypted(encrypted_transaction) = transaction {
+                check_supported_encrypted_transaction(&rollup, encrypted_transaction)?;
+            }
+        }
+
+        let mut rollup_metadata = RollupMetadata::get_mut(&self.rollup_id)?;
+        let cluster = Cluster::get(
+            rollup.platform,
+            rollup.service_provider,
+            &rollup.cluster_id,
+            rollup_metadata.platform_block_height,
+        )?;
+        let rollup_block_height = rollup_metadata.rollup_block_height;
+
+        if rollup_metadata.is_leader {
+            let (transaction_order, order_commitment) = order_transaction_bundle(
+                &context,
+                &rollup,
+                &mut rollup_metadata,
+                &self.transaction_list,
+            )
+            .await?;
+            drop(rollup_metadata);
+
+            sync_transaction_bundle(
+                cluster,
+                context.clone(),
+                rollup.platform,
+                self.rollup_id.clone(),
+                rollup_block_height,
+                transaction_order,
+                self.transaction_list,
+                order_commitment.clone(),
+            );
+
+            Ok(order_commitment)
+        } else {
+            let leader_external_rpc_url = rollup_metadata
+                .leader_sequencer_rpc_info
+                .external_rpc_url
+                .clone()
+                .ok_or(Error::EmptyLeaderClusterRpcUrl)?;
+            drop(rollup_metadata);
+
+            match context
+                .rpc_client()
+                .request(
+                    leader_external_rpc_url,
+                    SendRawTransactionBundle::method(),
+                    &self,
+                    Id::Null,
+                )
+                .await
+            {
+                Ok(response) => Ok(response),
+                Err(error) => {
+                    tracing::error!(
+                        "Send raw transaction bundle - leader external rpc error: {:?}",
+                        error
+                    );
+                    Err(error.into())
+                }
+            }
+        }
+    }
+}
+
+/// A transaction ordered as part of a bundle.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum BundleTransaction {
+    Raw(RawTransaction),
+    Encrypted(EncryptedTransaction),
+}
+
+impl BundleTransaction {
+    /// Returns the merkle leaf of every order the transaction takes. An
+    /// encrypted bundle takes one order per inner transaction, while a raw
+    /// bundle has to be split into its transactions first.
+    pub fn raw_transaction_hash_list(&self) -> Result<Vec<RawTransactionHash>, Error> {
+        match self {
+            Self::Raw(RawTransaction::Eth(eth_raw_transaction)) => {
+                Ok(vec![eth_raw_transaction.raw_transaction_hash()?])
+            }
+            Self::Raw(RawTransaction::EthBundle(_)) => Err(Error::NestedTransactionBundle),
+            Self::Encrypted(encrypted_transaction) => {
+                let transaction_hash_list = encrypted_transaction.raw_transaction_hash_list();
+                if transaction_hash_list.is_empty() {
+                    return Err(Error::EmptyTransactionBundle);
+                }
+
+                Ok(transaction_hash_list)
+            }
+        }
+    }
+
+    /// Returns the number of orders the transaction takes.
+    pub fn transaction_count(&self) -> u64 {
+        match self {
+            Self::Raw(_) => 1,
+            Self::Encrypted(encrypted_transaction) => {
+                encrypted_transaction.raw_transaction_hash_list().len() as u64
+            }
+        }
+    }
+
+    pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
+        match self {
+            Self::Raw(raw_transaction) => raw_transaction.get_transaction_gas_limit(),
+            Self::Encrypted(encrypted_transaction) => {
+                encrypted_transaction.get_transaction_gas_limit()
+            }
+        }
+    }
+}
+
+/// Orders `transaction_list` at consecutive orders of the current block and
+/// returns the first order along with the leader-signed
+/// [`BundleOrderCommitment`] covering every order. The caller holds the lock
+/// of `rollup_metadata`, so no other transaction can take an order in
+/// between. Nothing is kept unless the whole bundle is valid, fits in the
+/// remaining gas of the block and is stored.
+pub async fn order_transaction_bundle(
+    context: &AppState,
+    rollup: &Rollup,
+    rollup_metadata: &mut Lock<'static, RollupMetadata>,
+    transaction_list: &[BundleTransaction],
+) -> Result<(u64, OrderCommitment), RpcError> {
+    // Validate the whole bundle before touching the state so that a
+    // malformed transaction rejects all of it.
+    let mut transaction_hash_list = Vec::new();
+    let mut bundle_gas_limit: u64 = 0;
+    for transaction in transaction_list.iter() {
+        transaction_hash_list.extend(transaction.raw_transaction_hash_list()?);
+        bundle_gas_limit = bundle_gas_limit
+            .checked_add(transaction.get_transaction_gas_limit()?)
+            .ok_or(Error::ExceedMaxGasLimit)?;
+    }
+    if transaction_hash_list.is_empty() {
+        return Err(Error::EmptyTransactionBundle)?;
+    }
+
+    let current_gas = rollup_metadata
+        .current_gas
+        .checked_add(bundle_gas_limit)
+        .ok_or(Error::ExceedMaxGasLimit)?;
+    if rollup_metadata.max_gas_limit != 0 && current_gas > rollup_metadata.max_gas_limit {
+        return Err(Error::ExceedMaxGasLimit)?;
+    }
+
+    let rollup_block_height = rollup_metadata.rollup_block_height;
+    let first_transaction_order = rollup_metadata.transaction_order;
+    let transaction_count = transaction_hash_list.len() as u64;
+
+    let signer = context.get_signer(rollup.platform).await?;
+    let merkle_tree = context.merkle_tree_manager().get(&rollup.rollup_id).await?;
+
+    // The leaves are added first because the order commitments need their
+    // pre-merkle paths. Every step after it is undone on failure.
+    let merkle_tree_snapshot = merkle_tree.snapshot().await;
+    let started_at = Instant::now();
+    let order_commitment = match commit_transaction_bundle(
+        &signer,
+        &merkle_tree,
+        &rollup.rollup_id,
+        rollup.order_commitment_type,
+        rollup_block_height,
+        first_transaction_order,
+        &transaction_hash_list,
+    )
+    .await
+    {
+        Ok(bundle_order_commitment) => OrderCommitment::Bundle(bundle_order_commitment),
+        Err(error) => {
+            merkle_tree.rewind(merkle_tree_snapshot).await;
+            return Err(error)?;
+        }
+    };
+    context.metrics().observe_duration(
+        ORDER_COMMITMENT_ISSUANCE_SECONDS,
+        &[("rollup_id", rollup.rollup_id.as_str())],
+        started_at.elapsed(),
+    );
+
+    let result = store_transaction_bundle(
+        &rollup.rollup_id,
+        rollup_block_height,
+        first_transaction_order,
+        transaction_list,
+        &order_commitment,
+    )
+    .and_then(|_| {
+        rollup_metadata.current_gas = current_gas;
+        rollup_metadata.transaction_order += transaction_count;
+        rollup_metadata.update().map_err(Error::Database)
+    });
+    if let Err(error) = result {
+        merkle_tree.rewind(merkle_tree_snapshot).await;
+        delete_transaction_bundle(
+            &rollup.rollup_id,
+            rollup_block_height,
+            first_transaction_order,
+            transaction_list,
+        );
+
+        return Err(error)?;
+    }
+
+    MerkleTreeManager::checkpoint_if_due(
+        &merkle_tree,
+        &rollup.rollup_id,
+        rollup_block_height,
+        first_transaction_order + transaction_count,
+        context.config().merkle_tree_checkpoint_interval,
+    )
+    .await;
+
+    Ok((first_transaction_order, order_commitment))
+}
+
+/// Syncs every transaction of a bundle ordered by
+/// [`order_transaction_bundle`] at the first order it takes.
+#[allow(clippy::too_many_arguments)]
+pub fn sync_transaction_bundle(
+    cluster: Cluster,
+    context: AppState,
+    platform: Platform,
+    rollup_id: String,
+    rollup_block_height: u64,
+    first_transaction_order: u64,
+    transaction_list: Vec<BundleTransaction>,
+    order_commitment: OrderCommitment,
+) {
+    let mut transaction_order = first_transaction_order;
+    for transaction in transaction_list {
+        let transaction_count = transaction.transaction_count();
+
+        match transaction {
+            BundleTransaction::Raw(raw_transaction) => sync_raw_transaction(
+                cluster.clone(),
+                context.clone(),
+                platform,
+                rollup_id.clone(),
+                rollup_block_height,
+                transaction_order,
+                raw_transaction,
+                order_commitment.clone(),
+                true,
+            ),
+            BundleTransaction::Encrypted(encrypted_transaction) => sync_encrypted_transaction(
+                cluster.clone(),
+                context.clone(),
+                platform,
+                rollup_id.clone(),
+                rollup_block_height,
+                transaction_order,
+                encrypted_transaction,
+                order_commitment.clone(),
+            ),
+        }
+
+        transaction_order += transaction_count;
+    }
+}
+
+/// Stores every transaction of the bundle at the first order it takes and
+/// the order commitment at every order, the way the sync handlers do.
+fn store_transaction_bundle(
+    rollup_id: &str,
+    rollup_block_height: u64,
+    first_transaction_order: u64,
+    transaction_list: &[BundleTransaction],
+    order_commitment: &OrderCommitment,
+) -> Result<(), Error> {
+    let mut transaction_order = first_transaction_order;
+    for transaction in transaction_list.iter() {
+        match transaction {
+            BundleTransaction::Raw(raw_transaction) => SyncRawTransactionMessage {
+                rollup_id: rollup_id.to_owned(),
+                rollup_block_height,
+                transaction_order,
+                raw_transaction: raw_transaction.clone(),
+                order_commitment: Some(order_commitment.clone()),
+                is_direct_sent: true,
+            }
+            .store()?,
+            BundleTransaction::Encrypted(encrypted_transaction) => {
+                SyncEncryptedTransactionMessage {
+                    rollup_id: rollup_id.to_owned(),
+                    rollup_block_height,
+                    transaction_order,
+                    encrypted_transaction: encrypted_transaction.clone(),
+                    order_commitment: order_commitment.clone(),
+                }
+                .store()?
+            }
+        }
+
+        transaction_order += transaction.transaction_count();
+    }
+
+    Ok(())
+}
+
+/// Removes whatever [`store_transaction_bundle`] managed to write before it
+/// failed.
+fn delete_transaction_bundle(
+    rollup_id: &str,
+    rollup_block_height: u64,
+    first_transaction_order: u64,
+    transaction_list: &[BundleTransaction],
+) {
+    let mut transaction_order = first_transaction_order;
+    for transaction in transaction_list.iter() {
+        let transaction_count = transaction.transaction_count();
+        let transaction_hash_list = transaction.raw_transaction_hash_list().unwrap_or_default();
+
+        let result = match transaction {
+            BundleTransaction::Raw(_) => transaction_hash_list
+                .iter()
+                .try_for_each(|transaction_hash| {
+                    RawTransactionModel::delete_with_transaction_hash(rollup_id, transaction_hash)
+                })
+                .and_then(|_| {
+                    RawTransactionModel::delete(rollup_id, rollup_block_height, transaction_order)
+                }),
+            BundleTransaction::Encrypted(_) => transaction_hash_list
+                .iter()
+                .try_for_each(|transaction_hash| {
+                    EncryptedTransactionModel::delete_with_transaction_hash(
+                        rollup_id,
+                        transaction_hash,
+                    )
+                })
+                .and_then(|_| {
+                    EncryptedTransactionModel::delete(
+                        rollup_id,
+                        rollup_block_height,
+                        transaction_order,
+                    )
+                }),
+        }
+        .and_then(|_| {
+            (transaction_order..transaction_order + transaction_count).try_for_each(
+                |transaction_order| {
+                    OrderCommitment::delete(rollup_id, rollup_block_height, transaction_order)
+                },
+            )
+        });
+
+        if let Err(error) = result {
+            tracing::error!(
+                "Failed to roll back the bundle - rollup_id: {:?} / rollup_block_height: {:?} / transaction_order: {:?} / error: {:?}",
+                rollup_id,
+                rollup_block_height,
+                transaction_order,
+                error
+            );
+        }
+
+        transaction_order += transaction_count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers_core::types as eth_types;
+
+    use super::*;
+
+    fn send_raw_transaction_bundle(
+        transaction_list: Vec<BundleTransaction>,
+    ) -> SendRawTransactionBundle {
+        SendRawTransactionBundle {
+            rollup_id: "rollup_id".to_owned(),
+            transaction_list,
+        }
+    }
+
+    fn raw_transaction(raw_transaction: &str) -> BundleTransaction {
+        BundleTransaction::Raw(RawTransaction::Eth(EthRawTransaction::from(
+            raw_transaction.to_owned(),
+        )))
+    }
+
+    fn eth_open_data(byte: u8) -> EthOpenData {
+        EthOpenData::from(eth_types::Transaction {
+            hash: eth_types::H256::from([byte; 32]),
+            gas: eth_types::U256::from(21_000),
+            ..Default::default()
+        })
+    }
+
+    fn encrypted_transaction(transaction_data: TransactionData) -> BundleTransaction {
+        BundleTransaction::Encrypted(EncryptedTransaction::Skde(SkdeEncryptedTransaction::new(
+            transaction_data,
+            0,
+        )))
+    }
+
+    #[test]
+    fn accepts_raw_and_encrypted_transactions() {
+        let bundle = send_raw_transaction_bundle(vec![
+            raw_transaction("0x01"),
+            encrypted_transaction(TransactionData::from(EthTransactionData::new(
+                EncryptedData::from("0x00".to_owned()),
+                eth_open_data(1),
+            ))),
+        ]);
+
+        bundle.check_transaction_list().unwrap();
+        assert_eq!(
+            bundle
+                .transaction_list
+                .iter()
+                .map(BundleTransaction::transaction_count)
+                .sum::<u64>(),
+            2
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_bundle() {
+        assert!(matches!(
+            send_raw_transaction_bundle(Vec::new()).check_transaction_list(),
+            Err(Error::EmptyTransactionBundle)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_nested_bundle() {
+        let nested_bundle = BundleTransaction::Raw(RawTransaction::EthBundle(
+            EthRawBundleTransaction::from(vec![EthRawTransaction::from("0x01".to_owned())]),
+        ));
+
+        assert!(matches!(
+            send_raw_transaction_bundle(vec![raw_transaction("0x02"), nested_bundle])
+                .check_transaction_list(),
+            Err(Error::NestedTransactionBundle)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_nested_encrypted_bundle() {
+        let nested_bundle =
+            encrypted_transaction(TransactionData::from(EthBundleTransactionData::new(
+                EncryptedData::from("0x00".to_owned()),
+                EthBundleOpenData::new(vec![eth_open_data(1), eth_open_data(2)]),
+            )));
+
+        assert!(matches!(
+            send_raw_transaction_bundle(vec![raw_transaction("0x02"), nested_bundle])
+                .check_transaction_list(),
+            Err(Error::NestedTransactionBundle)
+        ));
+    }
+}
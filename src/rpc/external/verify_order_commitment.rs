@@ -0,0 +1,49 @@
+use crate::rpc::prelude::*;
+
+/// Verifies a signed order commitment against `leader_address` and, if this
+/// node has it, the final block of the committed height.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VerifyOrderCommitment {
+    pub order_commitment: SignOrderCommitment,
+    pub leader_address: Address,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VerifyOrderCommitmentResponse {
+    pub is_valid: bool,
+    pub is_block_checked: bool,
+    pub error: Option<String>,
+}
+
+impl RpcParameter<AppState> for VerifyOrderCommitment {
+    type Response = VerifyOrderCommitmentResponse;
+
+    fn method() -> &'static str {
+        "verify_order_commitment"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let order_commitment_data = &self.order_commitment.data;
+        let rollup = Rollup::get(&order_commitment_data.rollup_id)?;
+
+        let block = match Block::get(
+            &order_commitment_data.rollup_id,
+            order_commitment_data.block_height,
+        ) {
+            Ok(block) => Some(block),
+            Err(error) if error.is_none_type() => None,
+            Err(error) => return Err(error.into()),
+        };
+
+        let result = self
+            .order_commitment
+            .verify(rollup.platform.into(), &self.leader_address, block.as_ref())
+            .await;
+
+        Ok(VerifyOrderCommitmentResponse {
+            is_valid: result.is_ok(),
+            is_block_checked: block.is_some(),
+            error: result.err().map(|error| error.to_string()),
+        })
+    }
+}
@@ -25,8 +25,8 @@ impl RpcParameter<AppState> for AddCluster {
         );
 
         let seeder_client = context.seeder_client();
-        match self.platform {
-            Platform::Ethereum => {
+        match self.platform.liveness_backend() {
+            LivenessBackend::Radius => {
                 let signing_key = &context.config().signing_key;
                 let signer = PrivateKeySigner::from_str(self.platform.into(), signing_key)?;
 
@@ -49,8 +49,17 @@ impl RpcParameter<AppState> for AddCluster {
                 cluster_id_list.insert(&self.cluster_id);
                 cluster_id_list.update()?;
             }
-            Platform::Holesky => unimplemented!("Holesky client needs to be implemented."),
-            Platform::Local => unimplemented!("Local client needs to be implemented."),
+            LivenessBackend::Local => {
+                // Local clusters are not registered to the seeder. The cluster
+                // description is provided by the local liveness client.
+                let mut cluster_id_list = ClusterIdList::get_mut_or(
+                    self.platform,
+                    self.service_provider,
+                    ClusterIdList::default,
+                )?;
+                cluster_id_list.insert(&self.cluster_id);
+                cluster_id_list.update()?;
+            }
         }
 
         Ok(())
@@ -22,6 +22,8 @@ impl RpcParameter<AppState> for AddSequencingInfo {
             self.payload
         );
 
+        self.platform.check_sequencing_info_payload(&self.payload)?;
+
         // Save `LivenessClient` metadata.
         let mut sequencing_info_list = SequencingInfoList::get_mut_or(SequencingInfoList::default)?;
         sequencing_info_list.insert(self.platform, self.service_provider);
@@ -38,8 +40,13 @@ impl RpcParameter<AppState> for AddSequencingInfo {
                     payload.clone(),
                 );
             }
-            SequencingInfoPayload::Local(_payload) => {
-                todo!("Implement 'LivenessClient' for local sequencing.");
+            SequencingInfoPayload::Local(payload) => {
+                liveness::local::LivenessClient::initialize(
+                    context.clone(),
+                    self.platform,
+                    self.service_provider,
+                    payload.clone(),
+                );
             }
         }
 
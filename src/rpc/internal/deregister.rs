@@ -1,16 +1,28 @@
 use radius_sdk::signature::PrivateKeySigner;
 
-use crate::rpc::prelude::*;
+use crate::{rpc::prelude::*, task::purge_rollup};
 
+/// Deregisters the node from a cluster and stops sequencing its rollups.
+///
+/// The rollups are marked inactive and keep their data unless `purge` is
+/// set. A cluster loaded from a local cluster file comes back on the next
+/// reload unless it is removed from the file.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Deregister {
     pub platform: Platform,
     pub service_provider: ServiceProvider,
     pub cluster_id: String,
+    #[serde(default)]
+    pub purge: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeregisterResponse {
+    pub rollup_id_list: Vec<String>,
 }
 
 impl RpcParameter<AppState> for Deregister {
-    type Response = ();
+    type Response = DeregisterResponse;
 
     fn method() -> &'static str {
         "deregister"
@@ -18,15 +30,16 @@ impl RpcParameter<AppState> for Deregister {
 
     async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
         tracing::info!(
-            "Deregister - platform: {:?}, service provider: {:?}, cluster id: {:?}",
+            "Deregister - platform: {:?}, service provider: {:?}, cluster id: {:?}, purge: {:?}",
             self.platform,
             self.service_provider,
-            self.cluster_id
+            self.cluster_id,
+            self.purge
         );
 
         let seeder_client = context.seeder_client();
-        match self.platform {
-            Platform::Ethereum => {
+        match self.platform.liveness_backend() {
+            LivenessBackend::Radius => {
                 let signing_key = &context.config().signing_key;
                 let signer = PrivateKeySigner::from_str(self.platform.into(), signing_key)?;
 
@@ -38,16 +51,80 @@ impl RpcParameter<AppState> for Deregister {
                         &signer,
                     )
                     .await?;
+            }
+            LivenessBackend::Local => {
+                LocalClusterInfo::delete(self.platform, self.service_provider, &self.cluster_id)?;
+            }
+        }
+
+        // The liveness clients only process the clusters in the list.
+        let mut cluster_id_list = ClusterIdList::get_mut(self.platform, self.service_provider)?;
+        cluster_id_list.remove(&self.cluster_id);
+        cluster_id_list.update()?;
+
+        let rollup_id_list = self.get_rollup_id_list()?;
+        for rollup_id in rollup_id_list.iter() {
+            deactivate_rollup(&context, rollup_id, self.purge).await?;
+        }
+
+        Ok(DeregisterResponse { rollup_id_list })
+    }
+}
+
+impl Deregister {
+    fn get_rollup_id_list(&self) -> Result<Vec<String>, Error> {
+        let rollup_id_list =
+            RollupIdList::get_or(RollupIdList::default).map_err(Error::Database)?;
 
-                let mut cluster_id_list =
-                    ClusterIdList::get_mut(self.platform, self.service_provider)?;
-                cluster_id_list.remove(&self.cluster_id);
-                cluster_id_list.update()?;
+        let mut cluster_rollup_id_list = Vec::new();
+        for rollup_id in rollup_id_list.iter() {
+            let rollup = Rollup::get(rollup_id).map_err(Error::Database)?;
+
+            if rollup.platform == self.platform
+                && rollup.service_provider == self.service_provider
+                && rollup.cluster_id == self.cluster_id
+            {
+                cluster_rollup_id_list.push(rollup_id.clone());
             }
-            Platform::Holesky => unimplemented!("Holesky client needs to be implemented."),
-            Platform::Local => unimplemented!("Local client needs to be implemented."),
         }
 
-        Ok(())
+        Ok(cluster_rollup_id_list)
+    }
+}
+
+/// Stops accepting transactions for the rollup and removes it from the
+/// background tasks. Re-registering the cluster activates it again.
+async fn deactivate_rollup(context: &AppState, rollup_id: &str, purge: bool) -> Result<(), Error> {
+    let mut rollup = Rollup::get_mut(rollup_id).map_err(Error::Database)?;
+    rollup.set_is_active(false);
+    rollup.update().map_err(Error::Database)?;
+
+    let mut rollup_id_list =
+        RollupIdList::get_mut_or(RollupIdList::default).map_err(Error::Database)?;
+    rollup_id_list.remove(rollup_id);
+    rollup_id_list.update().map_err(Error::Database)?;
+
+    match RollupMetadata::get_mut(rollup_id) {
+        Ok(mut rollup_metadata) => {
+            rollup_metadata.is_leader = false;
+            rollup_metadata.update().map_err(Error::Database)?;
+        }
+        Err(error) if error.is_none_type() => {}
+        Err(error) => return Err(Error::Database(error)),
+    }
+
+    context.merkle_tree_manager().remove(rollup_id).await;
+    let buffered_transaction_list = context.transaction_buffer().drain(rollup_id).await;
+
+    tracing::info!(
+        "Deactivated the rollup - rollup_id: {:?} / dropped buffered transaction count: {:?}",
+        rollup_id,
+        buffered_transaction_list.len()
+    );
+
+    if purge {
+        purge_rollup(rollup_id)?;
     }
+
+    Ok(())
 }
@@ -0,0 +1,69 @@
+use crate::rpc::prelude::*;
+
+/// Overrides the leader of the current block with `leader_address`, which
+/// must be a member of the rollup's cluster. This node becomes the leader if
+/// the address is its own. Refused once transactions were ordered in the
+/// block, since the new leader would order others at the same orders; reset
+/// the transaction order first.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ForceLeader {
+    pub rollup_id: String,
+    pub leader_address: String,
+}
+
+impl RpcParameter<AppState> for ForceLeader {
+    type Response = RollupMetadata;
+
+    fn method() -> &'static str {
+        "force_leader"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let rollup = Rollup::get(&self.rollup_id).map_err(|_| Error::RollupNotFound)?;
+        let leader_address = Address::from_str(rollup.platform.into(), &self.leader_address)
+            .map_err(Error::Signature)?;
+
+        let mut rollup_metadata = RollupMetadata::get_mut(&self.rollup_id)?;
+        if rollup_metadata.is_leader
+            && context
+                .shutdown()
+                .in_flight_rollup_transaction_count(&self.rollup_id)
+                > 0
+        {
+            return Err(Error::RollupBusy)?;
+        }
+
+        if rollup_metadata.transaction_order > 0 {
+            return Err(Error::OrderCommitmentAlreadyIssued)?;
+        }
+
+        let cluster = Cluster::get(
+            rollup.platform,
+            rollup.service_provider,
+            &rollup.cluster_id,
+            rollup_metadata.platform_block_height,
+        )?;
+        let leader_sequencer_rpc_info = cluster
+            .get_sequencer_rpc_info(&leader_address)
+            .ok_or(Error::SequencerInfoNotFound)?;
+
+        let signer = context.get_signer(rollup.platform).await?;
+
+        let previous_rollup_metadata = RollupMetadata::clone(&rollup_metadata);
+        rollup_metadata.is_leader = *signer.address() == leader_address;
+        rollup_metadata.leader_sequencer_rpc_info = leader_sequencer_rpc_info;
+
+        let updated_rollup_metadata = RollupMetadata::clone(&rollup_metadata);
+        rollup_metadata.update()?;
+
+        tracing::warn!(
+            "Admin request - method: {:?} / rollup_id: {:?} / before: {:?} / after: {:?}",
+            Self::method(),
+            self.rollup_id,
+            previous_rollup_metadata,
+            updated_rollup_metadata
+        );
+
+        Ok(updated_rollup_metadata)
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{gossip::PeerGossipStatus, rpc::prelude::*};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetGossipStatus {}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetGossipStatusResponse {
+    pub peer_status_list: Vec<PeerGossipStatus>,
+}
+
+impl RpcParameter<AppState> for GetGossipStatus {
+    type Response = GetGossipStatusResponse;
+
+    fn method() -> &'static str {
+        "get_gossip_status"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let peer_status_list = context.gossip().peer_status_list().await;
+
+        Ok(GetGossipStatusResponse { peer_status_list })
+    }
+}
@@ -0,0 +1,16 @@
+use crate::{profiler::ProfilerStatus, rpc::prelude::*};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetProfilerStatus {}
+
+impl RpcParameter<AppState> for GetProfilerStatus {
+    type Response = ProfilerStatus;
+
+    fn method() -> &'static str {
+        "get_profiler_status"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        Ok(context.profiler().status().await)
+    }
+}
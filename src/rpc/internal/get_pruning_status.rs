@@ -0,0 +1,30 @@
+use crate::rpc::prelude::*;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetPruningStatus {
+    pub rollup_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetPruningStatusResponse {
+    pub retention_policy: RetentionPolicy,
+    pub pruning_status: PruningStatus,
+}
+
+impl RpcParameter<AppState> for GetPruningStatus {
+    type Response = GetPruningStatusResponse;
+
+    fn method() -> &'static str {
+        "get_pruning_status"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let retention_policy = RetentionPolicy::get_or(&self.rollup_id, RetentionPolicy::default)?;
+        let pruning_status = PruningStatus::get_or(&self.rollup_id, PruningStatus::default)?;
+
+        Ok(GetPruningStatusResponse {
+            retention_policy,
+            pruning_status,
+        })
+    }
+}
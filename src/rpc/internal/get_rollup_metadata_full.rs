@@ -0,0 +1,50 @@
+use crate::rpc::prelude::*;
+
+/// Returns the rollup metadata together with the in-memory state that
+/// depends on it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetRollupMetadataFull {
+    pub rollup_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetRollupMetadataFullResponse {
+    pub rollup_metadata: RollupMetadata,
+    pub has_merkle_tree: bool,
+    pub buffered_transaction_count: usize,
+    pub in_flight_transaction_count: usize,
+}
+
+impl RpcParameter<AppState> for GetRollupMetadataFull {
+    type Response = GetRollupMetadataFullResponse;
+
+    fn method() -> &'static str {
+        "get_rollup_metadata_full"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        tracing::info!(
+            "Admin request - method: {:?} / rollup_id: {:?}",
+            Self::method(),
+            self.rollup_id
+        );
+
+        let rollup_metadata = RollupMetadata::get(&self.rollup_id)?;
+        let has_merkle_tree = context
+            .merkle_tree_manager()
+            .get(&self.rollup_id)
+            .await
+            .is_ok();
+        let buffered_transaction_count = context.transaction_buffer().len(&self.rollup_id).await;
+        let in_flight_transaction_count = context
+            .shutdown()
+            .in_flight_rollup_transaction_count(&self.rollup_id);
+
+        Ok(GetRollupMetadataFullResponse {
+            rollup_metadata,
+            has_merkle_tree,
+            buffered_transaction_count,
+            in_flight_transaction_count,
+        })
+    }
+}
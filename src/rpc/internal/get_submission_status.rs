@@ -0,0 +1,42 @@
+use crate::rpc::prelude::*;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetSubmissionStatus {
+    pub rollup_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetSubmissionStatusResponse {
+    pub submission_policy: SubmissionPolicy,
+    pub submission_status: SubmissionStatus,
+    pub pending_block_commitment_list: Vec<PendingBlockCommitment>,
+}
+
+impl RpcParameter<AppState> for GetSubmissionStatus {
+    type Response = GetSubmissionStatusResponse;
+
+    fn method() -> &'static str {
+        "get_submission_status"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let submission_policy =
+            SubmissionPolicy::get_or(&self.rollup_id, SubmissionPolicy::default)?;
+        let submission_status =
+            SubmissionStatus::get_or(&self.rollup_id, SubmissionStatus::default)?;
+        let pending_block_commitment_list =
+            PendingBlockCommitmentQueue::get_or(PendingBlockCommitmentQueue::default)?
+                .iter()
+                .filter(|pending_block_commitment| {
+                    pending_block_commitment.rollup_id == self.rollup_id
+                })
+                .cloned()
+                .collect();
+
+        Ok(GetSubmissionStatusResponse {
+            submission_policy,
+            submission_status,
+            pending_block_commitment_list,
+        })
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{rpc::prelude::*, task::TaskStatus};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTaskStatus {}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTaskStatusResponse {
+    pub task_status_list: Vec<TaskStatus>,
+}
+
+impl RpcParameter<AppState> for GetTaskStatus {
+    type Response = GetTaskStatusResponse;
+
+    fn method() -> &'static str {
+        "get_task_status"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let task_status_list = context.supervisor().status_list();
+
+        Ok(GetTaskStatusResponse { task_status_list })
+    }
+}
@@ -2,18 +2,52 @@ mod add_cluster;
 mod add_sequencing_info;
 mod add_validation_info;
 mod deregister;
+mod force_leader;
 mod get_cluster;
 mod get_cluster_id_list;
+mod get_gossip_status;
+mod get_profiler_status;
+mod get_pruning_status;
+mod get_rollup_metadata_full;
 mod get_sequencing_info;
 mod get_sequencing_infos;
+mod get_submission_status;
+mod get_task_status;
+mod prune_rollup;
+mod reset_transaction_order;
+mod set_block_commitment_aggregation_size;
+mod set_local_cluster;
 mod set_max_gas_limit;
+mod set_retention_policy;
+mod set_rollup_block_height;
+mod set_submission_policy;
+mod start_profiler;
+mod stop_profiler;
+mod submit_block_commitment;
 
 pub use add_cluster::*;
 pub use add_sequencing_info::*;
 pub use add_validation_info::*;
 pub use deregister::*;
+pub use force_leader::*;
 pub use get_cluster::*;
 pub use get_cluster_id_list::*;
+pub use get_gossip_status::*;
+pub use get_profiler_status::*;
+pub use get_pruning_status::*;
+pub use get_rollup_metadata_full::*;
 pub use get_sequencing_info::*;
 pub use get_sequencing_infos::*;
+pub use get_submission_status::*;
+pub use get_task_status::*;
+pub use prune_rollup::*;
+pub use reset_transaction_order::*;
+pub use set_block_commitment_aggregation_size::*;
+pub use set_local_cluster::*;
 pub use set_max_gas_limit::*;
+pub use set_retention_policy::*;
+pub use set_rollup_block_height::*;
+pub use set_submission_policy::*;
+pub use start_profiler::*;
+pub use stop_profiler::*;
+pub use submit_block_commitment::*;
@@ -0,0 +1,28 @@
+use crate::{rpc::prelude::*, task::prune_rollup};
+
+/// Prunes a rollup according to its retention policy without waiting for the
+/// background pruner.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PruneRollup {
+    pub rollup_id: String,
+}
+
+impl RpcParameter<AppState> for PruneRollup {
+    type Response = PruningStatus;
+
+    fn method() -> &'static str {
+        "prune_rollup"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        Rollup::get(&self.rollup_id).map_err(|_| Error::RollupNotFound)?;
+
+        // Pruning walks the database block by block, so keep it off the async
+        // workers.
+        let pruning_status = tokio::task::spawn_blocking(move || prune_rollup(&self.rollup_id))
+            .await
+            .map_err(|error| Error::TaskPanicked(error.to_string()))??;
+
+        Ok(pruning_status)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{rpc::prelude::*, task::discard_block_transaction_list};
+
+/// Restarts the ordering of the current block from zero. The transactions
+/// ordered so far in the block are discarded together with their order
+/// commitments. A leader that already handed out order commitments for the
+/// block refuses, since ordering other transactions at the same orders would
+/// contradict them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ResetTransactionOrder {
+    pub rollup_id: String,
+}
+
+impl RpcParameter<AppState> for ResetTransactionOrder {
+    type Response = RollupMetadata;
+
+    fn method() -> &'static str {
+        "reset_transaction_order"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let mut rollup_metadata = RollupMetadata::get_mut(&self.rollup_id)?;
+        if rollup_metadata.is_leader {
+            if context
+                .shutdown()
+                .in_flight_rollup_transaction_count(&self.rollup_id)
+                > 0
+            {
+                return Err(Error::RollupBusy)?;
+            }
+
+            if rollup_metadata.transaction_order > 0 {
+                return Err(Error::OrderCommitmentAlreadyIssued)?;
+            }
+        }
+
+        let previous_rollup_metadata = RollupMetadata::clone(&rollup_metadata);
+        // A follower's transaction order is the highest order it received.
+        let discarded_transaction_count = discard_block_transaction_list(
+            &self.rollup_id,
+            rollup_metadata.rollup_block_height,
+            rollup_metadata.transaction_order.saturating_add(1),
+        )?;
+
+        rollup_metadata.transaction_order = 0;
+        rollup_metadata.current_gas = 0;
+
+        context
+            .merkle_tree_manager()
+            .insert(&self.rollup_id, MerkleTree::new())
+            .await;
+        let updated_rollup_metadata = RollupMetadata::clone(&rollup_metadata);
+        rollup_metadata.update()?;
+
+        tracing::warn!(
+            "Admin request - method: {:?} / rollup_id: {:?} / before: {:?} / after: {:?} / discarded_transaction_count: {:?}",
+            Self::method(),
+            self.rollup_id,
+            previous_rollup_metadata,
+            updated_rollup_metadata,
+            discarded_transaction_count
+        );
+
+        Ok(updated_rollup_metadata)
+    }
+}
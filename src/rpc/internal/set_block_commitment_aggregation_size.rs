@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    gossip::{Gossip, GossipKey},
+    rpc::{
+        cluster::{SyncBlockCommitmentAggregationSize, SyncBlockCommitmentAggregationSizeMessage},
+        prelude::*,
+    },
+};
+
+static UPDATE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Sets how many consecutive block commitments of a rollup are aggregated
+/// into one submission and gossips it to the cluster, whose members validate
+/// the submitted aggregates. Sizes below two disable aggregation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SetBlockCommitmentAggregationSize {
+    pub rollup_id: String,
+    pub block_commitment_aggregation_size: Option<u64>,
+}
+
+impl RpcParameter<AppState> for SetBlockCommitmentAggregationSize {
+    type Response = ();
+
+    fn method() -> &'static str {
+        "set_block_commitment_aggregation_size"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let block_commitment_aggregation_size = self
+            .block_commitment_aggregation_size
+            .filter(|aggregation_size| *aggregation_size > 1);
+
+        let rollup_metadata = RollupMetadata::get(&self.rollup_id)?;
+        let mut locked_rollup = Rollup::get_mut(&self.rollup_id)?;
+
+        let cluster = Cluster::get(
+            locked_rollup.platform,
+            locked_rollup.service_provider,
+            &locked_rollup.cluster_id,
+            rollup_metadata.platform_block_height,
+        )?;
+
+        let message = SyncBlockCommitmentAggregationSizeMessage {
+            rollup_id: self.rollup_id,
+            block_commitment_aggregation_size,
+        };
+        let signature = context
+            .get_signer(locked_rollup.platform)
+            .await
+            .map_err(|_| Error::SignerNotFound)?
+            .sign_message(&message)
+            .map_err(Error::Signature)?;
+
+        locked_rollup.block_commitment_aggregation_size = block_commitment_aggregation_size;
+        locked_rollup.update()?;
+
+        let others_cluster_rpc_url_list = cluster.get_others_cluster_rpc_url_list();
+        if others_cluster_rpc_url_list.is_empty() {
+            return Ok(());
+        }
+
+        // Every update gets its own key, so that a later size is not dropped
+        // as a duplicate of an earlier one still waiting in a peer queue.
+        let key = GossipKey::new(
+            SyncBlockCommitmentAggregationSize::method(),
+            &message.rollup_id,
+            rollup_metadata.rollup_block_height,
+            Some(UPDATE_SEQUENCE.fetch_add(1, Ordering::Relaxed)),
+        );
+        Gossip::broadcast(
+            &context,
+            others_cluster_rpc_url_list,
+            key,
+            &SyncBlockCommitmentAggregationSize { message, signature },
+        )
+        .await;
+
+        Ok(())
+    }
+}
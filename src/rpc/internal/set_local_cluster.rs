@@ -0,0 +1,51 @@
+use crate::rpc::prelude::*;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SetLocalCluster {
+    pub platform: Platform,
+    pub service_provider: ServiceProvider,
+    pub local_cluster_info: LocalClusterInfo,
+}
+
+impl RpcParameter<AppState> for SetLocalCluster {
+    type Response = ();
+
+    fn method() -> &'static str {
+        "set_local_cluster"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        tracing::info!(
+            "Set local cluster - platform: {:?}, service provider: {:?}, cluster id: {:?}",
+            self.platform,
+            self.service_provider,
+            self.local_cluster_info.cluster_id
+        );
+
+        let liveness_client: liveness::local::LivenessClient = context
+            .get_liveness_client::<liveness::local::LivenessClient>(
+                self.platform,
+                self.service_provider,
+            )
+            .await?;
+
+        liveness_client.add_local_cluster_info(&self.local_cluster_info)?;
+
+        let latest_cluster_block_height = LatestClusterBlockHeight::get_or(
+            self.platform,
+            self.service_provider,
+            &self.local_cluster_info.cluster_id,
+            LatestClusterBlockHeight::default,
+        )?;
+
+        liveness::local::initialize_new_cluster(
+            context.clone(),
+            &liveness_client,
+            &self.local_cluster_info.cluster_id,
+            latest_cluster_block_height.get_block_height(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use crate::rpc::prelude::*;
+
+/// Sets how long the blocks and transactions of a rollup are kept. Leaving
+/// both limits empty disables pruning.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SetRetentionPolicy {
+    pub rollup_id: String,
+    pub retention_policy: RetentionPolicy,
+}
+
+impl RpcParameter<AppState> for SetRetentionPolicy {
+    type Response = ();
+
+    fn method() -> &'static str {
+        "set_retention_policy"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        Rollup::get(&self.rollup_id).map_err(|_| Error::RollupNotFound)?;
+
+        self.retention_policy.put(&self.rollup_id)?;
+
+        Ok(())
+    }
+}
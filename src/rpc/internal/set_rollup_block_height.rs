@@ -0,0 +1,77 @@
+use crate::{rpc::prelude::*, task::discard_block_transaction_list};
+
+/// Moves the rollup to `rollup_block_height` with an empty block. The
+/// transactions ordered so far in the current block are discarded together
+/// with their order commitments. A leader that already handed out order
+/// commitments for the block refuses, since they would never be honored.
+///
+/// Heights below the current block, whose blocks were already built, are
+/// refused unless `force` is set. The blocks from that height are then built
+/// again and replace the stored ones.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SetRollupBlockHeight {
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+    #[serde(default)]
+    pub force: bool,
+}
+
+impl RpcParameter<AppState> for SetRollupBlockHeight {
+    type Response = RollupMetadata;
+
+    fn method() -> &'static str {
+        "set_rollup_block_height"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        let mut rollup_metadata = RollupMetadata::get_mut(&self.rollup_id)?;
+        if self.rollup_block_height < rollup_metadata.rollup_block_height && !self.force {
+            return Err(Error::RollupBlockHeightAlreadyBuilt)?;
+        }
+
+        if rollup_metadata.is_leader {
+            if context
+                .shutdown()
+                .in_flight_rollup_transaction_count(&self.rollup_id)
+                > 0
+            {
+                return Err(Error::RollupBusy)?;
+            }
+
+            if rollup_metadata.transaction_order > 0 {
+                return Err(Error::OrderCommitmentAlreadyIssued)?;
+            }
+        }
+
+        let previous_rollup_metadata = RollupMetadata::clone(&rollup_metadata);
+        // A follower's transaction order is the highest order it received.
+        let discarded_transaction_count = discard_block_transaction_list(
+            &self.rollup_id,
+            rollup_metadata.rollup_block_height,
+            rollup_metadata.transaction_order.saturating_add(1),
+        )?;
+
+        rollup_metadata.rollup_block_height = self.rollup_block_height;
+        rollup_metadata.transaction_order = 0;
+        rollup_metadata.current_gas = 0;
+
+        context
+            .merkle_tree_manager()
+            .insert(&self.rollup_id, MerkleTree::new())
+            .await;
+        let updated_rollup_metadata = RollupMetadata::clone(&rollup_metadata);
+        rollup_metadata.update()?;
+
+        tracing::warn!(
+            "Admin request - method: {:?} / rollup_id: {:?} / force: {:?} / before: {:?} / after: {:?} / discarded_transaction_count: {:?}",
+            Self::method(),
+            self.rollup_id,
+            self.force,
+            previous_rollup_metadata,
+            updated_rollup_metadata,
+            discarded_transaction_count
+        );
+
+        Ok(updated_rollup_metadata)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::rpc::prelude::*;
+
+/// Sets when the block commitments of a rollup are submitted. Leaving both
+/// intervals empty limits the rollup to on-demand submissions.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SetSubmissionPolicy {
+    pub rollup_id: String,
+    pub submission_policy: SubmissionPolicy,
+}
+
+impl RpcParameter<AppState> for SetSubmissionPolicy {
+    type Response = ();
+
+    fn method() -> &'static str {
+        "set_submission_policy"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        Rollup::get(&self.rollup_id).map_err(|_| Error::RollupNotFound)?;
+
+        self.submission_policy.put(&self.rollup_id)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{
+    profiler::{ProfilerMode, ProfilerStatus},
+    rpc::prelude::*,
+};
+
+/// Starts the profiler at runtime. `mode` overrides the configured mode for
+/// this session.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StartProfiler {
+    pub mode: Option<ProfilerMode>,
+}
+
+impl RpcParameter<AppState> for StartProfiler {
+    type Response = ProfilerStatus;
+
+    fn method() -> &'static str {
+        "start_profiler"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        Ok(context
+            .profiler()
+            .start(self.mode)
+            .await
+            .map_err(Error::Profiler)?)
+    }
+}
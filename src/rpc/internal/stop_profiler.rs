@@ -0,0 +1,18 @@
+use crate::{profiler::ProfilerStatus, rpc::prelude::*};
+
+/// Stops the running profiler. In the `folded` mode the response carries the
+/// path of the profile written on stop.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StopProfiler {}
+
+impl RpcParameter<AppState> for StopProfiler {
+    type Response = ProfilerStatus;
+
+    fn method() -> &'static str {
+        "stop_profiler"
+    }
+
+    async fn handler(self, context: AppState) -> Result<Self::Response, RpcError> {
+        Ok(context.profiler().stop().await.map_err(Error::Profiler)?)
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{rpc::prelude::*, task::push_block_commitment};
+
+/// Queues the commitment of a stored block for submission regardless of the
+/// submission policy of the rollup. A block whose submission failed is queued
+/// again with a fresh attempt budget.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SubmitBlockCommitment {
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SubmitBlockCommitmentResponse {
+    /// `false` if the block is already waiting for submission and has not
+    /// failed.
+    pub is_queued: bool,
+}
+
+impl RpcParameter<AppState> for SubmitBlockCommitment {
+    type Response = SubmitBlockCommitmentResponse;
+
+    fn method() -> &'static str {
+        "submit_block_commitment"
+    }
+
+    async fn handler(self, _context: AppState) -> Result<Self::Response, RpcError> {
+        let rollup = Rollup::get(&self.rollup_id).map_err(|_| Error::RollupNotFound)?;
+        ValidationInfo::get(
+            rollup.validation_info.platform,
+            rollup.validation_info.validation_service_provider,
+        )?;
+
+        let block = Block::get(&self.rollup_id, self.rollup_block_height)
+            .map_err(|_| Error::BlockNotFound)?;
+
+        let mut pending_block_commitment_queue =
+            PendingBlockCommitmentQueue::get_mut_or(PendingBlockCommitmentQueue::default)?;
+        if pending_block_commitment_queue.retry_failed(&self.rollup_id, self.rollup_block_height) {
+            pending_block_commitment_queue.update()?;
+
+            tracing::info!(
+                "Queued the failed block commitment again - rollup_id: {:?} / rollup_block_height: {:?}",
+                self.rollup_id,
+                self.rollup_block_height
+            );
+
+            return Ok(SubmitBlockCommitmentResponse { is_queued: true });
+        }
+        drop(pending_block_commitment_queue);
+
+        let is_queued = push_block_commitment(
+            &self.rollup_id,
+            self.rollup_block_height,
+            &block.block_commitment,
+        )?;
+
+        Ok(SubmitBlockCommitmentResponse { is_queued })
+    }
+}
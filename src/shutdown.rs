@@ -0,0 +1,322 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    time::{sleep, Duration, Instant},
+};
+
+use crate::{error::Error, state::AppState};
+
+/// Time allowed for each shutdown stage before giving up on it.
+const STAGE_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Coordinates the graceful shutdown of the node.
+///
+/// Work that must not be interrupted halfway holds an [`InFlightGuard`] for
+/// its duration. Once the shutdown begins, new external transactions are
+/// refused and [`shutdown`] waits for the guards to be released.
+pub struct Shutdown {
+    inner: Arc<ShutdownInner>,
+}
+
+#[derive(Default)]
+struct ShutdownInner {
+    is_shutting_down: AtomicBool,
+    transaction_count: AtomicUsize,
+    block_build_count: AtomicUsize,
+    /// In-flight transactions by rollup ID, for the admin RPCs that must not
+    /// run while a rollup is ordering transactions.
+    rollup_transaction_count_map: Mutex<HashMap<String, usize>>,
+}
+
+impl Clone for Shutdown {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(ShutdownInner::default()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum InFlightKind {
+    Transaction,
+    BlockBuild,
+}
+
+/// Marks a transaction or a block build as in progress until dropped.
+pub struct InFlightGuard {
+    inner: Arc<ShutdownInner>,
+    kind: InFlightKind,
+    rollup_id: Option<String>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.inner.counter(self.kind).fetch_sub(1, Ordering::SeqCst);
+
+        if let Some(rollup_id) = self.rollup_id.as_ref() {
+            if let Ok(mut rollup_transaction_count_map) =
+                self.inner.rollup_transaction_count_map.lock()
+            {
+                if let Some(count) = rollup_transaction_count_map.get_mut(rollup_id) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        rollup_transaction_count_map.remove(rollup_id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ShutdownInner {
+    fn counter(&self, kind: InFlightKind) -> &AtomicUsize {
+        match kind {
+            InFlightKind::Transaction => &self.transaction_count,
+            InFlightKind::BlockBuild => &self.block_build_count,
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn is_shutting_down(&self) -> bool {
+        self.inner.is_shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Number of external transactions being processed right now.
+    pub fn in_flight_transaction_count(&self) -> usize {
+        self.inner.transaction_count.load(Ordering::SeqCst)
+    }
+
+    /// Number of external transactions for `rollup_id` being processed right
+    /// now.
+    pub fn in_flight_rollup_transaction_count(&self, rollup_id: &str) -> usize {
+        self.inner
+            .rollup_transaction_count_map
+            .lock()
+            .map(|rollup_transaction_count_map| {
+                rollup_transaction_count_map
+                    .get(rollup_id)
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Registers an external transaction for `rollup_id`. Fails once the
+    /// shutdown has begun.
+    pub fn enter_transaction(&self, rollup_id: &str) -> Result<InFlightGuard, Error> {
+        // Counted before the check so that `shutdown` cannot miss a
+        // transaction that passed the check.
+        let mut guard = self.enter(InFlightKind::Transaction);
+
+        if let Ok(mut rollup_transaction_count_map) = self.inner.rollup_transaction_count_map.lock()
+        {
+            *rollup_transaction_count_map
+                .entry(rollup_id.to_owned())
+                .or_default() += 1;
+            guard.rollup_id = Some(rollup_id.to_owned());
+        }
+
+        if self.is_shutting_down() {
+            return Err(Error::ShuttingDown);
+        }
+
+        Ok(guard)
+    }
+
+    /// Registers a block build. Builds are never refused because the block
+    /// has already been finalized by the cluster.
+    pub fn enter_block_build(&self) -> InFlightGuard {
+        self.enter(InFlightKind::BlockBuild)
+    }
+
+    fn enter(&self, kind: InFlightKind) -> InFlightGuard {
+        self.inner.counter(kind).fetch_add(1, Ordering::SeqCst);
+
+        InFlightGuard {
+            inner: self.inner.clone(),
+            kind,
+            rollup_id: None,
+        }
+    }
+
+    fn begin(&self) {
+        self.inner.is_shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Waits until nothing of `kind` is in progress. Returns `false` on
+    /// timeout.
+    async fn wait_for_idle(&self, kind: InFlightKind) -> bool {
+        let deadline = Instant::now() + STAGE_TIMEOUT;
+
+        loop {
+            let count = self.inner.counter(kind).load(Ordering::SeqCst);
+            if count == 0 {
+                return true;
+            }
+
+            if Instant::now() >= deadline {
+                tracing::warn!("Shutdown - {:?} still in progress: {:?}", kind, count);
+                return false;
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Resolves on SIGTERM or SIGINT.
+pub async fn wait_for_signal() -> Result<(), Error> {
+    let mut terminate = signal(SignalKind::terminate()).map_err(Error::Syscall)?;
+    let mut interrupt = signal(SignalKind::interrupt()).map_err(Error::Syscall)?;
+
+    tokio::select! {
+        _ = terminate.recv() => tracing::info!("Received SIGTERM"),
+        _ = interrupt.recv() => tracing::info!("Received SIGINT"),
+    }
+
+    Ok(())
+}
+
+/// Shuts the node down in order:
+/// 1. refuses new external transactions and waits for the ones in progress,
+/// 2. waits for the block builds in progress,
+/// 3. drains the outbound sync queues,
+/// 4. checkpoints the merkle tree of every rollup this node leads,
+/// 5. stops the profiler so that a local profile is written.
+///
+/// Every stage runs even if a previous one timed out. Returns
+/// [`Error::ShutdownIncomplete`] if any stage did not complete.
+pub async fn shutdown(context: &AppState) -> Result<(), Error> {
+    tracing::info!("Shutting down..");
+    let shutdown = context.shutdown();
+    shutdown.begin();
+
+    let mut is_complete = shutdown.wait_for_idle(InFlightKind::Transaction).await;
+    tracing::info!("Shutdown - external transactions drained");
+
+    is_complete &= shutdown.wait_for_idle(InFlightKind::BlockBuild).await;
+    tracing::info!("Shutdown - block builds finished");
+
+    is_complete &= context.gossip().wait_for_drain(STAGE_TIMEOUT).await;
+    tracing::info!("Shutdown - sync queues drained");
+
+    if let Err(error) = context.merkle_tree_manager().checkpoint_all().await {
+        tracing::error!("Shutdown - failed to flush the merkle trees: {:?}", error);
+        is_complete = false;
+    } else {
+        tracing::info!("Shutdown - merkle trees flushed");
+    }
+
+    if context.profiler().status().await.is_running {
+        if let Err(error) = context.profiler().stop().await {
+            tracing::error!("Shutdown - failed to stop the profiler: {:?}", error);
+        }
+    }
+
+    if !is_complete {
+        tracing::error!("Shutdown incomplete");
+        return Err(Error::ShutdownIncomplete);
+    }
+
+    tracing::info!("Shutdown complete");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_transactions_until_the_guard_is_dropped() {
+        let shutdown = Shutdown::default();
+
+        let first_guard = shutdown.enter_transaction("rollup_a").unwrap();
+        let second_guard = shutdown.enter_transaction("rollup_b").unwrap();
+        assert_eq!(shutdown.in_flight_transaction_count(), 2);
+
+        drop(first_guard);
+        assert_eq!(shutdown.in_flight_transaction_count(), 1);
+        drop(second_guard);
+        assert_eq!(shutdown.in_flight_transaction_count(), 0);
+    }
+
+    #[test]
+    fn counts_transactions_per_rollup() {
+        let shutdown = Shutdown::default();
+
+        let first_guard = shutdown.enter_transaction("rollup_a").unwrap();
+        let second_guard = shutdown.enter_transaction("rollup_a").unwrap();
+        let third_guard = shutdown.enter_transaction("rollup_b").unwrap();
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_a"), 2);
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_b"), 1);
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_c"), 0);
+
+        drop(first_guard);
+        drop(third_guard);
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_a"), 1);
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_b"), 0);
+
+        drop(second_guard);
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_a"), 0);
+    }
+
+    #[test]
+    fn refuses_transactions_but_not_block_builds_once_begun() {
+        let shutdown = Shutdown::default();
+        shutdown.begin();
+
+        assert!(shutdown.is_shutting_down());
+        assert!(matches!(
+            shutdown.enter_transaction("rollup_a"),
+            Err(Error::ShuttingDown)
+        ));
+        assert_eq!(shutdown.in_flight_transaction_count(), 0);
+        assert_eq!(shutdown.in_flight_rollup_transaction_count("rollup_a"), 0);
+
+        let _guard = shutdown.enter_block_build();
+        assert_eq!(
+            shutdown
+                .inner
+                .counter(InFlightKind::BlockBuild)
+                .load(Ordering::SeqCst),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn waits_for_the_guards_to_be_released() {
+        let shutdown = Shutdown::default();
+        let guard = shutdown.enter_block_build();
+        shutdown.begin();
+
+        let release = tokio::spawn(async move {
+            sleep(Duration::from_millis(300)).await;
+            drop(guard);
+        });
+
+        let started_at = Instant::now();
+        assert!(shutdown.wait_for_idle(InFlightKind::BlockBuild).await);
+        assert!(started_at.elapsed() >= Duration::from_millis(300));
+        assert!(shutdown.wait_for_idle(InFlightKind::Transaction).await);
+
+        release.await.unwrap();
+    }
+}
@@ -11,8 +11,13 @@ use crate::{
     client::liveness::{
         distributed_key_generation::DistributedKeyGenerationClient, seeder::SeederClient,
     },
+    gossip::Gossip,
     merkle_tree_manager::MerkleTreeManager,
+    metrics::Metrics,
     profiler::Profiler,
+    shutdown::Shutdown,
+    task::Supervisor,
+    transaction_buffer::TransactionBuffer,
     types::*,
 };
 
@@ -28,9 +33,14 @@ struct AppStateInner {
     validation_clients: CachedKvStore,
     signers: CachedKvStore,
     skde_params: SkdeParams,
-    profiler: Option<Profiler>,
+    profiler: Profiler,
     rpc_client: RpcClient,
     merkle_tree_manager: MerkleTreeManager,
+    transaction_buffer: TransactionBuffer,
+    gossip: Gossip,
+    metrics: Metrics,
+    shutdown: Shutdown,
+    supervisor: Supervisor,
 }
 
 impl Clone for AppState {
@@ -51,7 +61,7 @@ impl AppState {
         liveness_clients: CachedKvStore,
         validation_clients: CachedKvStore,
         skde_params: SkdeParams,
-        profiler: Option<Profiler>,
+        profiler: Profiler,
         rpc_client: RpcClient,
         merkle_tree_manager: MerkleTreeManager,
     ) -> Self {
@@ -66,6 +76,11 @@ impl AppState {
             profiler,
             rpc_client,
             merkle_tree_manager,
+            transaction_buffer: TransactionBuffer::default(),
+            gossip: Gossip::default(),
+            metrics: Metrics::default(),
+            shutdown: Shutdown::default(),
+            supervisor: Supervisor::default(),
         };
 
         Self {
@@ -89,8 +104,8 @@ impl AppState {
         &self.inner.skde_params
     }
 
-    pub fn profiler(&self) -> Option<Profiler> {
-        self.inner.profiler.clone()
+    pub fn profiler(&self) -> &Profiler {
+        &self.inner.profiler
     }
 
     pub fn rpc_client(&self) -> &RpcClient {
@@ -100,6 +115,26 @@ impl AppState {
     pub fn merkle_tree_manager(&self) -> &MerkleTreeManager {
         &self.inner.merkle_tree_manager
     }
+
+    pub fn transaction_buffer(&self) -> &TransactionBuffer {
+        &self.inner.transaction_buffer
+    }
+
+    pub fn gossip(&self) -> &Gossip {
+        &self.inner.gossip
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.inner.metrics
+    }
+
+    pub fn shutdown(&self) -> &Shutdown {
+        &self.inner.shutdown
+    }
+
+    pub fn supervisor(&self) -> &Supervisor {
+        &self.inner.supervisor
+    }
 }
 
 /// Validation client functions
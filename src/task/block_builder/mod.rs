@@ -1,19 +1,26 @@
 mod skde_block_builder;
-mod validation;
 
-use radius_sdk::{
-    json_rpc::{client::Id, server::RpcParameter},
-    signature::Signature,
-};
+use std::time::Instant;
+
+use radius_sdk::{json_rpc::server::RpcParameter, signature::Signature};
 use skde_block_builder::*;
-use validation::*;
 
+use super::{
+    backfill_transaction, detect_order_commitment_violation, enqueue_block_commitment, RetryPolicy,
+};
 use crate::{
+    error::Error,
+    gossip::{Gossip, GossipKey},
+    metrics::BLOCK_BUILD_SECONDS,
     rpc::cluster::{FinalizeBlockMessage, SyncBlock},
     state::AppState,
     types::*,
 };
 
+/// Attempts per block build. Rebuilding a block overwrites the same keys, so
+/// a failed build can be retried.
+const BLOCK_BUILD_ATTEMPT_COUNT: u32 = 3;
+
 pub fn build_block(
     context: AppState,
 
@@ -33,56 +40,123 @@ pub fn build_block(
         transaction_count
     );
 
-    tokio::spawn(async move {
-        let leader_sequencer_address = finalize_block_message.next_block_creator_address.clone();
+    let supervisor = context.supervisor().clone();
+    let name = format!(
+        "build_block:{}:{}",
+        finalize_block_message.rollup_id, finalize_block_message.rollup_block_height
+    );
+    // Held by the task until its last attempt ends so that the shutdown also
+    // waits for a build waiting to be retried.
+    let block_build_guard = context.shutdown().enter_block_build();
+
+    supervisor.spawn(
+        name,
+        RetryPolicy::idempotent(BLOCK_BUILD_ATTEMPT_COUNT),
+        move || {
+            let _block_build_guard = &block_build_guard;
+
+            build_and_sync_block(
+                context.clone(),
+                cluster.clone(),
+                finalize_block_message.clone(),
+                rollup_signature.clone(),
+                encrypted_transaction_type,
+                transaction_count,
+            )
+        },
+    );
+}
+
+async fn build_and_sync_block(
+    context: AppState,
+    cluster: Cluster,
+    finalize_block_message: FinalizeBlockMessage,
+    rollup_signature: Signature,
+    encrypted_transaction_type: EncryptedTransactionType,
+    transaction_count: u64,
+) -> Result<(), Error> {
+    let leader_sequencer_address = finalize_block_message.block_creator_address.clone();
 
-        let block = match encrypted_transaction_type {
-            EncryptedTransactionType::Pvde => unimplemented!(),
-            EncryptedTransactionType::Skde => skde_build_block(
+    let started_at = Instant::now();
+    let block = match encrypted_transaction_type {
+        EncryptedTransactionType::Skde => {
+            skde_build_block(
                 context.clone(),
                 &cluster,
                 finalize_block_message.rollup_id.clone(),
-                finalize_block_message.rollup_block_height.clone(),
+                finalize_block_message.rollup_block_height,
                 transaction_count,
                 leader_sequencer_address,
                 None,
             )
-            .await
-            .unwrap(),
-            EncryptedTransactionType::NotSupport => unimplemented!(),
-        };
+            .await?
+        }
+        EncryptedTransactionType::Pvde | EncryptedTransactionType::NotSupport => {
+            return Err(Error::UnsupportedEncryptedMempool)
+        }
+    };
+    context.metrics().observe_duration(
+        BLOCK_BUILD_SECONDS,
+        &[
+            ("rollup_id", finalize_block_message.rollup_id.as_str()),
+            ("role", "leader"),
+        ],
+        started_at.elapsed(),
+    );
 
-        let rollup = Rollup::get(&finalize_block_message.rollup_id).unwrap();
-
-        let validation_platform = rollup.validation_info.platform.clone();
-        let validation_service_provider =
-            rollup.validation_info.validation_service_provider.clone();
-        let validation_info =
-            ValidationInfo::get(validation_platform, validation_service_provider).unwrap();
-        let block_commitment = block.block_commitment;
-        let rollup_block_height = finalize_block_message.rollup_block_height;
-
-        let _ = sync_block(
-            context.clone(),
-            cluster,
-            finalize_block_message,
-            rollup_signature,
-            transaction_count,
-            block.signature,
-        )
+    detect_order_commitment_violation(&finalize_block_message.rollup_id, &block, transaction_count)
         .await;
 
-        submit_block_commitment(
-            context.clone(),
-            &rollup,
+    let rollup =
+        Rollup::get(&finalize_block_message.rollup_id).map_err(|_| Error::RollupNotFound)?;
+
+    let validation_platform = rollup.validation_info.platform.clone();
+    let validation_service_provider = rollup.validation_info.validation_service_provider.clone();
+    let validation_info =
+        ValidationInfo::get(validation_platform, validation_service_provider).ok();
+    let block_commitment = block.block_commitment;
+    let rollup_block_height = finalize_block_message.rollup_block_height;
+
+    sync_block(
+        context.clone(),
+        cluster.clone(),
+        finalize_block_message,
+        rollup_signature,
+        transaction_count,
+        block.signature,
+    )
+    .await;
+
+    // Rollups without a registered validation service (e.g. local
+    // clusters) skip the block commitment submission.
+    match validation_info {
+        Some(_) => {
+            if let Err(error) = enqueue_block_commitment(
+                &context,
+                &rollup,
+                &cluster,
+                rollup_block_height,
+                &block_commitment,
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to queue the block commitment - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                    rollup.rollup_id,
+                    rollup_block_height,
+                    error
+                );
+            }
+        }
+        None => tracing::warn!(
+            "Validation info not found - rollup_id: {:?} / platform: {:?} / validation service provider: {:?}",
+            rollup.rollup_id,
             validation_platform,
-            validation_service_provider,
-            validation_info,
-            rollup_block_height,
-            &block_commitment,
-        )
-        .await;
-    });
+            validation_service_provider
+        ),
+    }
+
+    Ok(())
 }
 
 pub async fn sync_block(
@@ -93,6 +167,12 @@ pub async fn sync_block(
     transaction_count: u64,
     leader_sequencer_signature: Signature,
 ) {
+    let key = GossipKey::new(
+        SyncBlock::method(),
+        &finalize_block_message.rollup_id,
+        finalize_block_message.rollup_block_height,
+        None,
+    );
     let parameter = SyncBlock {
         finalize_block_message,
         rollup_signature,
@@ -107,22 +187,7 @@ pub async fn sync_block(
         return;
     }
 
-    match context
-        .rpc_client()
-        .multicast(
-            others_cluster_rpc_url_list.clone(),
-            SyncBlock::method(),
-            &parameter,
-            Id::Null,
-        )
-        .await
-    {
-        Ok(_) => tracing::info!(
-            "Successfully synchronized block to {:?}",
-            others_cluster_rpc_url_list
-        ),
-        Err(e) => tracing::error!("Failed to synchronize block: {:?}", e),
-    }
+    Gossip::broadcast(&context, others_cluster_rpc_url_list, key, &parameter).await;
 }
 
 pub fn follow_block(
@@ -144,31 +209,99 @@ pub fn follow_block(
         transaction_count
     );
 
-    tokio::spawn(async move {
-        match encrypted_transaction_type {
-            EncryptedTransactionType::Pvde => unimplemented!(),
-            EncryptedTransactionType::Skde => {
-                skde_build_block(
-                    context,
-                    &cluster,
-                    finalize_block_message.rollup_id.clone(),
-                    finalize_block_message.rollup_block_height.clone(),
-                    transaction_count,
-                    finalize_block_message.next_block_creator_address.clone(),
-                    Some(signature),
-                )
-                .await
-            }
-            EncryptedTransactionType::NotSupport => unimplemented!(),
+    let supervisor = context.supervisor().clone();
+    let name = format!(
+        "follow_block:{}:{}",
+        finalize_block_message.rollup_id, finalize_block_message.rollup_block_height
+    );
+    // Held by the task until its last attempt ends so that the shutdown also
+    // waits for a build waiting to be retried.
+    let block_build_guard = context.shutdown().enter_block_build();
+
+    supervisor.spawn(
+        name,
+        RetryPolicy::idempotent(BLOCK_BUILD_ATTEMPT_COUNT),
+        move || {
+            let _block_build_guard = &block_build_guard;
+
+            build_followed_block(
+                context.clone(),
+                cluster.clone(),
+                finalize_block_message.clone(),
+                encrypted_transaction_type,
+                transaction_count,
+                signature.clone(),
+            )
+        },
+    );
+}
+
+async fn build_followed_block(
+    context: AppState,
+    cluster: Cluster,
+    finalize_block_message: FinalizeBlockMessage,
+    encrypted_transaction_type: EncryptedTransactionType,
+    transaction_count: u64,
+    signature: Signature,
+) -> Result<(), Error> {
+    // Fill the gaps left by missed sync messages in batches instead of
+    // fetching them one by one while building.
+    if let Err(error) = backfill_transaction(
+        &context,
+        &cluster,
+        &finalize_block_message.rollup_id,
+        finalize_block_message.rollup_block_height,
+        transaction_count,
+        &finalize_block_message.block_creator_address,
+    )
+    .await
+    {
+        tracing::warn!(
+            "Failed to backfill transactions before building - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+            finalize_block_message.rollup_id,
+            finalize_block_message.rollup_block_height,
+            error
+        );
+    }
+
+    let started_at = Instant::now();
+    let block = match encrypted_transaction_type {
+        EncryptedTransactionType::Skde => {
+            skde_build_block(
+                context.clone(),
+                &cluster,
+                finalize_block_message.rollup_id.clone(),
+                finalize_block_message.rollup_block_height,
+                transaction_count,
+                finalize_block_message.block_creator_address.clone(),
+                Some(signature),
+            )
+            .await?
         }
-    });
+        EncryptedTransactionType::Pvde | EncryptedTransactionType::NotSupport => {
+            return Err(Error::UnsupportedEncryptedMempool)
+        }
+    };
+    context.metrics().observe_duration(
+        BLOCK_BUILD_SECONDS,
+        &[
+            ("rollup_id", finalize_block_message.rollup_id.as_str()),
+            ("role", "follower"),
+        ],
+        started_at.elapsed(),
+    );
+
+    detect_order_commitment_violation(&finalize_block_message.rollup_id, &block, transaction_count)
+        .await;
+
+    Ok(())
 }
 
 pub fn get_encrypted_transaction_list(
     rollup_id: &str,
     rollup_block_height: u64,
     transaction_count: u64,
-) -> Vec<Option<EncryptedTransaction>> {
+) -> Result<Vec<Option<EncryptedTransaction>>, Error> {
     let mut encrypted_transaction_list =
         Vec::<Option<EncryptedTransaction>>::with_capacity(transaction_count as usize);
 
@@ -183,7 +316,7 @@ pub fn get_encrypted_transaction_list(
                 if error.is_none_type() {
                     None
                 } else {
-                    panic!("block_builder: {:?}", error);
+                    return Err(Error::Database(error));
                 }
             }
         };
@@ -191,14 +324,14 @@ pub fn get_encrypted_transaction_list(
         encrypted_transaction_list.push(encrypted_transaction.clone());
     }
 
-    encrypted_transaction_list
+    Ok(encrypted_transaction_list)
 }
 
 pub fn get_raw_transaction_info_list(
     rollup_id: &str,
     rollup_block_height: u64,
     transaction_count: u64,
-) -> Vec<Option<(RawTransaction, bool)>> {
+) -> Result<Vec<Option<(RawTransaction, bool)>>, Error> {
     let mut raw_transaction_info_list =
         Vec::<Option<(RawTransaction, bool)>>::with_capacity(transaction_count as usize);
 
@@ -210,7 +343,7 @@ pub fn get_raw_transaction_info_list(
                     if error.is_none_type() {
                         None
                     } else {
-                        panic!("block_builder: {:?}", error);
+                        return Err(Error::Database(error));
                     }
                 }
             };
@@ -218,5 +351,5 @@ pub fn get_raw_transaction_info_list(
         raw_transaction_info_list.push(raw_transaction_info.clone());
     }
 
-    raw_transaction_info_list
+    Ok(raw_transaction_info_list)
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Instant};
 
 use radius_sdk::signature::{Address, Signature};
 use skde::delay_encryption::{decrypt, SkdeParams};
@@ -7,6 +7,7 @@ use super::{get_encrypted_transaction_list, get_raw_transaction_info_list};
 use crate::{
     client::liveness::distributed_key_generation::DistributedKeyGenerationClient,
     error::Error,
+    metrics::{Metrics, DECRYPTION_SECONDS},
     state::AppState,
     types::*,
     util::{fetch_encrypted_transaction, fetch_raw_transaction_info},
@@ -23,26 +24,36 @@ pub async fn skde_build_block(
 ) -> Result<Block, Error> {
     let distributed_key_generation_client = context.distributed_key_generation_client().clone();
 
-    let rollup = Rollup::get(&rollup_id).unwrap();
+    let rollup = Rollup::get(&rollup_id).map_err(|_| Error::RollupNotFound)?;
 
     let skde_params = distributed_key_generation_client
         .get_skde_params()
         .await
-        .unwrap()
+        .map_err(Error::DistributedKeyGeneration)?
         .skde_params;
 
     let merkle_tree = MerkleTree::new();
     let mut decryption_keys: HashMap<u64, String> = HashMap::new();
 
     let mut encrypted_transaction_list =
-        get_encrypted_transaction_list(&rollup_id, rollup_block_height, transaction_count);
+        get_encrypted_transaction_list(&rollup_id, rollup_block_height, transaction_count)?;
     let raw_transaction_info_list =
-        get_raw_transaction_info_list(&rollup_id, rollup_block_height, transaction_count);
+        get_raw_transaction_info_list(&rollup_id, rollup_block_height, transaction_count)?;
     let mut final_raw_transaction_list =
         Vec::<RawTransaction>::with_capacity(transaction_count as usize);
     final_raw_transaction_list.resize(transaction_count as usize, RawTransaction::default());
 
+    // An encrypted bundle is stored at the first order it covers, and its
+    // decrypted transactions fill the following orders.
+    let mut bundle_raw_transaction_list: HashMap<usize, RawTransaction> = HashMap::new();
+    let mut bundle_covered_until: usize = 0;
+
     for (i, raw_transaction_info) in raw_transaction_info_list.iter().enumerate() {
+        if let Some(encrypted_transaction) = encrypted_transaction_list[i].as_ref() {
+            bundle_covered_until = bundle_covered_until
+                .max(i + encrypted_transaction.raw_transaction_hash_list().len());
+        }
+
         match raw_transaction_info {
             Some((rawtransaction, is_direct_sent)) => {
                 final_raw_transaction_list[i] = rawtransaction.clone();
@@ -53,76 +64,91 @@ pub async fn skde_build_block(
             }
             None => {
                 let mut is_direct_sent = false;
-                if encrypted_transaction_list[i].is_some() {
-                    let skde_encrypted_transaction = encrypted_transaction_list[i]
-                        .as_ref()
-                        .cloned()
-                        .unwrap()
-                        .try_into_skde_transaction()
-                        .unwrap();
+                if let Some(raw_transaction) = bundle_raw_transaction_list.remove(&i) {
+                    final_raw_transaction_list[i] = raw_transaction;
+                } else if let Some(encrypted_transaction) = encrypted_transaction_list[i].as_ref() {
+                    let skde_encrypted_transaction =
+                        encrypted_transaction.clone().try_into_skde_transaction()?;
 
                     let (raw_transaction, _plain_data) = decrypt_skde_transaction(
                         &skde_encrypted_transaction,
                         distributed_key_generation_client.clone(),
                         &mut decryption_keys,
                         &skde_params,
+                        context.metrics(),
                     )
-                    .await
-                    .unwrap();
+                    .await?;
+
+                    let raw_transaction_list = split_raw_transaction(raw_transaction);
+                    if raw_transaction_list.len()
+                        != encrypted_transaction.raw_transaction_hash_list().len()
+                        || i + raw_transaction_list.len() > transaction_count as usize
+                    {
+                        return Err(Error::InvalidTransaction);
+                    }
 
-                    final_raw_transaction_list[i] = raw_transaction;
+                    for (index, raw_transaction) in raw_transaction_list.into_iter().enumerate() {
+                        if index == 0 {
+                            final_raw_transaction_list[i] = raw_transaction;
+                        } else {
+                            bundle_raw_transaction_list.insert(i + index, raw_transaction);
+                        }
+                    }
                 } else {
                     let (raw_transaction, is_direct_sent_result) = fetch_raw_transaction_info(
                         context.rpc_client(),
-                        &cluster,
+                        cluster,
                         &rollup_id,
                         rollup_block_height,
                         i as u64,
                     )
-                    .await
-                    .unwrap();
+                    .await?;
 
                     final_raw_transaction_list[i] = raw_transaction;
 
-                    if is_direct_sent_result == false {
+                    // The orders covered by a bundle have no encrypted
+                    // transaction of their own.
+                    if !is_direct_sent_result && i >= bundle_covered_until {
                         let encrypted_transaction = fetch_encrypted_transaction(
                             context.rpc_client(),
-                            &cluster,
+                            cluster,
                             &rollup_id,
                             rollup_block_height,
                             i as u64,
                         )
-                        .await
-                        .unwrap();
+                        .await?;
 
+                        bundle_covered_until =
+                            i + encrypted_transaction.raw_transaction_hash_list().len();
                         encrypted_transaction_list[i] = Some(encrypted_transaction.clone());
 
-                        let _ = EncryptedTransactionModel::put(
+                        EncryptedTransactionModel::put(
                             &rollup_id,
                             rollup_block_height,
                             i as u64,
                             &encrypted_transaction,
-                        );
+                        )
+                        .map_err(Error::Database)?;
                     }
 
                     is_direct_sent = is_direct_sent_result;
                 }
 
-                let _ = RawTransactionModel::put(
+                RawTransactionModel::put(
                     &rollup_id,
                     rollup_block_height,
                     i as u64,
                     final_raw_transaction_list[i].clone(),
                     is_direct_sent,
                 )
-                .unwrap();
+                .map_err(Error::Database)?;
             }
         }
 
         merkle_tree
             .add_data(
                 final_raw_transaction_list[i]
-                    .raw_transaction_hash()
+                    .raw_transaction_hash()?
                     .as_ref(),
             )
             .await;
@@ -131,11 +157,18 @@ pub async fn skde_build_block(
     merkle_tree.finalize_tree().await;
     let block_commitment = merkle_tree.get_merkle_root().await;
 
-    let signature = if signature.is_some() {
-        signature.unwrap()
-    } else {
-        let signer = context.get_signer(rollup.platform).await.unwrap();
-        signer.sign_message(block_commitment).unwrap()
+    let signature = match signature {
+        Some(signature) => signature,
+        None => {
+            let signer = context
+                .get_signer(rollup.platform)
+                .await
+                .map_err(|_| Error::SignerNotFound)?;
+
+            signer
+                .sign_message(block_commitment)
+                .map_err(Error::Signature)?
+        }
     };
 
     let block = Block::new(
@@ -147,7 +180,10 @@ pub async fn skde_build_block(
         leader_sequencer_address,
     );
 
-    Block::put(&block, &rollup_id, rollup_block_height).unwrap();
+    Block::put(&block, &rollup_id, rollup_block_height).map_err(Error::Database)?;
+    BlockTimestamp::now()
+        .put(&rollup_id, rollup_block_height)
+        .map_err(Error::Database)?;
 
     tracing::info!(
         "Block built - block_height: {:?} / transaction_count: {:?}",
@@ -158,11 +194,24 @@ pub async fn skde_build_block(
     Ok(block)
 }
 
+/// Splits a decrypted bundle into the transactions it carries, one per order.
+fn split_raw_transaction(raw_transaction: RawTransaction) -> Vec<RawTransaction> {
+    match raw_transaction {
+        RawTransaction::Eth(_) => vec![raw_transaction],
+        RawTransaction::EthBundle(eth_raw_bundle_transaction) => eth_raw_bundle_transaction
+            .0
+            .into_iter()
+            .map(RawTransaction::Eth)
+            .collect(),
+    }
+}
+
 async fn decrypt_skde_transaction(
     skde_encrypted_transaction: &SkdeEncryptedTransaction,
     distributed_key_generation_client: DistributedKeyGenerationClient,
     decryption_keys: &mut HashMap<u64, String>,
     skde_params: &SkdeParams,
+    metrics: &Metrics,
 ) -> Result<(RawTransaction, PlainData), Error> {
     let decryption_key_id = skde_encrypted_transaction.key_id;
 
@@ -182,38 +231,28 @@ async fn decrypt_skde_transaction(
         std::collections::hash_map::Entry::Occupied(entry) => entry.get().clone(),
     };
 
-    match &skde_encrypted_transaction.transaction_data {
-        TransactionData::Eth(transaction_data) => {
-            let encrypted_data = transaction_data.encrypted_data.clone();
-
-            let decrypted_data = decrypt(skde_params, encrypted_data.as_ref(), &decryption_key)
-                .map_err(|e| {
-                    tracing::error!(
-                        "Decryption failed for key_id: {}: {:?}",
-                        decryption_key_id,
-                        e
-                    );
-                    Error::Decryption
-                })?;
-
-            let eth_plain_data: EthPlainData =
-                serde_json::from_str(&decrypted_data).map_err(|e| {
-                    tracing::error!("Failed to parse decrypted data: {:?}", e);
-                    Error::Deserialize
-                })?;
-
-            let rollup_transaction = transaction_data
-                .open_data
-                .convert_to_rollup_transaction(&eth_plain_data);
-
-            let eth_raw_transaction = EthRawTransaction::from(to_raw_tx(rollup_transaction));
-            let raw_transaction = RawTransaction::from(eth_raw_transaction);
-
-            Ok((raw_transaction, PlainData::from(eth_plain_data)))
-        }
-        TransactionData::EthBundle(_data) => {
-            tracing::warn!("EthBundle transactions are not yet supported.");
-            unimplemented!()
-        }
-    }
+    let encrypted_data = skde_encrypted_transaction
+        .transaction_data
+        .encrypted_data()
+        .clone();
+
+    let started_at = Instant::now();
+    let decrypted_data =
+        decrypt(skde_params, encrypted_data.as_ref(), &decryption_key).map_err(|e| {
+            tracing::error!(
+                "Decryption failed for key_id: {}: {:?}",
+                decryption_key_id,
+                e
+            );
+            Error::Decryption
+        })?;
+    metrics.observe_duration(
+        DECRYPTION_SECONDS,
+        &[("key_id", decryption_key_id.to_string().as_str())],
+        started_at.elapsed(),
+    );
+
+    skde_encrypted_transaction
+        .transaction_data
+        .decrypted_to_raw_transaction(&decrypted_data)
 }
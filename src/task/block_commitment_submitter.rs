@@ -0,0 +1,628 @@
+use std::collections::BTreeSet;
+
+use tokio::time::{sleep, Duration};
+
+use super::{sync_block_history, RetryPolicy};
+use crate::{
+    client::validation::{
+        self,
+        chain::{BlockTag, ChainClient},
+    },
+    error::Error,
+    state::AppState,
+    types::*,
+    util::current_timestamp,
+};
+
+/// Interval at which the pending submission queue is polled.
+const SUBMISSION_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_SUBMISSION_ATTEMPTS: u32 = 10;
+/// Upper bound in seconds of the backoff between two attempts.
+const MAX_SUBMISSION_BACKOFF: u64 = 300;
+/// Seconds between two checks of a sent transaction.
+const CONFIRMATION_INTERVAL: u64 = 12;
+
+/// Queues the block commitment if the submission policy of the rollup is due
+/// at `rollup_block_height`. Returns whether it was queued.
+///
+/// In aggregation mode the blocks of the range missing on this node, e.g.
+/// those built while another sequencer was the leader, are downloaded from
+/// the cluster before the aggregate is built.
+pub async fn enqueue_block_commitment(
+    context: &AppState,
+    rollup: &Rollup,
+    cluster: &Cluster,
+    rollup_block_height: u64,
+    block_commitment: &BlockCommitment,
+) -> Result<bool, Error> {
+    if rollup
+        .block_commitment_aggregation_size
+        .is_some_and(|aggregation_size| aggregation_size > 1)
+    {
+        let (from_rollup_block_height, _) =
+            match rollup.block_commitment_aggregation_range(rollup_block_height) {
+                Some(aggregation_range) => aggregation_range,
+                None => return Ok(false),
+            };
+
+        if let Err(error) = sync_block_history(
+            context,
+            rollup,
+            cluster,
+            from_rollup_block_height,
+            rollup_block_height,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to sync the blocks to aggregate - rollup_id: {:?} / from: {:?} / to: {:?} / error: {:?}",
+                rollup.rollup_id,
+                from_rollup_block_height,
+                rollup_block_height,
+                error
+            );
+        }
+
+        let aggregate_commitment = aggregate_block_commitment(
+            &rollup.rollup_id,
+            from_rollup_block_height,
+            rollup_block_height,
+        )
+        .await?;
+
+        return push_block_commitment(
+            &rollup.rollup_id,
+            rollup_block_height,
+            &aggregate_commitment,
+        );
+    }
+
+    let submission_policy = SubmissionPolicy::get_or(&rollup.rollup_id, SubmissionPolicy::default)
+        .map_err(Error::Database)?;
+    let submission_status = SubmissionStatus::get_or(&rollup.rollup_id, SubmissionStatus::default)
+        .map_err(Error::Database)?;
+
+    if !submission_policy.is_due(
+        rollup_block_height,
+        submission_status.last_queued_at,
+        current_timestamp(),
+    ) {
+        return Ok(false);
+    }
+
+    push_block_commitment(&rollup.rollup_id, rollup_block_height, block_commitment)
+}
+
+/// Builds the merkle root over the block commitments of
+/// `[from_rollup_block_height, to_rollup_block_height]` and stores the proof
+/// of every block against it.
+pub async fn aggregate_block_commitment(
+    rollup_id: &str,
+    from_rollup_block_height: u64,
+    to_rollup_block_height: u64,
+) -> Result<BlockCommitment, Error> {
+    let mut block_commitment_list = Vec::new();
+    for rollup_block_height in from_rollup_block_height..=to_rollup_block_height {
+        let block = Block::get(rollup_id, rollup_block_height).map_err(|error| {
+            tracing::error!(
+                "Missing block to aggregate - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                rollup_id,
+                rollup_block_height,
+                error
+            );
+            Error::BlockNotFound
+        })?;
+
+        block_commitment_list.push(block.block_commitment);
+    }
+
+    let merkle_tree = MerkleTree::new();
+    for block_commitment in block_commitment_list.iter() {
+        merkle_tree.add_data(block_commitment.as_hex_string()).await;
+    }
+    merkle_tree.finalize_tree().await;
+    let aggregate_commitment = BlockCommitment::from(merkle_tree.get_merkle_root().await);
+
+    for (index, block_commitment) in block_commitment_list.into_iter().enumerate() {
+        let rollup_block_height = from_rollup_block_height + index as u64;
+
+        BlockCommitmentProof {
+            rollup_block_height,
+            block_commitment,
+            from_rollup_block_height,
+            to_rollup_block_height,
+            merkle_path: merkle_tree.get_merkle_path(index).await,
+            aggregate_commitment: aggregate_commitment.clone(),
+        }
+        .put(rollup_id, rollup_block_height)
+        .map_err(Error::Database)?;
+    }
+
+    tracing::info!(
+        "Aggregated the block commitments - rollup_id: {:?} / from: {:?} / to: {:?} / aggregate_commitment: {:?}",
+        rollup_id,
+        from_rollup_block_height,
+        to_rollup_block_height,
+        aggregate_commitment.as_hex_string()
+    );
+
+    Ok(aggregate_commitment)
+}
+
+/// Queues the block commitment regardless of the submission policy.
+pub fn push_block_commitment(
+    rollup_id: &str,
+    rollup_block_height: u64,
+    block_commitment: &BlockCommitment,
+) -> Result<bool, Error> {
+    let mut pending_block_commitment_queue =
+        PendingBlockCommitmentQueue::get_mut_or(PendingBlockCommitmentQueue::default)
+            .map_err(Error::Database)?;
+    let is_queued = pending_block_commitment_queue.push(PendingBlockCommitment::new(
+        rollup_id,
+        rollup_block_height,
+        block_commitment.clone(),
+    ));
+    pending_block_commitment_queue
+        .update()
+        .map_err(Error::Database)?;
+
+    if is_queued {
+        let mut submission_status =
+            SubmissionStatus::get_mut_or(rollup_id, SubmissionStatus::default)
+                .map_err(Error::Database)?;
+        submission_status.last_queued_at = Some(current_timestamp());
+        submission_status.update().map_err(Error::Database)?;
+
+        tracing::info!(
+            "Queued the block commitment - rollup_id: {:?} / rollup_block_height: {:?}",
+            rollup_id,
+            rollup_block_height
+        );
+    }
+
+    Ok(is_queued)
+}
+
+/// Submits the queued block commitments in order and retries failures with
+/// exponential backoff.
+///
+/// Submissions through the same validation client are sent one at a time and
+/// in queue order, and a failure holds back the later ones of that client, so
+/// the transactions sent from the operator account never race for a nonce.
+/// The hash and nonce of a sent transaction are kept with the entry, and the
+/// chain is checked for them before the commitment is sent again. An entry
+/// out of attempts is kept as failed until `submit_block_commitment` queues
+/// it again.
+pub fn submit_block_commitment_periodically(context: AppState) {
+    let supervisor = context.supervisor().clone();
+
+    supervisor.spawn(
+        "block_commitment_submitter",
+        RetryPolicy::forever(),
+        move || run_submission_loop(context.clone()),
+    );
+}
+
+async fn run_submission_loop(context: AppState) -> Result<(), Error> {
+    loop {
+        sleep(SUBMISSION_INTERVAL).await;
+
+        if let Err(error) = submit_pending_block_commitment(&context).await {
+            tracing::error!("Failed to process the block commitment queue: {:?}", error);
+        }
+    }
+}
+
+async fn submit_pending_block_commitment(context: &AppState) -> Result<(), Error> {
+    // Take a snapshot so that the queue is not locked during submission.
+    let pending_block_commitment_queue =
+        PendingBlockCommitmentQueue::get_or(PendingBlockCommitmentQueue::default)
+            .map_err(Error::Database)?;
+    if pending_block_commitment_queue.is_empty() {
+        return Ok(());
+    }
+
+    let now = current_timestamp();
+    let mut blocked_client_set = BTreeSet::new();
+
+    for pending_block_commitment in pending_block_commitment_queue.iter() {
+        if pending_block_commitment.state == SubmissionState::Failed {
+            continue;
+        }
+
+        let rollup = match Rollup::get(&pending_block_commitment.rollup_id) {
+            Ok(rollup) => rollup,
+            Err(error) if error.is_none_type() => {
+                tracing::warn!(
+                    "Dropping the block commitment of an unknown rollup - rollup_id: {:?}",
+                    pending_block_commitment.rollup_id
+                );
+                remove_pending_block_commitment(pending_block_commitment)?;
+                continue;
+            }
+            Err(error) => return Err(Error::Database(error)),
+        };
+
+        let client_key = (
+            rollup.validation_info.platform,
+            rollup.validation_info.validation_service_provider,
+        );
+        if blocked_client_set.contains(&client_key) {
+            continue;
+        }
+
+        if pending_block_commitment.next_attempt_at > now {
+            blocked_client_set.insert(client_key);
+            continue;
+        }
+
+        let submission_result =
+            process_pending_block_commitment(context, &rollup, pending_block_commitment).await;
+        if !matches!(submission_result, Ok(SubmissionResult::Confirmed(_))) {
+            blocked_client_set.insert(client_key);
+        }
+
+        match submission_result {
+            Ok(SubmissionResult::Confirmed(transaction_hash)) => {
+                tracing::info!(
+                    "Submitted the block commitment - rollup_id: {:?} / rollup_block_height: {:?} / transaction_hash: {:?}",
+                    pending_block_commitment.rollup_id,
+                    pending_block_commitment.rollup_block_height,
+                    transaction_hash
+                );
+
+                remove_pending_block_commitment(pending_block_commitment)?;
+
+                let mut submission_status = SubmissionStatus::get_mut_or(
+                    &pending_block_commitment.rollup_id,
+                    SubmissionStatus::default,
+                )
+                .map_err(Error::Database)?;
+                submission_status.last_submitted_rollup_block_height =
+                    Some(pending_block_commitment.rollup_block_height);
+                submission_status.last_submitted_at = Some(current_timestamp());
+                submission_status.last_transaction_hash = Some(transaction_hash);
+                submission_status.submitted_count += 1;
+                submission_status.update().map_err(Error::Database)?;
+            }
+            Ok(SubmissionResult::Pending(submission_transaction)) => {
+                update_pending_block_commitment(
+                    pending_block_commitment,
+                    |queued_block_commitment| {
+                        queued_block_commitment.state = SubmissionState::Submitted;
+                        queued_block_commitment.submission_transaction =
+                            Some(submission_transaction);
+                        queued_block_commitment.next_attempt_at =
+                            current_timestamp() + CONFIRMATION_INTERVAL;
+                    },
+                )?;
+            }
+            Ok(SubmissionResult::Failed(error)) => {
+                let attempt_count = pending_block_commitment.attempt_count + 1;
+                tracing::warn!(
+                    "Failed to submit the block commitment - rollup_id: {:?} / rollup_block_height: {:?} / attempt: {:?} / error: {:?}",
+                    pending_block_commitment.rollup_id,
+                    pending_block_commitment.rollup_block_height,
+                    attempt_count,
+                    error
+                );
+
+                let is_failed = attempt_count >= MAX_SUBMISSION_ATTEMPTS;
+                update_pending_block_commitment(
+                    pending_block_commitment,
+                    |queued_block_commitment| {
+                        queued_block_commitment.submission_transaction = None;
+                        queued_block_commitment.attempt_count = attempt_count;
+                        if is_failed {
+                            queued_block_commitment.state = SubmissionState::Failed;
+                        } else {
+                            queued_block_commitment.state = SubmissionState::Queued;
+                            queued_block_commitment.next_attempt_at =
+                                current_timestamp() + submission_backoff(attempt_count);
+                        }
+                    },
+                )?;
+
+                if is_failed {
+                    tracing::error!(
+                        "Gave up submitting the block commitment - rollup_id: {:?} / rollup_block_height: {:?}",
+                        pending_block_commitment.rollup_id,
+                        pending_block_commitment.rollup_block_height
+                    );
+
+                    let mut submission_status = SubmissionStatus::get_mut_or(
+                        &pending_block_commitment.rollup_id,
+                        SubmissionStatus::default,
+                    )
+                    .map_err(Error::Database)?;
+                    submission_status.failed_count += 1;
+                    submission_status.update().map_err(Error::Database)?;
+                }
+            }
+            // The state of the sent transaction is unknown, so keep it and
+            // check again later.
+            Err(error) => {
+                tracing::warn!(
+                    "Failed to check the block commitment submission - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                    pending_block_commitment.rollup_id,
+                    pending_block_commitment.rollup_block_height,
+                    error
+                );
+
+                update_pending_block_commitment(
+                    pending_block_commitment,
+                    |queued_block_commitment| {
+                        queued_block_commitment.next_attempt_at =
+                            current_timestamp() + CONFIRMATION_INTERVAL;
+                    },
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the delay in seconds before the next attempt after
+/// `attempt_count` failed ones.
+fn submission_backoff(attempt_count: u32) -> u64 {
+    2_u64
+        .saturating_pow(attempt_count)
+        .min(MAX_SUBMISSION_BACKOFF)
+}
+
+fn update_pending_block_commitment(
+    pending_block_commitment: &PendingBlockCommitment,
+    update: impl FnOnce(&mut PendingBlockCommitment),
+) -> Result<(), Error> {
+    let mut pending_block_commitment_queue =
+        PendingBlockCommitmentQueue::get_mut_or(PendingBlockCommitmentQueue::default)
+            .map_err(Error::Database)?;
+    if let Some(queued_block_commitment) = pending_block_commitment_queue.find_mut(
+        &pending_block_commitment.rollup_id,
+        pending_block_commitment.rollup_block_height,
+    ) {
+        update(queued_block_commitment);
+    }
+    pending_block_commitment_queue
+        .update()
+        .map_err(Error::Database)?;
+
+    Ok(())
+}
+
+fn remove_pending_block_commitment(
+    pending_block_commitment: &PendingBlockCommitment,
+) -> Result<(), Error> {
+    let mut pending_block_commitment_queue =
+        PendingBlockCommitmentQueue::get_mut_or(PendingBlockCommitmentQueue::default)
+            .map_err(Error::Database)?;
+    pending_block_commitment_queue.remove(
+        &pending_block_commitment.rollup_id,
+        pending_block_commitment.rollup_block_height,
+    );
+    pending_block_commitment_queue
+        .update()
+        .map_err(Error::Database)?;
+
+    Ok(())
+}
+
+enum SubmissionResult {
+    /// The transaction with this hash was mined.
+    Confirmed(String),
+    /// The transaction was sent and is not mined yet.
+    Pending(SubmissionTransaction),
+    /// Nothing is in flight anymore and the commitment has to be sent again.
+    Failed(Error),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TransactionState {
+    Confirmed,
+    Reverted,
+    /// Another transaction of the operator took the nonce.
+    Replaced,
+    Pending,
+}
+
+fn transaction_state(
+    transaction_status: Option<bool>,
+    latest_transaction_count: u64,
+    nonce: u64,
+) -> TransactionState {
+    match transaction_status {
+        Some(true) => TransactionState::Confirmed,
+        Some(false) => TransactionState::Reverted,
+        None if latest_transaction_count > nonce => TransactionState::Replaced,
+        None => TransactionState::Pending,
+    }
+}
+
+/// Checks the transaction already sent for the commitment on chain, or sends
+/// one if there is none. Returns an error only if the state of a sent
+/// transaction could not be read.
+async fn process_pending_block_commitment(
+    context: &AppState,
+    rollup: &Rollup,
+    pending_block_commitment: &PendingBlockCommitment,
+) -> Result<SubmissionResult, Error> {
+    let validation_info = ValidationInfo::get(
+        rollup.validation_info.platform,
+        rollup.validation_info.validation_service_provider,
+    )
+    .map_err(Error::Database)?;
+    let chain_client = ChainClient::new(validation_info.validation_rpc_url());
+
+    let submission_transaction = match &pending_block_commitment.submission_transaction {
+        Some(submission_transaction) => submission_transaction,
+        None => {
+            return Ok(
+                match submit_block_commitment(
+                    context,
+                    rollup,
+                    &validation_info,
+                    &chain_client,
+                    pending_block_commitment,
+                )
+                .await
+                {
+                    Ok(submission_transaction) => {
+                        tracing::info!(
+                        "Sent the block commitment - rollup_id: {:?} / rollup_block_height: {:?} / transaction_hash: {:?} / nonce: {:?}",
+                        pending_block_commitment.rollup_id,
+                        pending_block_commitment.rollup_block_height,
+                        submission_transaction.transaction_hash,
+                        submission_transaction.nonce
+                    );
+
+                        SubmissionResult::Pending(submission_transaction)
+                    }
+                    Err(error) => SubmissionResult::Failed(error),
+                },
+            );
+        }
+    };
+
+    let transaction_status = chain_client
+        .get_transaction_status(&submission_transaction.transaction_hash)
+        .await?;
+    let latest_transaction_count = chain_client
+        .get_transaction_count(&submission_transaction.operator_address, BlockTag::Latest)
+        .await?;
+
+    Ok(
+        match transaction_state(
+            transaction_status,
+            latest_transaction_count,
+            submission_transaction.nonce,
+        ) {
+            TransactionState::Confirmed => {
+                SubmissionResult::Confirmed(submission_transaction.transaction_hash.clone())
+            }
+            TransactionState::Reverted => SubmissionResult::Failed(Error::ValidationClient(
+                format!(
+                    "Transaction {} reverted",
+                    submission_transaction.transaction_hash
+                )
+                .into(),
+            )),
+            TransactionState::Replaced => SubmissionResult::Failed(Error::ValidationClient(
+                format!(
+                    "Nonce {} of transaction {} was taken by another transaction",
+                    submission_transaction.nonce, submission_transaction.transaction_hash
+                )
+                .into(),
+            )),
+            TransactionState::Pending => SubmissionResult::Pending(submission_transaction.clone()),
+        },
+    )
+}
+
+/// Registers the block commitment on the validation service of the rollup and
+/// returns the sent transaction.
+async fn submit_block_commitment(
+    context: &AppState,
+    rollup: &Rollup,
+    validation_info: &ValidationInfo,
+    chain_client: &ChainClient,
+    pending_block_commitment: &PendingBlockCommitment,
+) -> Result<SubmissionTransaction, Error> {
+    let validation_platform = rollup.validation_info.platform;
+    let validation_service_provider = rollup.validation_info.validation_service_provider;
+
+    let block_commitment = pending_block_commitment
+        .block_commitment
+        .as_bytes()
+        .map_err(|_| Error::Deserialize)?;
+
+    match validation_info {
+        ValidationInfo::EigenLayer(_) => {
+            let validation_client: validation::eigenlayer::ValidationClient = context
+                .get_validation_client(validation_platform, validation_service_provider)
+                .await
+                .map_err(Error::CachedKvStore)?;
+
+            let operator_address = validation_client.publisher().address().as_hex_string();
+            let nonce = chain_client
+                .get_transaction_count(&operator_address, BlockTag::Pending)
+                .await?;
+
+            let transaction_hash = validation_client
+                .publisher()
+                .register_block_commitment(
+                    &rollup.cluster_id,
+                    &rollup.rollup_id,
+                    pending_block_commitment.rollup_block_height,
+                    &block_commitment,
+                )
+                .await
+                .map(|transaction_hash| format!("{:?}", transaction_hash))
+                .map_err(|error| Error::ValidationClient(error.to_string().into()))?;
+
+            Ok(SubmissionTransaction {
+                operator_address,
+                transaction_hash,
+                nonce,
+            })
+        }
+        ValidationInfo::Symbiotic(_) => {
+            let validation_client: validation::symbiotic::ValidationClient = context
+                .get_validation_client(validation_platform, validation_service_provider)
+                .await
+                .map_err(Error::CachedKvStore)?;
+
+            let operator_address = validation_client.publisher().address().as_hex_string();
+            let nonce = chain_client
+                .get_transaction_count(&operator_address, BlockTag::Pending)
+                .await?;
+
+            let transaction_hash = validation_client
+                .publisher()
+                .register_block_commitment(
+                    &rollup.cluster_id,
+                    &rollup.rollup_id,
+                    pending_block_commitment.rollup_block_height,
+                    &block_commitment,
+                )
+                .await
+                .map(|transaction_hash| format!("{:?}", transaction_hash))
+                .map_err(|error| Error::ValidationClient(error.to_string().into()))?;
+
+            Ok(SubmissionTransaction {
+                operator_address,
+                transaction_hash,
+                nonce,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submission_backoff_doubles_up_to_the_bound() {
+        assert_eq!(submission_backoff(1), 2);
+        assert_eq!(submission_backoff(2), 4);
+        assert_eq!(submission_backoff(8), 256);
+        assert_eq!(submission_backoff(9), MAX_SUBMISSION_BACKOFF);
+        assert_eq!(submission_backoff(u32::MAX), MAX_SUBMISSION_BACKOFF);
+    }
+
+    #[test]
+    fn sent_transaction_is_resent_only_once_its_nonce_is_taken() {
+        assert_eq!(
+            transaction_state(Some(true), 8, 7),
+            TransactionState::Confirmed
+        );
+        assert_eq!(
+            transaction_state(Some(false), 8, 7),
+            TransactionState::Reverted
+        );
+        assert_eq!(transaction_state(None, 7, 7), TransactionState::Pending);
+        assert_eq!(transaction_state(None, 8, 7), TransactionState::Replaced);
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeSet;
+
+use radius_sdk::json_rpc::{client::Id, server::RpcParameter};
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    error::Error,
+    rpc::cluster::{GetBlockRange, GetBlockRangeResponse, MAX_BLOCK_RANGE},
+    state::AppState,
+    types::*,
+};
+
+/// Interval at which the retained block history is checked for gaps.
+const BLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Downloads the last `block_sync_depth` blocks of every rollup from the other
+/// cluster members, once on startup and then periodically, so that a newly
+/// joined sequencer can serve the history of the rollups it sequences.
+pub fn sync_block_history_periodically(context: AppState) {
+    let supervisor = context.supervisor().clone();
+
+    supervisor.spawn("block_sync", RetryPolicy::forever(), move || {
+        run_block_sync_loop(context.clone())
+    });
+}
+
+async fn run_block_sync_loop(context: AppState) -> Result<(), Error> {
+    loop {
+        let rollup_id_list = match RollupIdList::get_or(RollupIdList::default) {
+            Ok(rollup_id_list) => rollup_id_list,
+            Err(error) => {
+                tracing::error!("Failed to get the rollup ID list: {:?}", error);
+                sleep(BLOCK_SYNC_INTERVAL).await;
+                continue;
+            }
+        };
+
+        for rollup_id in rollup_id_list.iter() {
+            if let Err(error) = sync_retained_block_history(&context, rollup_id).await {
+                tracing::warn!(
+                    "Failed to sync the block history - rollup_id: {:?} / error: {:?}",
+                    rollup_id,
+                    error
+                );
+            }
+        }
+
+        sleep(BLOCK_SYNC_INTERVAL).await;
+    }
+}
+
+async fn sync_retained_block_history(context: &AppState, rollup_id: &str) -> Result<(), Error> {
+    let block_sync_depth = context.config().block_sync_depth;
+    if block_sync_depth == 0 {
+        return Ok(());
+    }
+
+    let rollup_metadata = match RollupMetadata::get(rollup_id) {
+        Ok(rollup_metadata) => rollup_metadata,
+        Err(error) if error.is_none_type() => return Ok(()),
+        Err(error) => return Err(Error::Database(error)),
+    };
+
+    let rollup = Rollup::get(rollup_id).map_err(|_| Error::RollupNotFound)?;
+    let cluster = Cluster::get(
+        rollup.platform,
+        rollup.service_provider,
+        &rollup.cluster_id,
+        rollup_metadata.platform_block_height,
+    )
+    .map_err(|_| Error::ClusterNotFound)?;
+
+    // The block at the current height is still being sequenced.
+    let to_rollup_block_height = rollup_metadata.rollup_block_height;
+    // Do not download again what the pruner has removed.
+    let pruning_status =
+        PruningStatus::get_or(rollup_id, PruningStatus::default).map_err(Error::Database)?;
+    let from_rollup_block_height = to_rollup_block_height
+        .saturating_sub(block_sync_depth)
+        .max(pruning_status.pruned_rollup_block_height);
+
+    sync_block_history(
+        context,
+        &rollup,
+        &cluster,
+        from_rollup_block_height,
+        to_rollup_block_height,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Fetches the blocks missing in `[from_rollup_block_height,
+/// to_rollup_block_height)` from the other cluster members, verifies them and
+/// stores them. Returns the number of blocks stored.
+///
+/// A block is accepted only if its raw transactions hash to its block
+/// commitment and the commitment is signed by its creator, who must be a
+/// member of the cluster.
+pub async fn sync_block_history(
+    context: &AppState,
+    rollup: &Rollup,
+    cluster: &Cluster,
+    from_rollup_block_height: u64,
+    to_rollup_block_height: u64,
+) -> Result<u64, Error> {
+    let missing_rollup_block_height_set = get_missing_rollup_block_height_set(
+        &rollup.rollup_id,
+        from_rollup_block_height,
+        to_rollup_block_height,
+    )?;
+    if missing_rollup_block_height_set.is_empty() {
+        return Ok(0);
+    }
+
+    let others_cluster_rpc_url_list = cluster.get_others_cluster_rpc_url_list();
+    if others_cluster_rpc_url_list.is_empty() {
+        return Err(Error::NoEndpointsAvailable);
+    }
+
+    tracing::info!(
+        "Syncing the block history - rollup_id: {:?} / from: {:?} / to: {:?} / missing: {:?}",
+        rollup.rollup_id,
+        from_rollup_block_height,
+        to_rollup_block_height,
+        missing_rollup_block_height_set.len()
+    );
+
+    let sequencer_address_list = cluster.get_sequencer_address_list();
+
+    let mut stored_count = 0;
+    for (from_rollup_block_height, to_rollup_block_height) in
+        group_rollup_block_height_range(&missing_rollup_block_height_set)
+    {
+        let parameter = GetBlockRange {
+            rollup_id: rollup.rollup_id.clone(),
+            from_rollup_block_height,
+            to_rollup_block_height,
+        };
+
+        let response: GetBlockRangeResponse = context
+            .rpc_client()
+            .fetch(
+                others_cluster_rpc_url_list.clone(),
+                GetBlockRange::method(),
+                &parameter,
+                Id::Null,
+            )
+            .await?;
+
+        for block in response.block_list {
+            if !missing_rollup_block_height_set.contains(&block.block_height) {
+                continue;
+            }
+
+            if !sequencer_address_list.contains(&block.block_creator_address) {
+                tracing::warn!(
+                    "Rejecting a block created outside the cluster - rollup_id: {:?} / rollup_block_height: {:?} / block_creator_address: {:?}",
+                    rollup.rollup_id,
+                    block.block_height,
+                    block.block_creator_address.as_hex_string()
+                );
+                continue;
+            }
+
+            if let Err(error) = block.verify(rollup.platform.into()).await {
+                tracing::warn!(
+                    "Rejecting an invalid block - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                    rollup.rollup_id,
+                    block.block_height,
+                    error
+                );
+                continue;
+            }
+
+            Block::put(&block, &rollup.rollup_id, block.block_height).map_err(Error::Database)?;
+            // The creation time of a downloaded block is unknown, so its age
+            // counts from the download.
+            BlockTimestamp::now()
+                .put(&rollup.rollup_id, block.block_height)
+                .map_err(Error::Database)?;
+
+            stored_count += 1;
+        }
+    }
+
+    Ok(stored_count)
+}
+
+fn get_missing_rollup_block_height_set(
+    rollup_id: &str,
+    from_rollup_block_height: u64,
+    to_rollup_block_height: u64,
+) -> Result<BTreeSet<u64>, Error> {
+    let mut missing_rollup_block_height_set = BTreeSet::new();
+
+    for rollup_block_height in from_rollup_block_height..to_rollup_block_height {
+        match Block::get(rollup_id, rollup_block_height) {
+            Ok(_) => {}
+            Err(error) if error.is_none_type() => {
+                missing_rollup_block_height_set.insert(rollup_block_height);
+            }
+            Err(error) => return Err(Error::Database(error)),
+        }
+    }
+
+    Ok(missing_rollup_block_height_set)
+}
+
+/// Groups the heights into contiguous `[from, to)` ranges of at most
+/// `MAX_BLOCK_RANGE` heights.
+fn group_rollup_block_height_range(rollup_block_height_set: &BTreeSet<u64>) -> Vec<(u64, u64)> {
+    let mut range_list: Vec<(u64, u64)> = Vec::new();
+
+    for &rollup_block_height in rollup_block_height_set.iter() {
+        match range_list.last_mut() {
+            Some((from, to)) if *to == rollup_block_height && *to - *from < MAX_BLOCK_RANGE => {
+                *to += 1;
+            }
+            _others => range_list.push((rollup_block_height, rollup_block_height + 1)),
+        }
+    }
+
+    range_list
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use radius_sdk::json_rpc::server::RpcParameter;
+use reqwest::Client;
+
+use super::RetryPolicy;
+use crate::{
+    error::Error,
+    rpc::external::{Health, SendEncryptedTransaction, SendRawTransaction},
+    state::AppState,
+    transaction_buffer::BufferedTransaction,
+};
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Buffers `transaction` if the leader cannot be reached. Returns `true` if
+/// the transaction was buffered and `false` if the leader answered the health
+/// check, in which case the forwarding error came from the leader itself and
+/// is returned to the client instead.
+pub async fn buffer_if_leader_unavailable(
+    context: &AppState,
+    leader_external_rpc_url: &str,
+    rollup_id: &str,
+    transaction: BufferedTransaction,
+) -> Result<bool, Error> {
+    if health_check(leader_external_rpc_url).await.is_ok() {
+        return Ok(false);
+    }
+
+    tracing::warn!(
+        "Leader is unavailable, buffering the transaction - rollup_id: {:?} / leader_external_rpc_url: {:?}",
+        rollup_id,
+        leader_external_rpc_url
+    );
+
+    context
+        .transaction_buffer()
+        .push(rollup_id, transaction)
+        .await?;
+
+    Ok(true)
+}
+
+/// Calls the `health` method of the RPC server at `sequencer_rpc_url`. Any
+/// JSON-RPC response, including an error object, means the server is
+/// reachable; only a transport failure or a body that is not a JSON-RPC
+/// response is an error.
+pub async fn health_check(sequencer_rpc_url: impl AsRef<str>) -> Result<(), Error> {
+    let client = Client::builder()
+        .timeout(HEALTH_CHECK_TIMEOUT)
+        .build()
+        .map_err(Error::InvalidURL)?;
+
+    let response: serde_json::Value = client
+        .post(sequencer_rpc_url.as_ref())
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": Health::method(),
+            "params": {},
+            "id": 0,
+        }))
+        .send()
+        .await
+        .map_err(Error::HealthCheck)?
+        .json()
+        .await
+        .map_err(Error::HealthCheck)?;
+
+    if !is_json_rpc_response(&response) {
+        return Err(Error::Unhealthy);
+    }
+
+    Ok(())
+}
+
+fn is_json_rpc_response(response: &serde_json::Value) -> bool {
+    response.get("jsonrpc").and_then(|version| version.as_str()) == Some("2.0")
+        && (response.get("result").is_some() || response.get("error").is_some())
+}
+
+/// Replays the transactions buffered for `rollup_id` through the regular
+/// send handlers, which order them locally if this node became the leader or
+/// forward them to the new leader otherwise.
+pub fn replay_buffered_transaction(context: AppState, rollup_id: String) {
+    let supervisor = context.supervisor().clone();
+    let name = format!("replay_buffered_transaction:{}", rollup_id);
+
+    supervisor.spawn(name, RetryPolicy::forever(), move || {
+        replay(context.clone(), rollup_id.clone())
+    });
+}
+
+async fn replay(context: AppState, rollup_id: String) -> Result<(), Error> {
+    let buffered_transaction_list = context.transaction_buffer().drain(&rollup_id).await;
+    if buffered_transaction_list.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(
+        "Replaying buffered transactions - rollup_id: {:?} / count: {:?}",
+        rollup_id,
+        buffered_transaction_list.len()
+    );
+
+    for buffered_transaction in buffered_transaction_list {
+        let result = match buffered_transaction {
+            BufferedTransaction::Raw(raw_transaction) => SendRawTransaction {
+                rollup_id: rollup_id.clone(),
+                raw_transaction,
+            }
+            .handler(context.clone())
+            .await
+            .map(|_| ()),
+            BufferedTransaction::Encrypted(encrypted_transaction) => SendEncryptedTransaction {
+                rollup_id: rollup_id.clone(),
+                encrypted_transaction,
+            }
+            .handler(context.clone())
+            .await
+            .map(|_| ()),
+        };
+
+        if let Err(error) = result {
+            tracing::warn!(
+                "Failed to replay a buffered transaction - rollup_id: {:?} / error: {:?}",
+                rollup_id,
+                error
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_json_rpc_error_means_the_leader_is_reachable() {
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": { "code": -32601, "message": "Method not found" },
+            "id": 0,
+        });
+
+        assert!(is_json_rpc_response(&response));
+    }
+
+    #[test]
+    fn a_json_rpc_result_means_the_leader_is_reachable() {
+        let response = serde_json::json!({ "jsonrpc": "2.0", "result": {}, "id": 0 });
+
+        assert!(is_json_rpc_response(&response));
+    }
+
+    #[test]
+    fn a_body_that_is_not_a_json_rpc_response_means_the_leader_is_unavailable() {
+        assert!(!is_json_rpc_response(&serde_json::json!({ "status": 502 })));
+        assert!(!is_json_rpc_response(&serde_json::json!({ "result": {} })));
+        assert!(!is_json_rpc_response(&serde_json::Value::Null));
+    }
+}
@@ -1,3 +1,17 @@
 mod block_builder;
+mod block_commitment_submitter;
+mod block_sync;
+mod leader_failover;
+mod order_commitment_checker;
+mod pruner;
+mod supervisor;
+mod transaction_backfill;
 
 pub use block_builder::*;
+pub use block_commitment_submitter::*;
+pub use block_sync::*;
+pub use leader_failover::*;
+pub use order_commitment_checker::*;
+pub use pruner::*;
+pub use supervisor::*;
+pub use transaction_backfill::*;
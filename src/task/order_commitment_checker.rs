@@ -0,0 +1,158 @@
+use crate::{error::Error, types::*};
+
+/// Checks every signed order commitment stored for the first
+/// `transaction_count` orders of `block` against the transactions the block
+/// actually contains and stores the evidence of each violation.
+pub async fn detect_order_commitment_violation(
+    rollup_id: &str,
+    block: &Block,
+    transaction_count: u64,
+) {
+    let evidence_list = match check_order_commitment(rollup_id, block, transaction_count).await {
+        Ok(evidence_list) => evidence_list,
+        Err(error) => {
+            tracing::error!(
+                "Failed to check order commitments - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+                rollup_id,
+                block.block_height,
+                error
+            );
+            return;
+        }
+    };
+
+    if evidence_list.is_empty() {
+        return;
+    }
+
+    for evidence in evidence_list.iter() {
+        tracing::warn!(
+            "Order commitment violation - rollup_id: {:?} / rollup_block_height: {:?} / transaction_order: {:?} / kind: {:?}",
+            evidence.rollup_id,
+            evidence.rollup_block_height,
+            evidence.transaction_order,
+            evidence.kind
+        );
+    }
+
+    if let Err(error) = OrderCommitmentEvidenceList::put(
+        &OrderCommitmentEvidenceList { evidence_list },
+        rollup_id,
+        block.block_height,
+    ) {
+        tracing::error!(
+            "Failed to store order commitment evidence - rollup_id: {:?} / rollup_block_height: {:?} / error: {:?}",
+            rollup_id,
+            block.block_height,
+            error
+        );
+    }
+}
+
+/// Returns the evidence of every signed order commitment for `block` that the
+/// block contradicts. Only commitments carrying a valid signature of the block
+/// creator count as evidence.
+pub async fn check_order_commitment(
+    rollup_id: &str,
+    block: &Block,
+    transaction_count: u64,
+) -> Result<Vec<OrderCommitmentEvidence>, Error> {
+    let platform = Rollup::get(rollup_id)
+        .map_err(|_| Error::RollupNotFound)?
+        .platform;
+
+    let raw_transaction_hash_list: Vec<RawTransactionHash> = block
+        .raw_transaction_list
+        .iter()
+        .map(|raw_transaction| raw_transaction.raw_transaction_hash())
+        .collect::<Result<_, _>>()?;
+
+    // Rebuilt leaf by leaf to get the pre-merkle path of every order.
+    let merkle_tree = MerkleTree::new();
+    let mut evidence_list = Vec::new();
+
+    // Orders may be missing from the middle of the block, so the scan covers
+    // every order the leader handed out.
+    let order_count = transaction_count.max(block.raw_transaction_list.len() as u64);
+    for transaction_order in 0..order_count {
+        let raw_transaction = block.raw_transaction_list.get(transaction_order as usize);
+        let included_pre_merkle_path =
+            match raw_transaction_hash_list.get(transaction_order as usize) {
+                Some(raw_transaction_hash) => {
+                    merkle_tree.add_data(raw_transaction_hash.as_ref()).await.1
+                }
+                None => Vec::new(),
+            };
+
+        let order_commitment =
+            match OrderCommitment::get(rollup_id, block.block_height, transaction_order) {
+                Ok(order_commitment) => Some(order_commitment),
+                Err(error) if error.is_none_type() => None,
+                Err(error) => return Err(Error::Database(error)),
+            };
+
+        let order_commitment = match order_commitment {
+            Some(order_commitment) => order_commitment,
+            None => continue,
+        };
+
+        let included_transaction_hash_list = raw_transaction
+            .map(|raw_transaction| raw_transaction.raw_transaction_hash_list())
+            .transpose()?
+            .unwrap_or_default();
+        // Every transaction of a bundle has an order, and a leaf, of its own.
+        let included_transaction_hash = raw_transaction_hash_list.get(transaction_order as usize);
+
+        for sign_order_commitment in order_commitment
+            .sign_order_commitment_list(transaction_order)
+            .into_iter()
+            .filter(|order_commitment| {
+                order_commitment.data.rollup_id == rollup_id
+                    && order_commitment.data.block_height == block.block_height
+            })
+        {
+            if let Err(error) = sign_order_commitment
+                .verify(platform.into(), &block.block_creator_address, None)
+                .await
+            {
+                tracing::warn!(
+                    "Skipping an order commitment not signed by the block creator - rollup_id: {:?} / rollup_block_height: {:?} / transaction_order: {:?} / error: {:?}",
+                    rollup_id,
+                    block.block_height,
+                    transaction_order,
+                    error
+                );
+                continue;
+            }
+
+            let committed_transaction_hash =
+                RawTransactionHash::from(sign_order_commitment.data.transaction_hash.clone());
+
+            let kind = if raw_transaction.is_none() {
+                OrderCommitmentViolationKind::MissingTransaction
+            } else if included_transaction_hash != Some(&committed_transaction_hash) {
+                OrderCommitmentViolationKind::TransactionMismatch
+            } else if sign_order_commitment.data.pre_merkle_path != included_pre_merkle_path {
+                OrderCommitmentViolationKind::PreMerklePathMismatch
+            } else {
+                continue;
+            };
+
+            evidence_list.push(OrderCommitmentEvidence {
+                kind,
+                order_commitment: sign_order_commitment.clone(),
+                rollup_id: rollup_id.to_owned(),
+                rollup_block_height: block.block_height,
+                transaction_order,
+                included_transaction_hash_list: included_transaction_hash_list.clone(),
+                included_pre_merkle_path: included_pre_merkle_path.clone(),
+                raw_transaction_hash_list: raw_transaction_hash_list.clone(),
+                block_commitment: block.block_commitment.clone(),
+                block_signature: block.signature.clone(),
+                block_creator_address: block.block_creator_address.clone(),
+            });
+        }
+    }
+
+    Ok(evidence_list)
+}
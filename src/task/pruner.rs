@@ -0,0 +1,299 @@
+use tokio::time::{sleep, Duration};
+
+use super::RetryPolicy;
+use crate::{error::Error, state::AppState, types::*, util::current_timestamp};
+
+/// Interval at which every rollup is pruned according to its retention policy.
+const PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of blocks pruned per rollup in a single run so that a
+/// backlog of old blocks is removed gradually.
+const MAX_PRUNED_BLOCKS_PER_RUN: u64 = 1024;
+
+/// Periodically prunes every rollup that has a retention policy.
+pub fn prune_periodically(context: AppState) {
+    context
+        .supervisor()
+        .spawn("pruner", RetryPolicy::forever(), run_pruning_loop);
+}
+
+async fn run_pruning_loop() -> Result<(), Error> {
+    loop {
+        sleep(PRUNING_INTERVAL).await;
+
+        let rollup_id_list = match RollupIdList::get_or(RollupIdList::default) {
+            Ok(rollup_id_list) => rollup_id_list,
+            Err(error) => {
+                tracing::error!("Failed to get the rollup ID list: {:?}", error);
+                continue;
+            }
+        };
+
+        for rollup_id in rollup_id_list.iter() {
+            let pruned_rollup_id = rollup_id.clone();
+            let result = tokio::task::spawn_blocking(move || prune_rollup(&pruned_rollup_id))
+                .await
+                .map_err(|error| Error::TaskPanicked(error.to_string()))
+                .and_then(|result| result);
+
+            if let Err(error) = result {
+                tracing::warn!(
+                    "Failed to prune the rollup - rollup_id: {:?} / error: {:?}",
+                    rollup_id,
+                    error
+                );
+            }
+        }
+    }
+}
+
+/// Removes the blocks that fall outside the retention policy of the rollup,
+/// together with their transactions, order commitments and transaction hash
+/// indexes. Order commitment evidence is kept.
+pub fn prune_rollup(rollup_id: &str) -> Result<PruningStatus, Error> {
+    let retention_policy =
+        RetentionPolicy::get_or(rollup_id, RetentionPolicy::default).map_err(Error::Database)?;
+    let mut pruning_status =
+        PruningStatus::get_mut_or(rollup_id, PruningStatus::default).map_err(Error::Database)?;
+
+    if !retention_policy.is_enabled() {
+        return Ok(PruningStatus::clone(&pruning_status));
+    }
+
+    let rollup_metadata = match RollupMetadata::get(rollup_id) {
+        Ok(rollup_metadata) => rollup_metadata,
+        Err(error) if error.is_none_type() => return Ok(PruningStatus::clone(&pruning_status)),
+        Err(error) => return Err(Error::Database(error)),
+    };
+
+    let from_rollup_block_height = pruning_status.pruned_rollup_block_height;
+    let to_rollup_block_height = get_pruning_target_height(
+        rollup_id,
+        &retention_policy,
+        from_rollup_block_height,
+        rollup_metadata.rollup_block_height,
+    )?;
+    if to_rollup_block_height <= from_rollup_block_height {
+        return Ok(PruningStatus::clone(&pruning_status));
+    }
+
+    let mut pruned_transaction_count = 0;
+    for rollup_block_height in from_rollup_block_height..to_rollup_block_height {
+        pruned_transaction_count += prune_block(rollup_id, rollup_block_height)?;
+    }
+
+    tracing::info!(
+        "Pruned the rollup - rollup_id: {:?} / from: {:?} / to: {:?} / transaction_count: {:?}",
+        rollup_id,
+        from_rollup_block_height,
+        to_rollup_block_height,
+        pruned_transaction_count
+    );
+
+    pruning_status.pruned_rollup_block_height = to_rollup_block_height;
+    pruning_status.pruned_block_count += to_rollup_block_height - from_rollup_block_height;
+    pruning_status.pruned_transaction_count += pruned_transaction_count;
+    pruning_status.last_pruned_at = Some(current_timestamp());
+    let pruned_status = PruningStatus::clone(&pruning_status);
+    pruning_status.update().map_err(Error::Database)?;
+
+    Ok(pruned_status)
+}
+
+/// Returns the height below which every block is pruned. The block being
+/// sequenced at `current_rollup_block_height` is never pruned.
+fn get_pruning_target_height(
+    rollup_id: &str,
+    retention_policy: &RetentionPolicy,
+    from_rollup_block_height: u64,
+    current_rollup_block_height: u64,
+) -> Result<u64, Error> {
+    let max_rollup_block_height = current_rollup_block_height
+        .min(from_rollup_block_height.saturating_add(MAX_PRUNED_BLOCKS_PER_RUN));
+
+    let mut to_rollup_block_height = from_rollup_block_height;
+
+    if let Some(block_count) = retention_policy.block_count {
+        to_rollup_block_height =
+            to_rollup_block_height.max(current_rollup_block_height.saturating_sub(block_count));
+    }
+
+    if let Some(max_age) = retention_policy.max_age {
+        let expired_before = current_timestamp().saturating_sub(max_age);
+
+        // Heights without a timestamp were stored before timestamps were
+        // recorded or never built here, so they count as expired.
+        for rollup_block_height in from_rollup_block_height..max_rollup_block_height {
+            let is_expired = match BlockTimestamp::get(rollup_id, rollup_block_height) {
+                Ok(block_timestamp) => block_timestamp.as_secs() < expired_before,
+                Err(error) if error.is_none_type() => true,
+                Err(error) => return Err(Error::Database(error)),
+            };
+            if !is_expired {
+                break;
+            }
+
+            to_rollup_block_height = to_rollup_block_height.max(rollup_block_height + 1);
+        }
+    }
+
+    Ok(to_rollup_block_height.min(max_rollup_block_height))
+}
+
+/// Deletes a block and everything stored for its transaction orders. Returns
+/// the number of transaction orders removed.
+fn prune_block(rollup_id: &str, rollup_block_height: u64) -> Result<u64, Error> {
+    let transaction_count = match Block::get(rollup_id, rollup_block_height) {
+        Ok(block) => block.raw_transaction_list.len() as u64,
+        Err(error) if error.is_none_type() => 0,
+        Err(error) => return Err(Error::Database(error)),
+    };
+
+    let pruned_transaction_count =
+        delete_transaction_list(rollup_id, rollup_block_height, transaction_count)?;
+
+    Block::delete(rollup_id, rollup_block_height).map_err(Error::Database)?;
+    BlockTimestamp::delete(rollup_id, rollup_block_height).map_err(Error::Database)?;
+
+    Ok(pruned_transaction_count)
+}
+
+/// Deletes the transactions and order commitments of a block abandoned before
+/// it was built, together with the merkle tree checkpoint of the rollup, so
+/// that none of them ends up in the block rebuilt at the same height. Returns
+/// the number of transaction orders removed.
+pub fn discard_block_transaction_list(
+    rollup_id: &str,
+    rollup_block_height: u64,
+    transaction_count: u64,
+) -> Result<u64, Error> {
+    let discarded_transaction_count =
+        delete_transaction_list(rollup_id, rollup_block_height, transaction_count)?;
+
+    MerkleTreeCheckpoint::delete(rollup_id).map_err(Error::Database)?;
+
+    Ok(discarded_transaction_count)
+}
+
+/// Deletes the transactions, their transaction hash indexes and the order
+/// commitments stored for the orders of a block. Returns the number of
+/// transaction orders removed.
+fn delete_transaction_list(
+    rollup_id: &str,
+    rollup_block_height: u64,
+    transaction_count: u64,
+) -> Result<u64, Error> {
+    // Transactions are stored by order even if the block was never built
+    // here, so keep going past `transaction_count` until the first empty
+    // order. The orders covered by an encrypted bundle, which is stored at
+    // the first of them, are not empty either.
+    let mut transaction_order = 0;
+    let mut bundle_covered_until = 0;
+    loop {
+        let raw_transaction =
+            match RawTransactionModel::get(rollup_id, rollup_block_height, transaction_order) {
+                Ok((raw_transaction, _is_direct_sent)) => Some(raw_transaction),
+                Err(error) if error.is_none_type() => None,
+                Err(error) => return Err(Error::Database(error)),
+            };
+        let encrypted_transaction =
+            match EncryptedTransactionModel::get(rollup_id, rollup_block_height, transaction_order)
+            {
+                Ok(encrypted_transaction) => Some(encrypted_transaction),
+                Err(error) if error.is_none_type() => None,
+                Err(error) => return Err(Error::Database(error)),
+            };
+
+        if let Some(encrypted_transaction) = encrypted_transaction.as_ref() {
+            bundle_covered_until = bundle_covered_until.max(
+                transaction_order + encrypted_transaction.raw_transaction_hash_list().len() as u64,
+            );
+        }
+
+        if raw_transaction.is_none()
+            && encrypted_transaction.is_none()
+            && transaction_order >= transaction_count
+            && transaction_order >= bundle_covered_until
+        {
+            break;
+        }
+
+        if let Some(raw_transaction) = raw_transaction {
+            RawTransactionModel::delete_with_transaction_hash(
+                rollup_id,
+                &raw_transaction.raw_transaction_hash()?,
+            )
+            .map_err(Error::Database)?;
+            RawTransactionModel::delete(rollup_id, rollup_block_height, transaction_order)
+                .map_err(Error::Database)?;
+        }
+
+        if let Some(encrypted_transaction) = encrypted_transaction {
+            for transaction_hash in encrypted_transaction.raw_transaction_hash_list() {
+                EncryptedTransactionModel::delete_with_transaction_hash(
+                    rollup_id,
+                    &transaction_hash,
+                )
+                .map_err(Error::Database)?;
+            }
+            EncryptedTransactionModel::delete(rollup_id, rollup_block_height, transaction_order)
+                .map_err(Error::Database)?;
+        }
+
+        OrderCommitment::delete(rollup_id, rollup_block_height, transaction_order)
+            .map_err(Error::Database)?;
+
+        transaction_order += 1;
+    }
+
+    Ok(transaction_order)
+}
+
+/// Deletes everything stored for a rollup regardless of its retention
+/// policy, including the order commitment evidence, the policies and the
+/// rollup itself. Returns the number of block heights removed.
+pub fn purge_rollup(rollup_id: &str) -> Result<u64, Error> {
+    let from_rollup_block_height = PruningStatus::get_or(rollup_id, PruningStatus::default)
+        .map_err(Error::Database)?
+        .pruned_rollup_block_height;
+    let to_rollup_block_height = match RollupMetadata::get(rollup_id) {
+        Ok(rollup_metadata) => rollup_metadata.rollup_block_height,
+        Err(error) if error.is_none_type() => from_rollup_block_height,
+        Err(error) => return Err(Error::Database(error)),
+    };
+
+    let mut purged_transaction_count = 0;
+    for rollup_block_height in from_rollup_block_height..=to_rollup_block_height {
+        purged_transaction_count += prune_block(rollup_id, rollup_block_height)?;
+
+        BlockCommitmentProof::delete(rollup_id, rollup_block_height).map_err(Error::Database)?;
+        OrderCommitmentEvidenceList::delete(rollup_id, rollup_block_height)
+            .map_err(Error::Database)?;
+    }
+
+    let mut pending_block_commitment_queue =
+        PendingBlockCommitmentQueue::get_mut_or(PendingBlockCommitmentQueue::default)
+            .map_err(Error::Database)?;
+    pending_block_commitment_queue.remove_rollup(rollup_id);
+    pending_block_commitment_queue
+        .update()
+        .map_err(Error::Database)?;
+
+    MerkleTreeCheckpoint::delete(rollup_id).map_err(Error::Database)?;
+    RetentionPolicy::delete(rollup_id).map_err(Error::Database)?;
+    PruningStatus::delete(rollup_id).map_err(Error::Database)?;
+    SubmissionPolicy::delete(rollup_id).map_err(Error::Database)?;
+    SubmissionStatus::delete(rollup_id).map_err(Error::Database)?;
+    RollupMetadata::delete(rollup_id).map_err(Error::Database)?;
+    Rollup::delete(rollup_id).map_err(Error::Database)?;
+
+    let purged_block_count = to_rollup_block_height - from_rollup_block_height + 1;
+    tracing::info!(
+        "Purged the rollup - rollup_id: {:?} / block_count: {:?} / transaction_count: {:?}",
+        rollup_id,
+        purged_block_count,
+        purged_transaction_count
+    );
+
+    Ok(purged_block_count)
+}
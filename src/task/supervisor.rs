@@ -0,0 +1,372 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    task::JoinHandle,
+    time::{sleep, Duration},
+};
+
+use crate::{error::Error, util::current_timestamp};
+
+/// Number of finished tasks whose status is kept for inspection.
+const MAX_FINISHED_TASK_COUNT: usize = 256;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Attempts including the first one. `None` retries forever.
+    pub max_attempt_count: Option<u32>,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Runs the task once. For tasks that are not safe to repeat.
+    pub const NEVER: Self = Self {
+        max_attempt_count: Some(1),
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    /// Retries a task that can be repeated without side effects up to
+    /// `max_attempt_count` attempts in total.
+    pub const fn idempotent(max_attempt_count: u32) -> Self {
+        Self {
+            max_attempt_count: Some(max_attempt_count),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Restarts a long-running task whenever it stops.
+    pub const fn forever() -> Self {
+        Self {
+            max_attempt_count: None,
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    fn can_retry(&self, attempt_count: u32) -> bool {
+        self.max_attempt_count
+            .map_or(true, |max_attempt_count| attempt_count < max_attempt_count)
+    }
+
+    fn backoff(&self, attempt_count: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt_count.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Failed and waiting for the next attempt.
+    Retrying,
+    Succeeded,
+    /// Failed and out of attempts.
+    Failed,
+}
+
+impl TaskState {
+    fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub attempt_count: u32,
+    pub failure_count: u64,
+    pub last_error: Option<String>,
+    /// Unix timestamp in seconds at which the task was first started.
+    pub started_at: u64,
+    /// Unix timestamp in seconds of the last state change.
+    pub updated_at: u64,
+}
+
+impl TaskStatus {
+    fn new(name: String) -> Self {
+        let now = current_timestamp();
+
+        Self {
+            name,
+            state: TaskState::Running,
+            attempt_count: 0,
+            failure_count: 0,
+            last_error: None,
+            started_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Runs named background tasks, turning their failures and panics into
+/// [`Error`]s, retrying them according to a [`RetryPolicy`] and keeping their
+/// status for the `get_task_status` internal RPC.
+pub struct Supervisor {
+    inner: Arc<Mutex<SupervisorInner>>,
+}
+
+#[derive(Default)]
+struct SupervisorInner {
+    task_map: BTreeMap<String, TaskStatus>,
+    finished_task_list: VecDeque<String>,
+}
+
+impl Clone for Supervisor {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SupervisorInner::default())),
+        }
+    }
+}
+
+impl Supervisor {
+    /// Spawns `task` under `name`. Every attempt calls `task` again to get a
+    /// fresh future.
+    pub fn spawn<F, Fut>(
+        &self,
+        name: impl Into<String>,
+        retry_policy: RetryPolicy,
+        task: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        let name = name.into();
+
+        tokio::spawn(async move { supervisor.run(name, retry_policy, task).await })
+    }
+
+    async fn run<F, Fut>(&self, name: String, retry_policy: RetryPolicy, mut task: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        let mut attempt_count: u32 = 0;
+
+        loop {
+            attempt_count += 1;
+            self.update(&name, |status| {
+                status.state = TaskState::Running;
+                status.attempt_count = attempt_count;
+            });
+
+            // The attempt runs in its own task so that a panic is caught as a
+            // join error instead of silently ending the supervisor.
+            let result = match tokio::spawn(task()).await {
+                Ok(result) => result,
+                Err(error) => Err(Error::TaskPanicked(error.to_string())),
+            };
+
+            let error = match result {
+                Ok(()) => {
+                    self.update(&name, |status| status.state = TaskState::Succeeded);
+                    return;
+                }
+                Err(error) => error,
+            };
+
+            let can_retry = retry_policy.can_retry(attempt_count);
+            self.update(&name, |status| {
+                status.state = if can_retry {
+                    TaskState::Retrying
+                } else {
+                    TaskState::Failed
+                };
+                status.failure_count += 1;
+                status.last_error = Some(format!("{:?}", error));
+            });
+
+            if !can_retry {
+                tracing::error!(
+                    "Task failed - name: {:?} / attempt: {:?} / error: {:?}",
+                    name,
+                    attempt_count,
+                    error
+                );
+                return;
+            }
+
+            let backoff = retry_policy.backoff(attempt_count);
+            tracing::warn!(
+                "Task failed, retrying in {:?} - name: {:?} / attempt: {:?} / error: {:?}",
+                backoff,
+                name,
+                attempt_count,
+                error
+            );
+            sleep(backoff).await;
+        }
+    }
+
+    /// Records the outcome of work driven by someone else, e.g. an event
+    /// callback, under `name`. The next report overwrites the state.
+    pub fn report(&self, name: &str, result: &Result<(), Error>) {
+        self.update_with(name, false, |status| {
+            status.attempt_count += 1;
+
+            match result {
+                Ok(()) => status.state = TaskState::Succeeded,
+                Err(error) => {
+                    status.state = TaskState::Failed;
+                    status.failure_count += 1;
+                    status.last_error = Some(format!("{:?}", error));
+                }
+            }
+        });
+    }
+
+    pub fn status_list(&self) -> Vec<TaskStatus> {
+        match self.inner.lock() {
+            Ok(inner) => inner.task_map.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn update(&self, name: &str, f: impl FnOnce(&mut TaskStatus)) {
+        self.update_with(name, true, f);
+    }
+
+    /// Updates the status of `name`. Finished tasks are evicted oldest first
+    /// past [`MAX_FINISHED_TASK_COUNT`] unless `is_evictable` is `false`.
+    fn update_with(&self, name: &str, is_evictable: bool, f: impl FnOnce(&mut TaskStatus)) {
+        let mut inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(_) => return,
+        };
+
+        let status = inner
+            .task_map
+            .entry(name.to_owned())
+            .or_insert_with(|| TaskStatus::new(name.to_owned()));
+        f(status);
+        status.updated_at = current_timestamp();
+        let is_finished = status.state.is_finished();
+
+        inner
+            .finished_task_list
+            .retain(|finished_task| finished_task != name);
+        if is_evictable && is_finished {
+            inner.finished_task_list.push_back(name.to_owned());
+        }
+
+        while inner.finished_task_list.len() > MAX_FINISHED_TASK_COUNT {
+            if let Some(finished_task) = inner.finished_task_list.pop_front() {
+                inner.task_map.remove(&finished_task);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    const FAST_RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_attempt_count: Some(3),
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(1),
+    };
+
+    fn task_status(supervisor: &Supervisor, name: &str) -> TaskStatus {
+        supervisor
+            .status_list()
+            .into_iter()
+            .find(|status| status.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_bound() {
+        let retry_policy = RetryPolicy::idempotent(5);
+
+        assert_eq!(retry_policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(retry_policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(retry_policy.backoff(5), Duration::from_secs(16));
+        assert_eq!(retry_policy.backoff(6), Duration::from_secs(30));
+        assert_eq!(retry_policy.backoff(u32::MAX), Duration::from_secs(30));
+
+        let retry_policy = RetryPolicy::forever();
+        assert_eq!(retry_policy.backoff(1), Duration::from_secs(5));
+        assert_eq!(retry_policy.backoff(10), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retries_within_the_attempt_count() {
+        let retry_policy = RetryPolicy::idempotent(3);
+        assert!(retry_policy.can_retry(2));
+        assert!(!retry_policy.can_retry(3));
+
+        assert!(!RetryPolicy::NEVER.can_retry(1));
+        assert!(RetryPolicy::forever().can_retry(u32::MAX));
+    }
+
+    #[tokio::test]
+    async fn retries_a_failed_task_until_it_succeeds() {
+        let supervisor = Supervisor::default();
+        let attempt_count = Arc::new(AtomicU32::new(0));
+
+        supervisor
+            .spawn("flaky", FAST_RETRY_POLICY, {
+                let attempt_count = attempt_count.clone();
+                move || {
+                    let attempt_count = attempt_count.clone();
+                    async move {
+                        match attempt_count.fetch_add(1, Ordering::SeqCst) {
+                            0 => Err(Error::Parse),
+                            _ => Ok(()),
+                        }
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        let status = task_status(&supervisor, "flaky");
+        assert_eq!(status.state, TaskState::Succeeded);
+        assert_eq!(status.attempt_count, 2);
+        assert_eq!(status.failure_count, 1);
+    }
+
+    #[tokio::test]
+    async fn turns_panics_into_failures_and_gives_up() {
+        let supervisor = Supervisor::default();
+
+        supervisor
+            .spawn("panicking", FAST_RETRY_POLICY, || async {
+                let key_map: BTreeMap<&str, u64> = BTreeMap::new();
+                let _value = key_map["missing key"];
+
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let status = task_status(&supervisor, "panicking");
+        assert_eq!(status.state, TaskState::Failed);
+        assert_eq!(status.attempt_count, 3);
+        assert_eq!(status.failure_count, 3);
+        assert!(status.last_error.unwrap().contains("TaskPanicked"));
+    }
+}
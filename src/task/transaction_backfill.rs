@@ -0,0 +1,535 @@
+use std::collections::BTreeSet;
+
+use radius_sdk::{
+    json_rpc::{client::Id, server::RpcParameter},
+    signature::Address,
+};
+use tokio::time::{sleep, Duration};
+
+use super::RetryPolicy;
+use crate::{
+    error::Error,
+    rpc::cluster::{
+        GetTransactionsRange, GetTransactionsRangeResponse, SyncEncryptedTransactionMessage,
+        SyncRawTransactionMessage, TransactionRangeEntry, MAX_TRANSACTIONS_RANGE,
+    },
+    state::AppState,
+    types::*,
+};
+
+/// Interval at which followers look for gaps in the current block.
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Periodically backfills the gaps in the current block of every rollup this
+/// node follows.
+pub fn backfill_transaction_periodically(context: AppState) {
+    let supervisor = context.supervisor().clone();
+
+    supervisor.spawn("transaction_backfill", RetryPolicy::forever(), move || {
+        run_backfill_loop(context.clone())
+    });
+}
+
+async fn run_backfill_loop(context: AppState) -> Result<(), Error> {
+    loop {
+        sleep(BACKFILL_INTERVAL).await;
+
+        let rollup_id_list = match RollupIdList::get_or(RollupIdList::default) {
+            Ok(rollup_id_list) => rollup_id_list,
+            Err(error) => {
+                tracing::error!("Failed to get the rollup ID list: {:?}", error);
+                continue;
+            }
+        };
+
+        for rollup_id in rollup_id_list.iter() {
+            if let Err(error) = backfill_current_block(&context, rollup_id).await {
+                tracing::warn!(
+                    "Failed to backfill transactions - rollup_id: {:?} / error: {:?}",
+                    rollup_id,
+                    error
+                );
+            }
+        }
+    }
+}
+
+async fn backfill_current_block(context: &AppState, rollup_id: &str) -> Result<(), Error> {
+    let rollup_metadata = match RollupMetadata::get(rollup_id) {
+        Ok(rollup_metadata) => rollup_metadata,
+        Err(error) if error.is_none_type() => return Ok(()),
+        Err(error) => return Err(Error::Database(error)),
+    };
+
+    // The leader orders every transaction itself.
+    if rollup_metadata.is_leader {
+        return Ok(());
+    }
+
+    let rollup = Rollup::get(rollup_id).map_err(|_| Error::RollupNotFound)?;
+    let cluster = Cluster::get(
+        rollup.platform,
+        rollup.service_provider,
+        &rollup.cluster_id,
+        rollup_metadata.platform_block_height,
+    )
+    .map_err(|_| Error::ClusterNotFound)?;
+
+    // Followers only know about orders below the highest order received.
+    backfill_transaction(
+        context,
+        &cluster,
+        rollup_id,
+        rollup_metadata.rollup_block_height,
+        rollup_metadata.transaction_order,
+        &rollup_metadata.leader_sequencer_rpc_info.address,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Fetches the transactions missing in `[0, transaction_count)` from the
+/// other cluster members in batches and stores the ones whose order
+/// commitment from `leader_address` matches them. Returns the number of
+/// transactions filled.
+pub async fn backfill_transaction(
+    context: &AppState,
+    cluster: &Cluster,
+    rollup_id: &str,
+    rollup_block_height: u64,
+    transaction_count: u64,
+    leader_address: &Address,
+) -> Result<u64, Error> {
+    let missing_transaction_order_set =
+        get_missing_transaction_order_set(rollup_id, rollup_block_height, transaction_count)?;
+    if missing_transaction_order_set.is_empty() {
+        return Ok(0);
+    }
+
+    let others_cluster_rpc_url_list = cluster.get_others_cluster_rpc_url_list();
+    if others_cluster_rpc_url_list.is_empty() {
+        return Err(Error::NoEndpointsAvailable);
+    }
+
+    let platform = Rollup::get(rollup_id)
+        .map_err(|_| Error::RollupNotFound)?
+        .platform;
+
+    tracing::info!(
+        "Backfilling transactions - rollup_id: {:?} / rollup_block_height: {:?} / missing: {:?}",
+        rollup_id,
+        rollup_block_height,
+        missing_transaction_order_set.len()
+    );
+
+    let mut filled_count = 0;
+    for (from_transaction_order, to_transaction_order) in
+        group_transaction_order_range(&missing_transaction_order_set)
+    {
+        let parameter = GetTransactionsRange {
+            rollup_id: rollup_id.to_owned(),
+            rollup_block_height,
+            from_transaction_order,
+            to_transaction_order,
+        };
+
+        let response: GetTransactionsRangeResponse = context
+            .rpc_client()
+            .fetch(
+                others_cluster_rpc_url_list.clone(),
+                GetTransactionsRange::method(),
+                &parameter,
+                Id::Null,
+            )
+            .await?;
+
+        for entry in response.transaction_list {
+            if !missing_transaction_order_set.contains(&entry.transaction_order) {
+                continue;
+            }
+
+            let transaction_order = entry.transaction_order;
+            if let Err(error) = store_backfilled_transaction(
+                platform,
+                leader_address,
+                rollup_id,
+                rollup_block_height,
+                entry,
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Rejected a backfilled transaction - rollup_id: {:?} / rollup_block_height: {:?} / transaction_order: {:?} / error: {:?}",
+                    rollup_id,
+                    rollup_block_height,
+                    transaction_order,
+                    error
+                );
+                continue;
+            }
+
+            filled_count += 1;
+        }
+    }
+
+    Ok(filled_count)
+}
+
+/// Verifies a transaction received from another cluster member and stores it
+/// the way the sync handlers do. Unlike a sync message, the entry carries no
+/// leader signature of its own, so its order commitment has to prove it.
+async fn store_backfilled_transaction(
+    platform: Platform,
+    leader_address: &Address,
+    rollup_id: &str,
+    rollup_block_height: u64,
+    entry: TransactionRangeEntry,
+) -> Result<(), Error> {
+    let order_commitment = entry
+        .order_commitment
+        .ok_or(Error::OrderCommitmentMismatch)?;
+
+    // Check every part of the entry before storing any of it.
+    let mut transaction_hash_list = Vec::new();
+    if let Some(encrypted_transaction) = entry.encrypted_transaction.as_ref() {
+        // A bundle is stored at the first order it covers.
+        let transaction_hash = encrypted_transaction
+            .raw_transaction_hash_list()
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyTransactionBundle)?;
+        transaction_hash_list.push(transaction_hash);
+    }
+    if let Some(raw_transaction) = entry.raw_transaction.as_ref() {
+        transaction_hash_list.push(raw_transaction.raw_transaction_hash()?);
+    }
+    for transaction_hash in transaction_hash_list.iter() {
+        verify_order_commitment(
+            platform,
+            leader_address,
+            rollup_id,
+            rollup_block_height,
+            entry.transaction_order,
+            transaction_hash,
+            &order_commitment,
+        )
+        .await?;
+    }
+
+    if let Some(encrypted_transaction) = entry.encrypted_transaction {
+        SyncEncryptedTransactionMessage {
+            rollup_id: rollup_id.to_owned(),
+            rollup_block_height,
+            transaction_order: entry.transaction_order,
+            encrypted_transaction,
+            order_commitment: order_commitment.clone(),
+        }
+        .store()?;
+    }
+
+    if let Some(raw_transaction) = entry.raw_transaction {
+        SyncRawTransactionMessage {
+            rollup_id: rollup_id.to_owned(),
+            rollup_block_height,
+            transaction_order: entry.transaction_order,
+            raw_transaction,
+            order_commitment: Some(order_commitment),
+            is_direct_sent: entry.is_direct_sent,
+        }
+        .store()?;
+    }
+
+    Ok(())
+}
+
+/// Checks that `order_commitment` commits to `transaction_hash`, the merkle
+/// leaf of the transaction, at `transaction_order` and, if it is signed, that
+/// `leader_address` signed it. Transaction hash commitments carry no
+/// signature, so only the hash can be checked for them unless they are part of
+/// a bundle, whose list the leader signs as a whole.
+async fn verify_order_commitment(
+    platform: Platform,
+    leader_address: &Address,
+    rollup_id: &str,
+    rollup_block_height: u64,
+    transaction_order: u64,
+    transaction_hash: &RawTransactionHash,
+    order_commitment: &OrderCommitment,
+) -> Result<(), Error> {
+    let transaction_hash: &str = transaction_hash.as_ref();
+
+    if let OrderCommitment::Single(SingleOrderCommitment::TransactionHash(order_commitment)) =
+        order_commitment
+    {
+        let committed_transaction_hash: &str = order_commitment.as_ref();
+        if committed_transaction_hash != transaction_hash {
+            return Err(Error::OrderCommitmentMismatch);
+        }
+
+        return Ok(());
+    }
+
+    if let OrderCommitment::Bundle(bundle_order_commitment) = order_commitment {
+        bundle_order_commitment
+            .signature
+            .verify_message(
+                platform.into(),
+                &bundle_order_commitment.order_commitment_list,
+                leader_address,
+            )
+            .map_err(|_| Error::InvalidSignature)?;
+
+        let is_committed =
+            bundle_order_commitment
+                .order_commitment_list
+                .iter()
+                .any(|order_commitment| match order_commitment {
+                    SingleOrderCommitment::TransactionHash(order_commitment) => {
+                        let committed_transaction_hash: &str = order_commitment.as_ref();
+                        committed_transaction_hash == transaction_hash
+                    }
+                    SingleOrderCommitment::Sign(_) => false,
+                });
+        if is_committed {
+            return Ok(());
+        }
+    }
+
+    let sign_order_commitment = order_commitment
+        .sign_order_commitment_list(transaction_order)
+        .into_iter()
+        .next()
+        .ok_or(Error::OrderCommitmentMismatch)?;
+
+    sign_order_commitment
+        .verify(platform.into(), leader_address, None)
+        .await?;
+
+    let data = &sign_order_commitment.data;
+    if data.rollup_id != rollup_id
+        || data.block_height != rollup_block_height
+        || data.transaction_hash != transaction_hash
+    {
+        return Err(Error::OrderCommitmentMismatch);
+    }
+
+    Ok(())
+}
+
+fn get_missing_transaction_order_set(
+    rollup_id: &str,
+    rollup_block_height: u64,
+    transaction_count: u64,
+) -> Result<BTreeSet<u64>, Error> {
+    let mut missing_transaction_order_set = BTreeSet::new();
+    // An encrypted bundle is stored at the first order it covers, so the
+    // orders it covers are not missing.
+    let mut bundle_covered_until: u64 = 0;
+
+    for transaction_order in 0..transaction_count {
+        match EncryptedTransactionModel::get(rollup_id, rollup_block_height, transaction_order) {
+            Ok(encrypted_transaction) => {
+                let covered_count = encrypted_transaction.raw_transaction_hash_list().len() as u64;
+                bundle_covered_until =
+                    bundle_covered_until.max(transaction_order + covered_count.max(1));
+            }
+            Err(error) if error.is_none_type() => {}
+            Err(error) => return Err(Error::Database(error)),
+        }
+
+        if transaction_order < bundle_covered_until {
+            continue;
+        }
+
+        match RawTransactionModel::get(rollup_id, rollup_block_height, transaction_order) {
+            Ok(_) => {}
+            Err(error) if error.is_none_type() => {
+                missing_transaction_order_set.insert(transaction_order);
+            }
+            Err(error) => return Err(Error::Database(error)),
+        }
+    }
+
+    Ok(missing_transaction_order_set)
+}
+
+/// Groups the orders into contiguous `[from, to)` ranges of at most
+/// `MAX_TRANSACTIONS_RANGE` orders.
+fn group_transaction_order_range(transaction_order_set: &BTreeSet<u64>) -> Vec<(u64, u64)> {
+    let mut range_list: Vec<(u64, u64)> = Vec::new();
+
+    for &transaction_order in transaction_order_set.iter() {
+        match range_list.last_mut() {
+            Some((from, to))
+                if *to == transaction_order && *to - *from < MAX_TRANSACTIONS_RANGE =>
+            {
+                *to += 1;
+            }
+            _others => range_list.push((transaction_order, transaction_order + 1)),
+        }
+    }
+
+    range_list
+}
+
+#[cfg(test)]
+mod tests {
+    use radius_sdk::signature::{ChainType, PrivateKeySigner};
+
+    use super::*;
+
+    const LEADER_SIGNING_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OTHER_SIGNING_KEY: &str =
+        "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn signer(signing_key: &str) -> PrivateKeySigner {
+        PrivateKeySigner::from_str(ChainType::Ethereum, signing_key).unwrap()
+    }
+
+    fn transaction_hash() -> RawTransactionHash {
+        RawTransactionHash::from([7; 32])
+    }
+
+    fn sign_order_commitment(signing_key: &str, transaction_order: u64) -> OrderCommitment {
+        let data = OrderCommitmentData {
+            rollup_id: "rollup_id".to_owned(),
+            block_height: 3,
+            transaction_order,
+            transaction_hash: transaction_hash().as_string(),
+            pre_merkle_path: Vec::new(),
+        };
+
+        OrderCommitment::Single(SingleOrderCommitment::Sign(SignOrderCommitment {
+            signature: signer(signing_key)
+                .sign_message(&data)
+                .unwrap()
+                .as_hex_string(),
+            data,
+        }))
+    }
+
+    async fn verify(order_commitment: &OrderCommitment) -> Result<(), Error> {
+        verify_order_commitment(
+            Platform::Ethereum,
+            signer(LEADER_SIGNING_KEY).address(),
+            "rollup_id",
+            3,
+            5,
+            &transaction_hash(),
+            order_commitment,
+        )
+        .await
+    }
+
+    #[test]
+    fn groups_contiguous_orders_into_bounded_ranges() {
+        let transaction_order_set: BTreeSet<u64> = [0, 1, 2, 5, 7, 8].into_iter().collect();
+        assert_eq!(
+            group_transaction_order_range(&transaction_order_set),
+            vec![(0, 3), (5, 6), (7, 9)]
+        );
+
+        let transaction_order_set: BTreeSet<u64> = (0..MAX_TRANSACTIONS_RANGE + 1).collect();
+        assert_eq!(
+            group_transaction_order_range(&transaction_order_set),
+            vec![
+                (0, MAX_TRANSACTIONS_RANGE),
+                (MAX_TRANSACTIONS_RANGE, MAX_TRANSACTIONS_RANGE + 1)
+            ]
+        );
+
+        assert!(group_transaction_order_range(&BTreeSet::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn accepts_the_leader_order_commitment() {
+        verify(&sign_order_commitment(LEADER_SIGNING_KEY, 5))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_an_order_commitment_signed_by_another_key() {
+        assert!(matches!(
+            verify(&sign_order_commitment(OTHER_SIGNING_KEY, 5)).await,
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_order_commitment_for_another_order() {
+        assert!(matches!(
+            verify(&sign_order_commitment(LEADER_SIGNING_KEY, 4)).await,
+            Err(Error::OrderCommitmentMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_the_leader_signature_of_a_bundle_order_commitment() {
+        let bundle_order_commitment = |signing_key: &str| {
+            let order_commitment_list = vec![
+                SingleOrderCommitment::TransactionHash(TransactionHashOrderCommitment::new(
+                    RawTransactionHash::from([6; 32]).as_string(),
+                )),
+                SingleOrderCommitment::TransactionHash(TransactionHashOrderCommitment::new(
+                    transaction_hash().as_string(),
+                )),
+            ];
+
+            OrderCommitment::Bundle(BundleOrderCommitment {
+                signature: signer(signing_key)
+                    .sign_message(&order_commitment_list)
+                    .unwrap(),
+                order_commitment_list,
+            })
+        };
+
+        verify(&bundle_order_commitment(LEADER_SIGNING_KEY))
+            .await
+            .unwrap();
+        assert!(matches!(
+            verify(&bundle_order_commitment(OTHER_SIGNING_KEY)).await,
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_entry_without_an_order_commitment() {
+        let entry = TransactionRangeEntry {
+            transaction_order: 5,
+            raw_transaction: None,
+            is_direct_sent: false,
+            encrypted_transaction: None,
+            order_commitment: None,
+        };
+
+        assert!(matches!(
+            store_backfilled_transaction(
+                Platform::Ethereum,
+                signer(LEADER_SIGNING_KEY).address(),
+                "rollup_id",
+                3,
+                entry,
+            )
+            .await,
+            Err(Error::OrderCommitmentMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_the_hash_of_a_transaction_hash_commitment() {
+        let order_commitment = |transaction_hash: RawTransactionHash| {
+            OrderCommitment::Single(SingleOrderCommitment::TransactionHash(
+                TransactionHashOrderCommitment::new(transaction_hash.as_string()),
+            ))
+        };
+
+        verify(&order_commitment(transaction_hash())).await.unwrap();
+        assert!(matches!(
+            verify(&order_commitment(RawTransactionHash::from([8; 32]))).await,
+            Err(Error::OrderCommitmentMismatch)
+        ));
+    }
+}
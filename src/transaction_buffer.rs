@@ -0,0 +1,84 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use tokio::sync::Mutex;
+
+use crate::{error::Error, types::*};
+
+/// Number of transactions kept per rollup while its leader is unreachable.
+pub const TRANSACTION_BUFFER_CAPACITY: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub enum BufferedTransaction {
+    Raw(RawTransaction),
+    Encrypted(EncryptedTransaction),
+}
+
+/// Bounded per-rollup queue of transactions a follower could not forward to
+/// the leader. The queue is drained and replayed once the next leader is
+/// announced.
+pub struct TransactionBuffer {
+    inner: Arc<TransactionBufferInner>,
+}
+
+struct TransactionBufferInner {
+    capacity: usize,
+    buffer: Mutex<HashMap<String, VecDeque<BufferedTransaction>>>,
+}
+
+impl Clone for TransactionBuffer {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for TransactionBuffer {
+    fn default() -> Self {
+        Self::new(TRANSACTION_BUFFER_CAPACITY)
+    }
+}
+
+impl TransactionBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(TransactionBufferInner {
+                capacity,
+                buffer: Mutex::new(HashMap::default()),
+            }),
+        }
+    }
+
+    pub async fn push(
+        &self,
+        rollup_id: &str,
+        transaction: BufferedTransaction,
+    ) -> Result<(), Error> {
+        let mut buffer = self.inner.buffer.lock().await;
+        let queue = buffer.entry(rollup_id.to_owned()).or_default();
+
+        if queue.len() >= self.inner.capacity {
+            return Err(Error::TransactionBufferFull);
+        }
+        queue.push_back(transaction);
+
+        Ok(())
+    }
+
+    /// Removes and returns the buffered transactions of `rollup_id` in the
+    /// order they were received.
+    pub async fn drain(&self, rollup_id: &str) -> Vec<BufferedTransaction> {
+        let mut buffer = self.inner.buffer.lock().await;
+
+        buffer.remove(rollup_id).map(Vec::from).unwrap_or_default()
+    }
+
+    pub async fn len(&self, rollup_id: &str) -> usize {
+        let buffer = self.inner.buffer.lock().await;
+
+        buffer.get(rollup_id).map(VecDeque::len).unwrap_or_default()
+    }
+}
@@ -0,0 +1,148 @@
+use crate::types::prelude::*;
+
+/// Proof that a block commitment is a leaf of the aggregate commitment
+/// submitted for the range `[from_rollup_block_height,
+/// to_rollup_block_height]`.
+///
+/// The leaves of the aggregate are the hex block commitments of the range in
+/// height order, hashed the same way as [`MerkleTree::add_data`].
+#[derive(Clone, Debug, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str, rollup_block_height: u64))]
+pub struct BlockCommitmentProof {
+    pub rollup_block_height: u64,
+    pub block_commitment: BlockCommitment,
+
+    pub from_rollup_block_height: u64,
+    pub to_rollup_block_height: u64,
+
+    #[serde(
+        serialize_with = "serialize_merkle_path",
+        deserialize_with = "deserialize_merkle_path"
+    )]
+    pub merkle_path: Vec<[u8; 32]>,
+    pub aggregate_commitment: BlockCommitment,
+}
+
+impl BlockCommitmentProof {
+    /// Returns the range `[from, to]` of `aggregation_size` blocks closed at
+    /// `rollup_block_height`, or `None` if no range ends there. Sizes below
+    /// two disable aggregation.
+    pub fn aggregation_range(
+        aggregation_size: u64,
+        rollup_block_height: u64,
+    ) -> Option<(u64, u64)> {
+        if aggregation_size < 2 || (rollup_block_height + 1) % aggregation_size != 0 {
+            return None;
+        }
+
+        Some((
+            rollup_block_height + 1 - aggregation_size,
+            rollup_block_height,
+        ))
+    }
+
+    /// Checks the proof against an aggregate commitment the caller trusts,
+    /// e.g. the one registered on the validation service.
+    pub fn verify(&self, aggregate_commitment: &BlockCommitment) -> bool {
+        if self.rollup_block_height < self.from_rollup_block_height
+            || self.rollup_block_height > self.to_rollup_block_height
+        {
+            return false;
+        }
+
+        let merkle_root: [u8; 32] = match aggregate_commitment
+            .as_bytes()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+        {
+            Some(merkle_root) => merkle_root,
+            None => return false,
+        };
+
+        MerkleTree::verify_merkle_path(
+            MerkleTree::leaf_hash(self.block_commitment.as_hex_string()),
+            (self.rollup_block_height - self.from_rollup_block_height) as usize,
+            &self.merkle_path,
+            merkle_root,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn aggregate(
+        block_commitment_list: &[BlockCommitment],
+        from_rollup_block_height: u64,
+    ) -> Vec<BlockCommitmentProof> {
+        let merkle_tree = MerkleTree::new();
+        for block_commitment in block_commitment_list {
+            merkle_tree.add_data(block_commitment.as_hex_string()).await;
+        }
+        merkle_tree.finalize_tree().await;
+        let aggregate_commitment = BlockCommitment::from(merkle_tree.get_merkle_root().await);
+
+        let mut block_commitment_proof_list = Vec::new();
+        for (index, block_commitment) in block_commitment_list.iter().enumerate() {
+            block_commitment_proof_list.push(BlockCommitmentProof {
+                rollup_block_height: from_rollup_block_height + index as u64,
+                block_commitment: block_commitment.clone(),
+                from_rollup_block_height,
+                to_rollup_block_height: from_rollup_block_height
+                    + block_commitment_list.len() as u64
+                    - 1,
+                merkle_path: merkle_tree.get_merkle_path(index).await,
+                aggregate_commitment: aggregate_commitment.clone(),
+            });
+        }
+
+        block_commitment_proof_list
+    }
+
+    fn block_commitment_list(count: u8) -> Vec<BlockCommitment> {
+        (0..count)
+            .map(|index| BlockCommitment::from([index; 32]))
+            .collect()
+    }
+
+    #[test]
+    fn aggregation_range_ends_on_multiples_of_the_size() {
+        assert_eq!(BlockCommitmentProof::aggregation_range(4, 3), Some((0, 3)));
+        assert_eq!(BlockCommitmentProof::aggregation_range(4, 7), Some((4, 7)));
+        assert_eq!(BlockCommitmentProof::aggregation_range(4, 5), None);
+        assert_eq!(BlockCommitmentProof::aggregation_range(1, 5), None);
+        assert_eq!(BlockCommitmentProof::aggregation_range(0, 5), None);
+    }
+
+    #[tokio::test]
+    async fn proves_every_block_of_the_range() {
+        let block_commitment_proof_list = aggregate(&block_commitment_list(5), 10).await;
+
+        for block_commitment_proof in block_commitment_proof_list.iter() {
+            assert!(block_commitment_proof.verify(&block_commitment_proof.aggregate_commitment));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_another_aggregate_or_block_commitment() {
+        let block_commitment_proof_list = aggregate(&block_commitment_list(4), 0).await;
+        let other_aggregate_commitment = aggregate(&block_commitment_list(3), 0).await[0]
+            .aggregate_commitment
+            .clone();
+
+        let block_commitment_proof = block_commitment_proof_list[1].clone();
+        assert!(!block_commitment_proof.verify(&other_aggregate_commitment));
+
+        let mut tampered_block_commitment_proof = block_commitment_proof.clone();
+        tampered_block_commitment_proof.block_commitment = BlockCommitment::from([9; 32]);
+        assert!(
+            !tampered_block_commitment_proof.verify(&block_commitment_proof.aggregate_commitment)
+        );
+
+        let mut out_of_range_block_commitment_proof = block_commitment_proof.clone();
+        out_of_range_block_commitment_proof.rollup_block_height = 4;
+        assert!(!out_of_range_block_commitment_proof
+            .verify(&block_commitment_proof.aggregate_commitment));
+    }
+}
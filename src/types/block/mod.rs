@@ -1,7 +1,11 @@
 mod block_commitment;
+mod block_commitment_proof;
+
 pub use block_commitment::*;
+pub use block_commitment_proof::*;
+use radius_sdk::signature::ChainType;
 
-use crate::types::prelude::*;
+use crate::{error::Error, types::prelude::*, util::current_timestamp};
 
 #[derive(Clone, Debug, Deserialize, Serialize, Model)]
 #[kvstore(key(rollup_id: &str, block_height: u64))]
@@ -35,4 +39,43 @@ impl Block {
             block_creator_address,
         }
     }
+
+    /// Checks that the raw transactions hash to the block commitment and that
+    /// the block creator signed it.
+    pub async fn verify(&self, chain_type: ChainType) -> Result<(), Error> {
+        let merkle_tree = MerkleTree::new();
+        for raw_transaction in self.raw_transaction_list.iter() {
+            merkle_tree
+                .add_data(raw_transaction.raw_transaction_hash()?.as_ref())
+                .await;
+        }
+        merkle_tree.finalize_tree().await;
+        let block_commitment = merkle_tree.get_merkle_root().await;
+
+        if BlockCommitment::from(block_commitment) != self.block_commitment {
+            return Err(Error::BlockCommitmentMismatch);
+        }
+
+        self.signature
+            .verify_message(chain_type, &block_commitment, &self.block_creator_address)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(())
+    }
+}
+
+/// Unix timestamp in seconds at which a block was stored on this node. Used by
+/// age-based retention.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str, rollup_block_height: u64))]
+pub struct BlockTimestamp(u64);
+
+impl BlockTimestamp {
+    pub fn now() -> Self {
+        Self(current_timestamp())
+    }
+
+    pub fn as_secs(&self) -> u64 {
+        self.0
+    }
 }
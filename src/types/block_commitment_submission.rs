@@ -0,0 +1,334 @@
+use std::collections::{vec_deque, VecDeque};
+
+use crate::types::prelude::*;
+
+/// Block interval used by rollups without a submission policy.
+pub const DEFAULT_SUBMISSION_BLOCK_INTERVAL: u64 = 201600;
+
+/// When the block commitments of a rollup are submitted to its validation
+/// service. A block is submitted once any configured interval is reached.
+/// With neither interval set, commitments are only submitted on demand.
+///
+/// Rollups with [`Rollup::block_commitment_aggregation_size`] set ignore the
+/// intervals and submit the aggregate of every range instead.
+#[derive(Clone, Debug, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str))]
+pub struct SubmissionPolicy {
+    /// Submit every block whose height is a multiple of this interval.
+    pub block_interval: Option<u64>,
+    /// Submit a block once this many seconds passed since the last queued
+    /// submission.
+    pub time_interval: Option<u64>,
+}
+
+impl Default for SubmissionPolicy {
+    fn default() -> Self {
+        Self {
+            block_interval: Some(DEFAULT_SUBMISSION_BLOCK_INTERVAL),
+            time_interval: None,
+        }
+    }
+}
+
+impl SubmissionPolicy {
+    pub fn is_due(&self, rollup_block_height: u64, last_queued_at: Option<u64>, now: u64) -> bool {
+        let is_block_interval_reached = self
+            .block_interval
+            .map(|block_interval| block_interval != 0 && rollup_block_height % block_interval == 0)
+            .unwrap_or(false);
+
+        let is_time_interval_reached = self
+            .time_interval
+            .map(|time_interval| match last_queued_at {
+                Some(last_queued_at) => now.saturating_sub(last_queued_at) >= time_interval,
+                None => true,
+            })
+            .unwrap_or(false);
+
+        is_block_interval_reached || is_time_interval_reached
+    }
+}
+
+/// Submission history of a rollup.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str))]
+pub struct SubmissionStatus {
+    /// Unix timestamp in seconds at which a commitment was last queued.
+    pub last_queued_at: Option<u64>,
+    pub last_submitted_rollup_block_height: Option<u64>,
+    pub last_submitted_at: Option<u64>,
+    pub last_transaction_hash: Option<String>,
+    pub submitted_count: u64,
+    pub failed_count: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionState {
+    /// Waiting to be sent.
+    #[default]
+    Queued,
+    /// Sent and waiting to be mined.
+    Submitted,
+    /// Given up after too many attempts. Kept until it is queued again on
+    /// demand.
+    Failed,
+}
+
+/// Transaction sent by the operator for a block commitment.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubmissionTransaction {
+    pub operator_address: String,
+    pub transaction_hash: String,
+    pub nonce: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PendingBlockCommitment {
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+    pub block_commitment: BlockCommitment,
+    #[serde(default)]
+    pub state: SubmissionState,
+    /// Set while the submission is waiting to be mined, so that the chain is
+    /// checked before the commitment is sent again.
+    #[serde(default)]
+    pub submission_transaction: Option<SubmissionTransaction>,
+    pub attempt_count: u32,
+    /// Unix timestamp in seconds before which the submission is not retried.
+    pub next_attempt_at: u64,
+}
+
+impl PendingBlockCommitment {
+    pub fn new(
+        rollup_id: impl AsRef<str>,
+        rollup_block_height: u64,
+        block_commitment: BlockCommitment,
+    ) -> Self {
+        Self {
+            rollup_id: rollup_id.as_ref().to_owned(),
+            rollup_block_height,
+            block_commitment,
+            state: SubmissionState::default(),
+            submission_transaction: None,
+            attempt_count: 0,
+            next_attempt_at: 0,
+        }
+    }
+}
+
+/// Block commitments waiting to be submitted, in submission order. Stored so
+/// that pending submissions survive a restart.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key())]
+pub struct PendingBlockCommitmentQueue(VecDeque<PendingBlockCommitment>);
+
+impl PendingBlockCommitmentQueue {
+    /// Appends the commitment unless the same block is already queued.
+    pub fn push(&mut self, pending_block_commitment: PendingBlockCommitment) -> bool {
+        if self
+            .find_mut(
+                &pending_block_commitment.rollup_id,
+                pending_block_commitment.rollup_block_height,
+            )
+            .is_some()
+        {
+            return false;
+        }
+
+        self.0.push_back(pending_block_commitment);
+
+        true
+    }
+
+    pub fn find_mut(
+        &mut self,
+        rollup_id: &str,
+        rollup_block_height: u64,
+    ) -> Option<&mut PendingBlockCommitment> {
+        self.0.iter_mut().find(|pending_block_commitment| {
+            pending_block_commitment.rollup_id == rollup_id
+                && pending_block_commitment.rollup_block_height == rollup_block_height
+        })
+    }
+
+    /// Queues a failed commitment again with a fresh attempt budget. Returns
+    /// whether the block was queued and had failed.
+    pub fn retry_failed(&mut self, rollup_id: &str, rollup_block_height: u64) -> bool {
+        match self.find_mut(rollup_id, rollup_block_height) {
+            Some(pending_block_commitment)
+                if pending_block_commitment.state == SubmissionState::Failed =>
+            {
+                *pending_block_commitment = PendingBlockCommitment::new(
+                    rollup_id,
+                    rollup_block_height,
+                    pending_block_commitment.block_commitment.clone(),
+                );
+
+                true
+            }
+            _others => false,
+        }
+    }
+
+    pub fn remove(&mut self, rollup_id: &str, rollup_block_height: u64) {
+        self.0.retain(|pending_block_commitment| {
+            pending_block_commitment.rollup_id != rollup_id
+                || pending_block_commitment.rollup_block_height != rollup_block_height
+        });
+    }
+
+    pub fn remove_rollup(&mut self, rollup_id: &str) {
+        self.0
+            .retain(|pending_block_commitment| pending_block_commitment.rollup_id != rollup_id);
+    }
+
+    pub fn iter(&self) -> vec_deque::Iter<'_, PendingBlockCommitment> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission_policy(
+        block_interval: Option<u64>,
+        time_interval: Option<u64>,
+    ) -> SubmissionPolicy {
+        SubmissionPolicy {
+            block_interval,
+            time_interval,
+        }
+    }
+
+    #[test]
+    fn block_interval_is_due_on_multiples() {
+        let submission_policy = submission_policy(Some(10), None);
+
+        assert!(submission_policy.is_due(0, None, 0));
+        assert!(submission_policy.is_due(20, Some(100), 100));
+        assert!(!submission_policy.is_due(21, None, 100));
+
+        assert!(!submission_policy(Some(0), None).is_due(0, None, 0));
+    }
+
+    #[test]
+    fn time_interval_is_due_once_elapsed() {
+        let submission_policy = submission_policy(None, Some(60));
+
+        assert!(submission_policy.is_due(1, None, 100));
+        assert!(!submission_policy.is_due(1, Some(100), 159));
+        assert!(submission_policy.is_due(1, Some(100), 160));
+        // A clock that went backwards does not underflow.
+        assert!(!submission_policy.is_due(1, Some(100), 50));
+    }
+
+    #[test]
+    fn on_demand_policy_is_never_due() {
+        let submission_policy = submission_policy(None, None);
+
+        assert!(!submission_policy.is_due(0, None, 0));
+        assert!(!submission_policy.is_due(201600, Some(0), u64::MAX));
+    }
+
+    #[test]
+    fn queue_keeps_one_entry_per_block() {
+        let mut pending_block_commitment_queue = PendingBlockCommitmentQueue::default();
+
+        assert!(
+            pending_block_commitment_queue.push(PendingBlockCommitment::new(
+                "rollup_a",
+                1,
+                BlockCommitment::default()
+            ))
+        );
+        assert!(
+            !pending_block_commitment_queue.push(PendingBlockCommitment::new(
+                "rollup_a",
+                1,
+                BlockCommitment::default()
+            ))
+        );
+        assert!(
+            pending_block_commitment_queue.push(PendingBlockCommitment::new(
+                "rollup_a",
+                2,
+                BlockCommitment::default()
+            ))
+        );
+        assert!(
+            pending_block_commitment_queue.push(PendingBlockCommitment::new(
+                "rollup_b",
+                1,
+                BlockCommitment::default()
+            ))
+        );
+        assert_eq!(pending_block_commitment_queue.len(), 3);
+
+        pending_block_commitment_queue.remove("rollup_a", 1);
+        assert!(pending_block_commitment_queue
+            .find_mut("rollup_a", 1)
+            .is_none());
+        assert_eq!(pending_block_commitment_queue.len(), 2);
+
+        pending_block_commitment_queue.remove_rollup("rollup_a");
+        let remaining: Vec<(&str, u64)> = pending_block_commitment_queue
+            .iter()
+            .map(|pending_block_commitment| {
+                (
+                    pending_block_commitment.rollup_id.as_str(),
+                    pending_block_commitment.rollup_block_height,
+                )
+            })
+            .collect();
+        assert_eq!(remaining, vec![("rollup_b", 1)]);
+    }
+
+    #[test]
+    fn only_failed_entries_are_retried() {
+        let mut pending_block_commitment_queue = PendingBlockCommitmentQueue::default();
+        pending_block_commitment_queue.push(PendingBlockCommitment::new(
+            "rollup_id",
+            1,
+            BlockCommitment::default(),
+        ));
+
+        assert!(!pending_block_commitment_queue.retry_failed("rollup_id", 1));
+        assert!(!pending_block_commitment_queue.retry_failed("rollup_id", 2));
+
+        let pending_block_commitment = pending_block_commitment_queue
+            .find_mut("rollup_id", 1)
+            .unwrap();
+        pending_block_commitment.state = SubmissionState::Failed;
+        pending_block_commitment.attempt_count = 10;
+        pending_block_commitment.next_attempt_at = 1_000;
+
+        assert!(pending_block_commitment_queue.retry_failed("rollup_id", 1));
+        let pending_block_commitment = pending_block_commitment_queue
+            .find_mut("rollup_id", 1)
+            .unwrap();
+        assert_eq!(pending_block_commitment.state, SubmissionState::Queued);
+        assert_eq!(pending_block_commitment.attempt_count, 0);
+        assert_eq!(pending_block_commitment.next_attempt_at, 0);
+    }
+
+    #[test]
+    fn reads_a_queue_stored_before_submission_states() {
+        let pending_block_commitment: PendingBlockCommitment = serde_json::from_str(
+            r#"{"rollup_id":"rollup_id","rollup_block_height":1,"block_commitment":"0x00","attempt_count":2,"next_attempt_at":3}"#,
+        )
+        .unwrap();
+
+        assert_eq!(pending_block_commitment.state, SubmissionState::Queued);
+        assert!(pending_block_commitment.submission_transaction.is_none());
+    }
+}
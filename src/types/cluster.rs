@@ -5,10 +5,7 @@ use std::collections::{
 
 use super::prelude::*;
 use crate::{
-    client::liveness::{
-        radius::{initialize_new_cluster, LivenessClient},
-        seeder::SequencerRpcInfo,
-    },
+    client::liveness::{local, radius, seeder::SequencerRpcInfo},
     error::Error,
     state::AppState,
 };
@@ -138,33 +135,56 @@ impl Cluster {
 }
 
 impl Cluster {
+    /// Rebuilds the cluster at `platform_block_height` with the liveness
+    /// client registered for `platform` and `service_provider`.
     pub async fn sync_cluster(
         context: AppState,
+        platform: Platform,
+        service_provider: ServiceProvider,
         cluster_id: &str,
-        liveness_client: &LivenessClient,
         platform_block_height: u64,
     ) -> Result<Cluster, Error> {
-        let block_margin: u64 = liveness_client
-            .publisher()
-            .get_block_margin()
-            .await
-            .expect("Failed to get block margin")
-            .try_into()
-            .expect("Failed to convert block margin");
-
-        initialize_new_cluster(
-            context,
-            liveness_client,
-            cluster_id,
-            platform_block_height,
-            block_margin,
-        )
-        .await
-        .unwrap();
+        let sequencing_info_payload = SequencingInfoPayload::get(platform, service_provider)
+            .map_err(|_| Error::UnsupportedPlatform)?;
+
+        match sequencing_info_payload {
+            SequencingInfoPayload::Ethereum(_) => {
+                let liveness_client: radius::LivenessClient = context
+                    .get_liveness_client::<radius::LivenessClient>(platform, service_provider)
+                    .await
+                    .map_err(Error::CachedKvStore)?;
+
+                let block_margin = liveness_client.block_margin().await?;
+
+                radius::initialize_new_cluster(
+                    context,
+                    &liveness_client,
+                    cluster_id,
+                    platform_block_height,
+                    block_margin,
+                )
+                .await
+                .map_err(Error::InitializeNewCluster)?;
+            }
+            SequencingInfoPayload::Local(_) => {
+                let liveness_client: local::LivenessClient = context
+                    .get_liveness_client::<local::LivenessClient>(platform, service_provider)
+                    .await
+                    .map_err(Error::CachedKvStore)?;
+
+                local::initialize_new_cluster(
+                    context,
+                    &liveness_client,
+                    cluster_id,
+                    platform_block_height,
+                )
+                .await?;
+            }
+        }
 
         Cluster::get(
-            liveness_client.platform(),
-            liveness_client.service_provider(),
+            platform,
+            service_provider,
             cluster_id,
             platform_block_height,
         ).map_err(|e| {
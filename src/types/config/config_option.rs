@@ -3,7 +3,11 @@ use std::path::PathBuf;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 
-use super::ConfigPath;
+use super::{
+    ConfigPath, DEFAULT_BLOCK_SYNC_DEPTH, DEFAULT_MERKLE_TREE_CHECKPOINT_INTERVAL,
+    DEFAULT_MONITORING_URL, DEFAULT_PROFILER_APPLICATION_NAME, DEFAULT_PROFILER_SAMPLE_RATE,
+    DEFAULT_PROFILER_SERVER_URL,
+};
 
 const DEFAULT_EXTERNAL_RPC_URL: &str = "http://127.0.0.1:3000";
 const DEFAULT_INTERNAL_RPC_URL: &str = "http://127.0.0.1:4000";
@@ -40,6 +44,42 @@ pub struct ConfigOption {
     #[doc = "Set using zkp"]
     #[clap(long = "is-using-zkp")]
     pub is_using_zkp: Option<bool>,
+
+    #[doc = "Set the number of past blocks to sync from the cluster"]
+    #[clap(long = "block-sync-depth")]
+    pub block_sync_depth: Option<u64>,
+
+    #[doc = "Set the number of transactions between two merkle tree checkpoints"]
+    #[clap(long = "merkle-tree-checkpoint-interval")]
+    pub merkle_tree_checkpoint_interval: Option<u64>,
+
+    #[doc = "Set the monitoring url serving the metrics endpoint"]
+    #[clap(long = "monitoring-url")]
+    pub monitoring_url: Option<String>,
+
+    #[doc = "Set whether the profiler starts with the node"]
+    #[clap(long = "is-profiler-enabled")]
+    pub is_profiler_enabled: Option<bool>,
+
+    #[doc = "Set the profiler mode (pyroscope or folded)"]
+    #[clap(long = "profiler-mode")]
+    pub profiler_mode: Option<String>,
+
+    #[doc = "Set the pyroscope server url"]
+    #[clap(long = "profiler-server-url")]
+    pub profiler_server_url: Option<String>,
+
+    #[doc = "Set the profiler application name"]
+    #[clap(long = "profiler-application-name")]
+    pub profiler_application_name: Option<String>,
+
+    #[doc = "Set the profiler sampling frequency in Hertz"]
+    #[clap(long = "profiler-sample-rate")]
+    pub profiler_sample_rate: Option<u32>,
+
+    #[doc = "Add a key=value tag to the profiles (e.g. rollup_id=rollup_1)"]
+    #[clap(long = "profiler-tag")]
+    pub profiler_tags: Option<Vec<String>>,
 }
 
 impl Default for ConfigOption {
@@ -57,6 +97,19 @@ impl Default for ConfigOption {
             ),
 
             is_using_zkp: Some(false),
+
+            block_sync_depth: Some(DEFAULT_BLOCK_SYNC_DEPTH),
+
+            merkle_tree_checkpoint_interval: Some(DEFAULT_MERKLE_TREE_CHECKPOINT_INTERVAL),
+
+            monitoring_url: Some(DEFAULT_MONITORING_URL.into()),
+
+            is_profiler_enabled: Some(false),
+            profiler_mode: Some("pyroscope".into()),
+            profiler_server_url: Some(DEFAULT_PROFILER_SERVER_URL.into()),
+            profiler_application_name: Some(DEFAULT_PROFILER_APPLICATION_NAME.into()),
+            profiler_sample_rate: Some(DEFAULT_PROFILER_SAMPLE_RATE),
+            profiler_tags: None,
         }
     }
 }
@@ -87,6 +140,43 @@ impl ConfigOption {
         set_toml_comment(&mut toml_string, "Set using zkp");
         set_toml_name_value(&mut toml_string, "is_using_zkp", &self.is_using_zkp);
 
+        set_toml_comment(&mut toml_string, "Set block sync depth");
+        set_toml_name_value(&mut toml_string, "block_sync_depth", &self.block_sync_depth);
+
+        set_toml_comment(&mut toml_string, "Set merkle tree checkpoint interval");
+        set_toml_name_value(
+            &mut toml_string,
+            "merkle_tree_checkpoint_interval",
+            &self.merkle_tree_checkpoint_interval,
+        );
+
+        set_toml_comment(&mut toml_string, "Set monitoring url");
+        set_toml_name_value(&mut toml_string, "monitoring_url", &self.monitoring_url);
+
+        set_toml_comment(&mut toml_string, "Set profiler");
+        set_toml_name_value(
+            &mut toml_string,
+            "is_profiler_enabled",
+            &self.is_profiler_enabled,
+        );
+        set_toml_name_value(&mut toml_string, "profiler_mode", &self.profiler_mode);
+        set_toml_name_value(
+            &mut toml_string,
+            "profiler_server_url",
+            &self.profiler_server_url,
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "profiler_application_name",
+            &self.profiler_application_name,
+        );
+        set_toml_name_value(
+            &mut toml_string,
+            "profiler_sample_rate",
+            &self.profiler_sample_rate,
+        );
+        set_toml_name_value(&mut toml_string, "profiler_tags", &self.profiler_tags);
+
         toml_string
     }
 
@@ -120,6 +210,47 @@ impl ConfigOption {
             self.is_using_zkp.clone_from(&other.is_using_zkp);
         }
 
+        if other.block_sync_depth.is_some() {
+            self.block_sync_depth.clone_from(&other.block_sync_depth);
+        }
+
+        if other.merkle_tree_checkpoint_interval.is_some() {
+            self.merkle_tree_checkpoint_interval
+                .clone_from(&other.merkle_tree_checkpoint_interval);
+        }
+
+        if other.monitoring_url.is_some() {
+            self.monitoring_url.clone_from(&other.monitoring_url);
+        }
+
+        if other.is_profiler_enabled.is_some() {
+            self.is_profiler_enabled
+                .clone_from(&other.is_profiler_enabled);
+        }
+
+        if other.profiler_mode.is_some() {
+            self.profiler_mode.clone_from(&other.profiler_mode);
+        }
+
+        if other.profiler_server_url.is_some() {
+            self.profiler_server_url
+                .clone_from(&other.profiler_server_url);
+        }
+
+        if other.profiler_application_name.is_some() {
+            self.profiler_application_name
+                .clone_from(&other.profiler_application_name);
+        }
+
+        if other.profiler_sample_rate.is_some() {
+            self.profiler_sample_rate
+                .clone_from(&other.profiler_sample_rate);
+        }
+
+        if other.profiler_tags.is_some() {
+            self.profiler_tags.clone_from(&other.profiler_tags);
+        }
+
         self
     }
 }
@@ -1,20 +1,29 @@
 mod config_option;
 mod config_path;
 
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, str::FromStr};
 
 pub use config_option::*;
 pub use config_path::*;
 use serde::{Deserialize, Serialize};
 
+use crate::profiler::{ProfilerMode, ProfilerSettings};
+
 pub const DEFAULT_DATA_PATH: &str = ".radius";
 pub const DATABASE_DIR_NAME: &str = "database";
 pub const LOG_DIR_NAME: &str = "logs";
+pub const PROFILE_DIR_NAME: &str = "profiles";
 
 pub const CONFIG_FILE_NAME: &str = "Config.toml";
 pub const SIGNING_KEY_PATH: &str = "signing_key";
 pub const DEFAULT_SIGNING_KEY: &str =
     "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+pub const DEFAULT_BLOCK_SYNC_DEPTH: u64 = 128;
+pub const DEFAULT_MONITORING_URL: &str = "http://127.0.0.1:9000";
+pub const DEFAULT_MERKLE_TREE_CHECKPOINT_INTERVAL: u64 = 64;
+pub const DEFAULT_PROFILER_SERVER_URL: &str = "http://127.0.0.1:4040";
+pub const DEFAULT_PROFILER_APPLICATION_NAME: &str = "sequencer";
+pub const DEFAULT_PROFILER_SAMPLE_RATE: u32 = 100;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Config {
@@ -31,6 +40,19 @@ pub struct Config {
     pub signing_key: String,
 
     pub is_using_zkp: bool,
+
+    pub block_sync_depth: u64,
+
+    pub merkle_tree_checkpoint_interval: u64,
+
+    pub monitoring_url: String,
+
+    pub is_profiler_enabled: bool,
+    pub profiler_mode: ProfilerMode,
+    pub profiler_server_url: String,
+    pub profiler_application_name: String,
+    pub profiler_sample_rate: u32,
+    pub profiler_tags: Vec<(String, String)>,
 }
 
 /// Provides a default implementation for the `Config` struct.
@@ -52,6 +74,17 @@ pub struct Config {
 /// - `signing_key`: A placeholder signing key for development.
 /// - `is_using_zkp`: Boolean flag indicating whether Zero-Knowledge Proofs
 ///   (ZKP) are enabled.
+/// - `block_sync_depth`: Number of past blocks downloaded from the other
+///   cluster members and kept available locally.
+/// - `merkle_tree_checkpoint_interval`: Number of transactions between two
+///   checkpoints of the in-progress merkle tree of a rollup.
+/// - `monitoring_url`: Address serving the Prometheus `/metrics` endpoint.
+/// - `is_profiler_enabled`: Whether the profiler starts with the node.
+/// - `profiler_mode`: `pyroscope` to push to `profiler_server_url`, or `folded`
+///   to write collapsed stacks under the data directory.
+/// - `profiler_application_name`, `profiler_sample_rate`, `profiler_tags`:
+///   Application name, sampling frequency in Hertz and `key=value` tags of the
+///   profiles.
 ///
 /// Note: For production use, ensure these values are set explicitly in the
 /// configuration file or environment variables to meet security and functional
@@ -67,6 +100,15 @@ impl Default for Config {
             distributed_key_generation_rpc_url: "http://127.0.0.1:7100".to_string(),
             signing_key: DEFAULT_SIGNING_KEY.to_string(),
             is_using_zkp: true,
+            block_sync_depth: DEFAULT_BLOCK_SYNC_DEPTH,
+            merkle_tree_checkpoint_interval: DEFAULT_MERKLE_TREE_CHECKPOINT_INTERVAL,
+            monitoring_url: DEFAULT_MONITORING_URL.to_string(),
+            is_profiler_enabled: false,
+            profiler_mode: ProfilerMode::default(),
+            profiler_server_url: DEFAULT_PROFILER_SERVER_URL.to_string(),
+            profiler_application_name: DEFAULT_PROFILER_APPLICATION_NAME.to_string(),
+            profiler_sample_rate: DEFAULT_PROFILER_SAMPLE_RATE,
+            profiler_tags: Vec::new(),
         }
     }
 }
@@ -97,6 +139,18 @@ impl Config {
         let signing_key_path = config_path.join(SIGNING_KEY_PATH);
         let signing_key = fs::read_to_string(signing_key_path).unwrap();
 
+        let profiler_mode = match merged_config_option.profiler_mode.as_ref() {
+            Some(profiler_mode) => ProfilerMode::from_str(profiler_mode)
+                .map_err(|_| ConfigError::InvalidProfilerMode(profiler_mode.clone()))?,
+            None => ProfilerMode::default(),
+        };
+        let profiler_tags = merged_config_option
+            .profiler_tags
+            .unwrap_or_default()
+            .iter()
+            .map(|tag| parse_profiler_tag(tag))
+            .collect::<Result<Vec<(String, String)>, ConfigError>>()?;
+
         Ok(Config {
             path: config_path,
             external_rpc_url: merged_config_option.external_rpc_url.unwrap(),
@@ -108,6 +162,28 @@ impl Config {
                 .unwrap(),
             signing_key,
             is_using_zkp: merged_config_option.is_using_zkp.unwrap(),
+            // Configuration files written by older versions lack this option.
+            block_sync_depth: merged_config_option
+                .block_sync_depth
+                .unwrap_or(DEFAULT_BLOCK_SYNC_DEPTH),
+            merkle_tree_checkpoint_interval: merged_config_option
+                .merkle_tree_checkpoint_interval
+                .unwrap_or(DEFAULT_MERKLE_TREE_CHECKPOINT_INTERVAL),
+            monitoring_url: merged_config_option
+                .monitoring_url
+                .unwrap_or(DEFAULT_MONITORING_URL.to_string()),
+            is_profiler_enabled: merged_config_option.is_profiler_enabled.unwrap_or(false),
+            profiler_mode,
+            profiler_server_url: merged_config_option
+                .profiler_server_url
+                .unwrap_or(DEFAULT_PROFILER_SERVER_URL.to_string()),
+            profiler_application_name: merged_config_option
+                .profiler_application_name
+                .unwrap_or(DEFAULT_PROFILER_APPLICATION_NAME.to_string()),
+            profiler_sample_rate: merged_config_option
+                .profiler_sample_rate
+                .unwrap_or(DEFAULT_PROFILER_SAMPLE_RATE),
+            profiler_tags,
         })
     }
 
@@ -119,6 +195,21 @@ impl Config {
         self.path.join(LOG_DIR_NAME)
     }
 
+    pub fn profile_path(&self) -> PathBuf {
+        self.path.join(PROFILE_DIR_NAME)
+    }
+
+    pub fn profiler_settings(&self) -> ProfilerSettings {
+        ProfilerSettings {
+            mode: self.profiler_mode,
+            server_url: self.profiler_server_url.clone(),
+            application_name: self.profiler_application_name.clone(),
+            sample_rate: self.profiler_sample_rate,
+            tag_list: self.profiler_tags.clone(),
+            profile_path: self.profile_path(),
+        }
+    }
+
     pub fn external_port(&self) -> Result<String, ConfigError> {
         Ok(self
             .external_rpc_url
@@ -136,6 +227,23 @@ impl Config {
             .ok_or(ConfigError::InvalidClusterPort)?
             .to_string())
     }
+
+    pub fn monitoring_port(&self) -> Result<String, ConfigError> {
+        Ok(self
+            .monitoring_url
+            .split(':')
+            .last()
+            .ok_or(ConfigError::InvalidMonitoringPort)?
+            .to_string())
+    }
+}
+
+/// Splits a `key=value` profiler tag.
+fn parse_profiler_tag(tag: &str) -> Result<(String, String), ConfigError> {
+    match tag.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _others => Err(ConfigError::InvalidProfilerTag(tag.to_owned())),
+    }
 }
 
 #[derive(Debug)]
@@ -148,6 +256,9 @@ pub enum ConfigError {
     CreatePrivateKeyFile(std::io::Error),
     InvalidExternalPort,
     InvalidClusterPort,
+    InvalidMonitoringPort,
+    InvalidProfilerMode(String),
+    InvalidProfilerTag(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -1,5 +1,6 @@
 use std::{
     collections::btree_set::{BTreeSet, Iter},
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -38,8 +39,129 @@ pub struct LivenessRadius {
     pub contract_address: String,
 }
 
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LivenessLocal {
+    /// Optional TOML or JSON file describing the local clusters. When it is
+    /// not set, clusters are registered through the `set_local_cluster`
+    /// internal RPC.
+    pub cluster_config_path: Option<PathBuf>,
+}
+
+impl<'de> Deserialize<'de> for LivenessLocal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct LivenessLocalFields {
+            #[serde(default)]
+            cluster_config_path: Option<PathBuf>,
+        }
+
+        // Older versions stored `LivenessLocal` as a unit struct, i.e. `null`.
+        let liveness_local = Option::<LivenessLocalFields>::deserialize(deserializer)?
+            .map(|fields| Self {
+                cluster_config_path: fields.cluster_config_path,
+            })
+            .unwrap_or_default();
+
+        Ok(liveness_local)
+    }
+}
+
+/// The root of the local cluster configuration file.
+///
+/// ```toml
+/// [[cluster_list]]
+/// cluster_id = "local_cluster"
+/// leader_rotation = true
+///
+/// [[cluster_list.sequencer_list]]
+/// address = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+/// external_rpc_url = "http://127.0.0.1:3000"
+/// cluster_rpc_url = "http://127.0.0.1:5000"
+///
+/// [[cluster_list.rollup_list]]
+/// rollup_id = "rollup_id"
+/// rollup_type = "polygon_cdk"
+/// encrypted_transaction_type = "skde"
+/// order_commitment_type = "sign"
+/// owner = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+/// executor_address_list = ["0x70997970c51812dc3a010c7d01b50e0d17dc79c8"]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LocalClusterConfig {
+    #[serde(default)]
+    pub cluster_list: Vec<LocalClusterInfo>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Model)]
+#[kvstore(key(platform: Platform, service_provider: ServiceProvider, cluster_id: &str))]
+pub struct LocalClusterInfo {
+    pub cluster_id: String,
+
+    /// The order of the list is the order of the leader rotation.
+    pub sequencer_list: Vec<LocalSequencerInfo>,
+    #[serde(default)]
+    pub rollup_list: Vec<LocalRollupInfo>,
+
+    /// Rejects `finalize_block` requests whose next block creator does not
+    /// follow the round-robin order of `sequencer_list`.
+    #[serde(default)]
+    pub leader_rotation: bool,
+}
+
+impl LocalClusterInfo {
+    /// Returns the sequencer expected to build the block following
+    /// `rollup_block_height`.
+    pub fn next_leader(&self, rollup_block_height: u64) -> Option<&LocalSequencerInfo> {
+        if self.sequencer_list.is_empty() {
+            return None;
+        }
+
+        let index = (rollup_block_height + 1) % self.sequencer_list.len() as u64;
+        self.sequencer_list.get(index as usize)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LocalSequencerInfo {
+    pub address: String,
+    pub external_rpc_url: Option<String>,
+    pub cluster_rpc_url: Option<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct LivenessLocal;
+pub struct LocalRollupInfo {
+    pub rollup_id: String,
+    pub rollup_type: String,
+    pub encrypted_transaction_type: String,
+    pub order_commitment_type: String,
+    pub owner: String,
+    #[serde(default)]
+    pub executor_address_list: Vec<String>,
+
+    #[serde(default = "LocalRollupInfo::default_validation_platform")]
+    pub validation_platform: Platform,
+    #[serde(default = "LocalRollupInfo::default_validation_service_provider")]
+    pub validation_service_provider: String,
+    #[serde(default = "LocalRollupInfo::default_validation_service_manager_address")]
+    pub validation_service_manager_address: String,
+}
+
+impl LocalRollupInfo {
+    fn default_validation_platform() -> Platform {
+        Platform::Local
+    }
+
+    fn default_validation_service_provider() -> String {
+        "symbiotic".to_owned()
+    }
+
+    fn default_validation_service_manager_address() -> String {
+        const_hex::encode_prefixed([0u8; 20])
+    }
+}
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
 #[kvstore(key())]
@@ -58,3 +180,44 @@ impl SequencingInfoList {
         self.0.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_local_payload_stored_as_unit_struct() {
+        let payload: SequencingInfoPayload = serde_json::from_str("null").unwrap();
+
+        match payload {
+            SequencingInfoPayload::Local(liveness_local) => {
+                assert!(liveness_local.cluster_config_path.is_none())
+            }
+            _others => panic!("expected a local payload"),
+        }
+    }
+
+    #[test]
+    fn deserializes_local_payload_with_fields() {
+        let payload: SequencingInfoPayload =
+            serde_json::from_str(r#"{"cluster_config_path":"clusters.toml"}"#).unwrap();
+
+        match payload {
+            SequencingInfoPayload::Local(liveness_local) => assert_eq!(
+                liveness_local.cluster_config_path,
+                Some(PathBuf::from("clusters.toml"))
+            ),
+            _others => panic!("expected a local payload"),
+        }
+    }
+
+    #[test]
+    fn deserializes_radius_payload() {
+        let payload: SequencingInfoPayload = serde_json::from_str(
+            r#"{"liveness_rpc_url":"http://127.0.0.1:8545","liveness_websocket_url":"ws://127.0.0.1:8545","contract_address":"0x0"}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(payload, SequencingInfoPayload::Ethereum(_)));
+    }
+}
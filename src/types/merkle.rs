@@ -1,8 +1,20 @@
 use std::sync::Arc;
 
+use radius_sdk::kvstore::{KvStoreError, Model};
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use tokio::sync::Mutex;
 
+/// Levels of the in-progress merkle tree of a rollup, written periodically by
+/// the leader so that the tree can be restored on startup by replaying only
+/// the transactions ordered after it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str))]
+pub struct MerkleTreeCheckpoint {
+    pub rollup_block_height: u64,
+    pub nodes: Vec<Vec<[u8; 32]>>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct MerkleTree {
     pub nodes: Arc<Mutex<Vec<Vec<[u8; 32]>>>>, // nodes by tree level
@@ -15,6 +27,65 @@ impl MerkleTree {
         }
     }
 
+    /// Stores the current levels of the tree for `rollup_block_height`.
+    pub async fn checkpoint(
+        &self,
+        rollup_id: &str,
+        rollup_block_height: u64,
+    ) -> Result<(), KvStoreError> {
+        let nodes = self.nodes.lock().await;
+
+        MerkleTreeCheckpoint {
+            rollup_block_height,
+            nodes: nodes.clone(),
+        }
+        .put(rollup_id)
+    }
+
+    /// Restores the tree from its checkpoint. Returns `None` if there is no
+    /// usable checkpoint, see [`Self::from_checkpoint`].
+    pub fn restore(rollup_id: &str, rollup_block_height: u64, leaf_count: u64) -> Option<Self> {
+        let checkpoint = MerkleTreeCheckpoint::get(rollup_id).ok()?;
+
+        Self::from_checkpoint(checkpoint, rollup_block_height, leaf_count)
+    }
+
+    /// Returns the tree of `checkpoint` if it was taken at
+    /// `rollup_block_height` and holds at most `leaf_count` leaves. The leaves
+    /// after [`Self::leaf_count`] have to be added again.
+    pub fn from_checkpoint(
+        checkpoint: MerkleTreeCheckpoint,
+        rollup_block_height: u64,
+        leaf_count: u64,
+    ) -> Option<Self> {
+        let checkpoint_leaf_count = checkpoint.nodes.first()?.len() as u64;
+
+        if checkpoint.rollup_block_height != rollup_block_height
+            || checkpoint_leaf_count > leaf_count
+        {
+            return None;
+        }
+
+        Some(Self {
+            nodes: Arc::new(Mutex::new(checkpoint.nodes)),
+        })
+    }
+
+    pub async fn leaf_count(&self) -> u64 {
+        self.nodes.lock().await[0].len() as u64
+    }
+
+    /// Returns a copy of the levels of the tree for [`Self::rewind`].
+    pub async fn snapshot(&self) -> Vec<Vec<[u8; 32]>> {
+        self.nodes.lock().await.clone()
+    }
+
+    /// Restores the levels taken by [`Self::snapshot`], dropping the leaves
+    /// added since.
+    pub async fn rewind(&self, snapshot: Vec<Vec<[u8; 32]>>) {
+        *self.nodes.lock().await = snapshot;
+    }
+
     fn update_tree(nodes: &mut Vec<Vec<[u8; 32]>>) {
         let mut current_level = 0;
 
@@ -46,19 +117,22 @@ impl MerkleTree {
 
         while nodes[current_level].len() > 1 {
             if nodes[current_level].len() % 2 == 1 {
-                let left_node = nodes[current_level].last().unwrap();
-                let parent_node = Self::hash(&Self::concat_arrays(*left_node, last_node));
-
                 nodes[current_level].push(last_node);
-                nodes[current_level + 1].push(parent_node);
             }
 
             if nodes.len() <= current_level + 1 {
-                let left_node = nodes[current_level][0];
-                let right_node = nodes[current_level][1];
+                nodes.push(vec![]);
+            }
+
+            // Merge the pairs that have not been propagated to the parent
+            // level yet, including the last leaf and the padding node.
+            let parent_count = nodes[current_level].len() / 2;
+            for parent_index in nodes[current_level + 1].len()..parent_count {
+                let left_node = nodes[current_level][parent_index * 2];
+                let right_node = nodes[current_level][parent_index * 2 + 1];
 
-                let merkle_root = Self::hash(&Self::concat_arrays(left_node, right_node));
-                nodes.push(vec![merkle_root]);
+                let parent_node = Self::hash(&Self::concat_arrays(left_node, right_node));
+                nodes[current_level + 1].push(parent_node);
             }
 
             current_level += 1;
@@ -150,6 +224,36 @@ impl MerkleTree {
             .unwrap()
     }
 
+    /// Returns the leaf stored for `data`, as computed by [`Self::add_data`].
+    pub fn leaf_hash(data: &str) -> [u8; 32] {
+        Self::hash(data.as_bytes())
+    }
+
+    /// Checks that `leaf` at `index` hashes up to `merkle_root` along
+    /// `merkle_path`, which lists the sibling of each level from the leaves
+    /// up as returned by [`Self::get_merkle_path`] on a finalized tree.
+    pub fn verify_merkle_path(
+        leaf: [u8; 32],
+        index: usize,
+        merkle_path: &[[u8; 32]],
+        merkle_root: [u8; 32],
+    ) -> bool {
+        let mut current_hash = leaf;
+        let mut current_index = index;
+
+        for sibling in merkle_path.iter() {
+            current_hash = if current_index % 2 == 0 {
+                Self::hash(&Self::concat_arrays(current_hash, *sibling))
+            } else {
+                Self::hash(&Self::concat_arrays(*sibling, current_hash))
+            };
+
+            current_index /= 2;
+        }
+
+        current_hash == merkle_root
+    }
+
     pub fn hash(data: &[u8]) -> [u8; 32] {
         let mut hasher = Keccak256::new();
         hasher.update(data);
@@ -182,3 +286,201 @@ impl MerkleTree {
         array
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn finalized_tree(leaf_list: &[String]) -> MerkleTree {
+        let merkle_tree = MerkleTree::new();
+        for leaf in leaf_list {
+            merkle_tree.add_data(leaf).await;
+        }
+        merkle_tree.finalize_tree().await;
+
+        merkle_tree
+    }
+
+    fn leaf_list(leaf_count: usize) -> Vec<String> {
+        (0..leaf_count)
+            .map(|index| format!("0x{:02x}", index))
+            .collect()
+    }
+
+    fn parent(left_node: [u8; 32], right_node: [u8; 32]) -> [u8; 32] {
+        MerkleTree::hash(&MerkleTree::concat_arrays(left_node, right_node))
+    }
+
+    #[tokio::test]
+    async fn merkle_root_of_four_leaves_covers_every_leaf() {
+        // The pair of the last two leaves is only propagated when the tree is
+        // finalized. It used to be dropped, leaving the root at H(a, b).
+        let leaf_list = leaf_list(4);
+        let leaf = |index: usize| MerkleTree::leaf_hash(&leaf_list[index]);
+
+        assert_eq!(
+            finalized_tree(&leaf_list).await.get_merkle_root().await,
+            parent(parent(leaf(0), leaf(1)), parent(leaf(2), leaf(3)))
+        );
+    }
+
+    #[tokio::test]
+    async fn merkle_root_commits_to_the_trailing_leaves_of_an_even_count() {
+        for leaf_count in [2, 4, 6, 8] {
+            let leaf_list = leaf_list(leaf_count);
+            let merkle_root = finalized_tree(&leaf_list).await.get_merkle_root().await;
+
+            for index in [leaf_count - 2, leaf_count - 1] {
+                let mut tampered_leaf_list = leaf_list.clone();
+                tampered_leaf_list[index] = "0xff".to_owned();
+                let tampered_merkle_root = finalized_tree(&tampered_leaf_list)
+                    .await
+                    .get_merkle_root()
+                    .await;
+
+                assert_ne!(
+                    merkle_root, tampered_merkle_root,
+                    "leaf {} of {}",
+                    index, leaf_count
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn merkle_path_of_every_leaf_verifies() {
+        for leaf_count in 1..=9 {
+            let leaf_list = leaf_list(leaf_count);
+            let merkle_tree = finalized_tree(&leaf_list).await;
+            let merkle_root = merkle_tree.get_merkle_root().await;
+
+            for (index, leaf) in leaf_list.iter().enumerate() {
+                let merkle_path = merkle_tree.get_merkle_path(index).await;
+
+                assert!(
+                    MerkleTree::verify_merkle_path(
+                        MerkleTree::leaf_hash(leaf),
+                        index,
+                        &merkle_path,
+                        merkle_root
+                    ),
+                    "leaf {} of {}",
+                    index,
+                    leaf_count
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn merkle_path_rejects_another_leaf_or_index() {
+        let leaf_list = leaf_list(5);
+        let merkle_tree = finalized_tree(&leaf_list).await;
+        let merkle_root = merkle_tree.get_merkle_root().await;
+        let merkle_path = merkle_tree.get_merkle_path(2).await;
+
+        assert!(!MerkleTree::verify_merkle_path(
+            MerkleTree::leaf_hash("0xff"),
+            2,
+            &merkle_path,
+            merkle_root
+        ));
+        assert!(!MerkleTree::verify_merkle_path(
+            MerkleTree::leaf_hash(&leaf_list[2]),
+            3,
+            &merkle_path,
+            merkle_root
+        ));
+    }
+
+    #[tokio::test]
+    async fn merkle_root_commits_to_the_last_leaf() {
+        // An odd leaf count leaves the last leaf unpaired until the tree is
+        // finalized.
+        for leaf_count in [3, 5, 7] {
+            let mut leaf_list = leaf_list(leaf_count);
+            let merkle_root = finalized_tree(&leaf_list).await.get_merkle_root().await;
+
+            leaf_list[leaf_count - 1] = "0xff".to_owned();
+            let tampered_merkle_root = finalized_tree(&leaf_list).await.get_merkle_root().await;
+
+            assert_ne!(
+                merkle_root, tampered_merkle_root,
+                "leaf count {}",
+                leaf_count
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rewind_drops_the_leaves_added_after_the_snapshot() {
+        let merkle_tree = MerkleTree::new();
+        merkle_tree.add_data("0x01").await;
+        merkle_tree.add_data("0x02").await;
+
+        let snapshot = merkle_tree.snapshot().await;
+        let (_, pre_merkle_path) = merkle_tree.add_data("0x03").await;
+        merkle_tree.rewind(snapshot).await;
+
+        // The next leaf takes the place of the abandoned one.
+        assert_eq!(merkle_tree.add_data("0x04").await, (2, pre_merkle_path));
+    }
+
+    async fn checkpoint_of(leaf_list: &[&str], rollup_block_height: u64) -> MerkleTreeCheckpoint {
+        let merkle_tree = MerkleTree::new();
+        for leaf in leaf_list {
+            merkle_tree.add_data(leaf).await;
+        }
+
+        MerkleTreeCheckpoint {
+            rollup_block_height,
+            nodes: merkle_tree.snapshot().await,
+        }
+    }
+
+    #[tokio::test]
+    async fn restored_tree_continues_like_the_original() {
+        let original = MerkleTree::new();
+        for leaf in ["0x01", "0x02", "0x03", "0x04", "0x05"] {
+            original.add_data(leaf).await;
+        }
+        original.finalize_tree().await;
+
+        // The checkpoint lags behind: the last two leaves are replayed.
+        let checkpoint = checkpoint_of(&["0x01", "0x02", "0x03"], 7).await;
+        let restored = MerkleTree::from_checkpoint(checkpoint, 7, 5).unwrap();
+        assert_eq!(restored.leaf_count().await, 3);
+        restored.add_data("0x04").await;
+        restored.add_data("0x05").await;
+        restored.finalize_tree().await;
+
+        assert_eq!(
+            restored.get_merkle_root().await,
+            original.get_merkle_root().await
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_a_checkpoint_of_another_block() {
+        let checkpoint = checkpoint_of(&["0x01"], 6).await;
+
+        assert!(MerkleTree::from_checkpoint(checkpoint, 7, 1).is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_checkpoint_ahead_of_the_transaction_order() {
+        let checkpoint = checkpoint_of(&["0x01", "0x02"], 7).await;
+
+        assert!(MerkleTree::from_checkpoint(checkpoint, 7, 1).is_none());
+    }
+
+    #[test]
+    fn rejects_a_checkpoint_without_levels() {
+        let checkpoint = MerkleTreeCheckpoint {
+            rollup_block_height: 7,
+            nodes: Vec::new(),
+        };
+
+        assert!(MerkleTree::from_checkpoint(checkpoint, 7, 0).is_none());
+    }
+}
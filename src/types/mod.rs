@@ -1,22 +1,26 @@
 mod block;
+mod block_commitment_submission;
 mod cluster;
 mod config;
 mod liveness;
 mod merkle;
 mod order_commitment;
 mod platform;
+mod pruning;
 mod rollup;
 mod transaction;
 mod validation;
 mod version;
 
 pub use block::*;
+pub use block_commitment_submission::*;
 pub use cluster::*;
 pub use config::*;
 pub use liveness::*;
 pub use merkle::*;
 pub use order_commitment::*;
 pub use platform::*;
+pub use pruning::*;
 use radius_sdk::signature::Address;
 pub use rollup::*;
 use serde::ser::SerializeSeq;
@@ -1,8 +1,10 @@
 mod bundle_order_commitment;
+mod order_commitment_evidence;
 mod order_commitment_type;
 mod single_order_commitment;
 
 pub use bundle_order_commitment::*;
+pub use order_commitment_evidence::*;
 pub use order_commitment_type::*;
 use radius_sdk::kvstore::Model;
 use serde::{Deserialize, Serialize};
@@ -22,3 +24,23 @@ impl Default for OrderCommitment {
         Self::Single(SingleOrderCommitment::default())
     }
 }
+
+impl OrderCommitment {
+    /// Returns the signed commitments for `transaction_order`. Transaction
+    /// hash commitments carry no signature and are skipped.
+    pub fn sign_order_commitment_list(&self, transaction_order: u64) -> Vec<&SignOrderCommitment> {
+        let order_commitment_list = match self {
+            Self::Single(order_commitment) => std::slice::from_ref(order_commitment),
+            Self::Bundle(bundle) => bundle.order_commitment_list.as_slice(),
+        };
+
+        order_commitment_list
+            .iter()
+            .filter_map(|order_commitment| match order_commitment {
+                SingleOrderCommitment::Sign(order_commitment) => Some(order_commitment),
+                SingleOrderCommitment::TransactionHash(_) => None,
+            })
+            .filter(|order_commitment| order_commitment.data.transaction_order == transaction_order)
+            .collect()
+    }
+}
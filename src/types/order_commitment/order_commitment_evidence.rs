@@ -0,0 +1,56 @@
+use radius_sdk::{
+    kvstore::Model,
+    signature::{Address, Signature},
+};
+use serde::{Deserialize, Serialize};
+
+use super::SignOrderCommitment;
+use crate::types::{
+    deserialize_merkle_path, serialize_merkle_path, BlockCommitment, RawTransactionHash,
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderCommitmentViolationKind {
+    /// The block holds a different transaction at the committed order.
+    TransactionMismatch,
+    /// The block ends before the committed order.
+    MissingTransaction,
+    /// The committed pre-merkle path does not match the transactions placed
+    /// before the committed order in the block.
+    PreMerklePathMismatch,
+}
+
+/// Self-contained evidence that a leader built a block contradicting an order
+/// commitment it signed. Together with the block signature and the hash list
+/// of the block, anyone can recompute the block commitment and check the
+/// violation without access to the sequencer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OrderCommitmentEvidence {
+    pub kind: OrderCommitmentViolationKind,
+    pub order_commitment: SignOrderCommitment,
+
+    pub rollup_id: String,
+    pub rollup_block_height: u64,
+    pub transaction_order: u64,
+
+    /// Hash list of the transaction found at the committed order, empty if
+    /// the block ends before it.
+    pub included_transaction_hash_list: Vec<RawTransactionHash>,
+    #[serde(
+        serialize_with = "serialize_merkle_path",
+        deserialize_with = "deserialize_merkle_path"
+    )]
+    pub included_pre_merkle_path: Vec<[u8; 32]>,
+
+    pub raw_transaction_hash_list: Vec<RawTransactionHash>,
+    pub block_commitment: BlockCommitment,
+    pub block_signature: Signature,
+    pub block_creator_address: Address,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str, rollup_block_height: u64))]
+pub struct OrderCommitmentEvidenceList {
+    pub evidence_list: Vec<OrderCommitmentEvidence>,
+}
@@ -1,6 +1,13 @@
+use radius_sdk::signature::{Address, ChainType, Signature};
 use serde::{Deserialize, Serialize};
 
-use crate::types::{deserialize_merkle_path, serialize_merkle_path, RawTransactionHash};
+use crate::{
+    error::Error,
+    types::{
+        deserialize_merkle_path, serialize_merkle_path, Block, BlockCommitment, MerkleTree,
+        RawTransactionHash,
+    },
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SignOrderCommitment {
@@ -8,6 +15,66 @@ pub struct SignOrderCommitment {
     pub signature: String,
 }
 
+impl SignOrderCommitment {
+    /// Verifies the commitment on the client side.
+    ///
+    /// Checks that `leader_address` signed the commitment data and, if the
+    /// final `block` is given, that the committed transaction sits at the
+    /// committed order (every transaction of a bundle has an order of its
+    /// own), that the pre-merkle path matches the transactions placed before
+    /// it and that the block hashes to its block commitment.
+    pub async fn verify(
+        &self,
+        chain_type: ChainType,
+        leader_address: &Address,
+        block: Option<&Block>,
+    ) -> Result<(), Error> {
+        let signature = const_hex::decode(&self.signature)
+            .map(Signature::from)
+            .map_err(|_| Error::InvalidSignature)?;
+        signature
+            .verify_message(chain_type, &self.data, leader_address)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        let block = match block {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+
+        if block.block_height != self.data.block_height {
+            return Err(Error::BlockHeightMismatch);
+        }
+
+        let transaction_order = self.data.transaction_order as usize;
+        let transaction_hash = RawTransactionHash::from(self.data.transaction_hash.clone());
+        let committed_transaction = block
+            .raw_transaction_list
+            .get(transaction_order)
+            .ok_or(Error::InvalidTransactionOrder)?;
+        if committed_transaction.raw_transaction_hash()? != transaction_hash {
+            return Err(Error::InvalidTransactionOrder);
+        }
+
+        let merkle_tree = MerkleTree::new();
+        for (index, raw_transaction) in block.raw_transaction_list.iter().enumerate() {
+            let (_, pre_merkle_path) = merkle_tree
+                .add_data(raw_transaction.raw_transaction_hash()?.as_ref())
+                .await;
+
+            if index == transaction_order && pre_merkle_path != self.data.pre_merkle_path {
+                return Err(Error::PreMerklePathMismatch);
+            }
+        }
+        merkle_tree.finalize_tree().await;
+
+        if BlockCommitment::from(merkle_tree.get_merkle_root().await) != block.block_commitment {
+            return Err(Error::BlockCommitmentMismatch);
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct OrderCommitmentData {
     pub rollup_id: String,
@@ -34,3 +101,202 @@ impl Default for OrderCommitmentData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ethers_core::{
+        k256::ecdsa::SigningKey,
+        types::{self as eth_types, transaction::eip2718::TypedTransaction},
+    };
+    use radius_sdk::signature::PrivateKeySigner;
+
+    use super::*;
+    use crate::types::{EthRawTransaction, RawTransaction};
+
+    const LEADER_SIGNING_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OTHER_SIGNING_KEY: &str =
+        "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn signer(signing_key: &str) -> PrivateKeySigner {
+        PrivateKeySigner::from_str(ChainType::Ethereum, signing_key).unwrap()
+    }
+
+    /// Returns a signed legacy transfer, distinct for every `nonce`.
+    fn raw_transaction(nonce: u64) -> RawTransaction {
+        let chain_id = 1_u64;
+        let transaction: TypedTransaction = eth_types::TransactionRequest::new()
+            .nonce(nonce)
+            .gas(21_000)
+            .gas_price(1)
+            .to(eth_types::Address::zero())
+            .value(0)
+            .chain_id(chain_id)
+            .into();
+
+        let signing_key = SigningKey::from_slice(&[1; 32]).unwrap();
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(transaction.sighash().as_bytes())
+            .unwrap();
+        let signature = eth_types::Signature {
+            r: eth_types::U256::from_big_endian(&signature.r().to_bytes()),
+            s: eth_types::U256::from_big_endian(&signature.s().to_bytes()),
+            v: recovery_id.to_byte() as u64 + 35 + chain_id * 2,
+        };
+
+        RawTransaction::Eth(EthRawTransaction::from(const_hex::encode_prefixed(
+            transaction.rlp_signed(&signature),
+        )))
+    }
+
+    /// Builds the block of `raw_transaction_list` and the leader-signed
+    /// commitment of the transaction at `transaction_order`.
+    async fn block_and_order_commitment(
+        raw_transaction_list: Vec<RawTransaction>,
+        transaction_order: usize,
+    ) -> (Block, SignOrderCommitment) {
+        let leader = signer(LEADER_SIGNING_KEY);
+
+        let merkle_tree = MerkleTree::new();
+        let mut committed_pre_merkle_path = Vec::new();
+        for (index, raw_transaction) in raw_transaction_list.iter().enumerate() {
+            let (_, pre_merkle_path) = merkle_tree
+                .add_data(raw_transaction.raw_transaction_hash().unwrap().as_ref())
+                .await;
+            if index == transaction_order {
+                committed_pre_merkle_path = pre_merkle_path;
+            }
+        }
+        merkle_tree.finalize_tree().await;
+
+        let data = OrderCommitmentData {
+            rollup_id: "rollup_id".to_owned(),
+            block_height: 7,
+            transaction_order: transaction_order as u64,
+            transaction_hash: raw_transaction_list[transaction_order]
+                .raw_transaction_hash()
+                .unwrap()
+                .as_string(),
+            pre_merkle_path: committed_pre_merkle_path,
+        };
+        let order_commitment = SignOrderCommitment {
+            signature: leader.sign_message(&data).unwrap().as_hex_string(),
+            data,
+        };
+
+        let block = Block::new(
+            7,
+            Vec::new(),
+            raw_transaction_list,
+            Signature::from(vec![0; 65]),
+            BlockCommitment::from(merkle_tree.get_merkle_root().await),
+            leader.address().clone(),
+        );
+
+        (block, order_commitment)
+    }
+
+    fn raw_transaction_list(transaction_count: u64) -> Vec<RawTransaction> {
+        (0..transaction_count).map(raw_transaction).collect()
+    }
+
+    #[tokio::test]
+    async fn accepts_the_leader_signature() {
+        let (block, order_commitment) =
+            block_and_order_commitment(raw_transaction_list(3), 1).await;
+
+        order_commitment
+            .verify(ChainType::Ethereum, &block.block_creator_address, None)
+            .await
+            .unwrap();
+        order_commitment
+            .verify(
+                ChainType::Ethereum,
+                &block.block_creator_address,
+                Some(&block),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_another_signer() {
+        let (_, order_commitment) = block_and_order_commitment(raw_transaction_list(3), 1).await;
+        let other_address = signer(OTHER_SIGNING_KEY).address().clone();
+
+        assert!(matches!(
+            order_commitment
+                .verify(ChainType::Ethereum, &other_address, None)
+                .await,
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_data() {
+        let (block, mut order_commitment) =
+            block_and_order_commitment(raw_transaction_list(3), 1).await;
+        order_commitment.data.transaction_order = 2;
+
+        assert!(matches!(
+            order_commitment
+                .verify(ChainType::Ethereum, &block.block_creator_address, None)
+                .await,
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_block_with_the_transaction_at_another_order() {
+        let (mut block, order_commitment) =
+            block_and_order_commitment(raw_transaction_list(3), 1).await;
+        block.raw_transaction_list.swap(1, 2);
+
+        assert!(matches!(
+            order_commitment
+                .verify(
+                    ChainType::Ethereum,
+                    &block.block_creator_address,
+                    Some(&block)
+                )
+                .await,
+            Err(Error::InvalidTransactionOrder)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_block_reordered_before_the_transaction() {
+        let (mut block, order_commitment) =
+            block_and_order_commitment(raw_transaction_list(3), 2).await;
+        block.raw_transaction_list.swap(0, 1);
+
+        assert!(matches!(
+            order_commitment
+                .verify(
+                    ChainType::Ethereum,
+                    &block.block_creator_address,
+                    Some(&block)
+                )
+                .await,
+            Err(Error::PreMerklePathMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_block_that_does_not_match_its_commitment() {
+        let (mut block, order_commitment) =
+            block_and_order_commitment(raw_transaction_list(3), 1).await;
+        block.block_commitment = BlockCommitment::from([0; 32]);
+
+        assert!(matches!(
+            order_commitment
+                .verify(
+                    ChainType::Ethereum,
+                    &block.block_creator_address,
+                    Some(&block)
+                )
+                .await,
+            Err(Error::BlockCommitmentMismatch)
+        ));
+    }
+}
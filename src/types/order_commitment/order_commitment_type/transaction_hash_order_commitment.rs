@@ -8,3 +8,9 @@ impl TransactionHashOrderCommitment {
         Self(value)
     }
 }
+
+impl AsRef<str> for TransactionHashOrderCommitment {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
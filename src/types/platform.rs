@@ -12,13 +12,71 @@ pub enum Platform {
     Local,
 }
 
+/// The liveness backend a platform's clusters are managed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LivenessBackend {
+    /// Cluster membership is read from the Radius liveness contract and
+    /// sequencers register their RPC URLs to the seeder.
+    Radius,
+    /// Cluster membership is read from a local file or internal RPC.
+    Local,
+}
+
+/// Static description of a platform.
+///
+/// Adding a new EVM chain only requires a new [`Platform`] variant and its
+/// entry in [`Platform::info`]; the RPC handlers dispatch on this table.
+#[derive(Clone, Debug)]
+pub struct PlatformInfo {
+    pub name: &'static str,
+    pub chain_type: ChainType,
+    pub liveness_backend: LivenessBackend,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 3] = [Self::Ethereum, Self::Holesky, Self::Local];
+
+    pub fn info(&self) -> PlatformInfo {
+        match self {
+            Self::Ethereum => PlatformInfo {
+                name: "ethereum",
+                chain_type: ChainType::Ethereum,
+                liveness_backend: LivenessBackend::Radius,
+            },
+            Self::Holesky => PlatformInfo {
+                name: "holesky",
+                chain_type: ChainType::Ethereum,
+                liveness_backend: LivenessBackend::Radius,
+            },
+            Self::Local => PlatformInfo {
+                name: "local",
+                chain_type: ChainType::Ethereum,
+                liveness_backend: LivenessBackend::Local,
+            },
+        }
+    }
+
+    pub fn liveness_backend(&self) -> LivenessBackend {
+        self.info().liveness_backend
+    }
+
+    /// Checks that the sequencing info payload matches the liveness backend
+    /// of the platform.
+    pub fn check_sequencing_info_payload(
+        &self,
+        sequencing_info_payload: &SequencingInfoPayload,
+    ) -> Result<(), Error> {
+        match (self.liveness_backend(), sequencing_info_payload) {
+            (LivenessBackend::Radius, SequencingInfoPayload::Ethereum(_)) => Ok(()),
+            (LivenessBackend::Local, SequencingInfoPayload::Local(_)) => Ok(()),
+            _others => Err(Error::UnsupportedPlatform),
+        }
+    }
+}
+
 impl From<Platform> for ChainType {
     fn from(value: Platform) -> Self {
-        match value {
-            Platform::Ethereum => ChainType::Ethereum,
-            Platform::Holesky => ChainType::Ethereum,
-            Platform::Local => ChainType::Ethereum,
-        }
+        value.info().chain_type
     }
 }
 
@@ -26,11 +84,11 @@ impl FromStr for Platform {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "ethereum" => Ok(Self::Ethereum),
-            "holesky" => Ok(Self::Holesky),
-            "local" => Ok(Self::Local),
-            _ => Err(Error::UnsupportedPlatform),
-        }
+        let s = s.to_lowercase();
+
+        Self::ALL
+            .into_iter()
+            .find(|platform| platform.info().name == s)
+            .ok_or(Error::UnsupportedPlatform)
     }
 }
@@ -0,0 +1,32 @@
+use crate::types::prelude::*;
+
+/// How long the blocks and transactions of a rollup are kept.
+///
+/// A block is pruned once it falls outside any configured limit. With neither
+/// limit set, nothing is pruned.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str))]
+pub struct RetentionPolicy {
+    /// Number of most recent blocks to keep.
+    pub block_count: Option<u64>,
+    /// Maximum age of a block in seconds.
+    pub max_age: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.block_count.is_some() || self.max_age.is_some()
+    }
+}
+
+/// Progress of the pruner for a rollup.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
+#[kvstore(key(rollup_id: &str))]
+pub struct PruningStatus {
+    /// Every block below this height has been pruned.
+    pub pruned_rollup_block_height: u64,
+    pub pruned_block_count: u64,
+    pub pruned_transaction_count: u64,
+    /// Unix timestamp in seconds of the last pruning run that removed data.
+    pub last_pruned_at: Option<u64>,
+}
@@ -31,6 +31,17 @@ pub struct Rollup {
     pub executor_address_list: Vec<Address>,
 
     pub max_gas_limit: u64,
+
+    /// Cleared when the cluster of the rollup is deregistered. Inactive
+    /// rollups refuse transactions and are not sequenced.
+    #[serde(default = "Rollup::default_is_active")]
+    pub is_active: bool,
+
+    /// Number of consecutive blocks whose commitments are aggregated into a
+    /// single submission. Shared by every member of the cluster so that
+    /// followers validate the aggregate instead of the block commitment.
+    #[serde(default)]
+    pub block_commitment_aggregation_size: Option<u64>,
 }
 
 impl Rollup {
@@ -62,12 +73,33 @@ impl Rollup {
             platform,
             service_provider,
             max_gas_limit: 0,
+            is_active: true,
+            block_commitment_aggregation_size: None,
         }
     }
 
+    fn default_is_active() -> bool {
+        true
+    }
+
     pub fn set_executor_address_list(&mut self, executor_address_list: Vec<Address>) {
         self.executor_address_list = executor_address_list;
     }
+
+    pub fn set_is_active(&mut self, is_active: bool) {
+        self.is_active = is_active;
+    }
+
+    /// Returns the aggregated range closed at `rollup_block_height`, if any.
+    pub fn block_commitment_aggregation_range(
+        &self,
+        rollup_block_height: u64,
+    ) -> Option<(u64, u64)> {
+        self.block_commitment_aggregation_size
+            .and_then(|aggregation_size| {
+                BlockCommitmentProof::aggregation_range(aggregation_size, rollup_block_height)
+            })
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Model)]
@@ -83,6 +115,10 @@ impl RollupIdList {
         self.0.remove(cluster_id.as_ref());
     }
 
+    pub fn contains(&self, rollup_id: impl AsRef<str>) -> bool {
+        self.0.contains(rollup_id.as_ref())
+    }
+
     pub fn iter(&self) -> btree_set::Iter<'_, String> {
         self.0.iter()
     }
@@ -11,36 +11,92 @@ pub struct EthBundleTransactionData {
 }
 
 impl EthBundleTransactionData {
-    pub fn convert_to_rollup_transaction(&self) -> Result<RollupTransaction, Error> {
-        if self.plain_data.is_none() {
-            return Err(Error::PlainDataDoesNotExist);
+    pub fn new(encrypted_data: EncryptedData, open_data: EthBundleOpenData) -> Self {
+        Self {
+            encrypted_data,
+            open_data,
+            plain_data: None,
         }
+    }
 
-        // TODO:
-        // let rollup_transaction = self
-        // .open_data
-        // .convert_to_rollup_transaction(self.plain_data.as_ref().unwrap());
+    pub fn convert_to_rollup_transaction(&self) -> Result<RollupTransaction, Error> {
+        let plain_data = self
+            .plain_data
+            .as_ref()
+            .ok_or(Error::PlainDataDoesNotExist)?;
+
+        Ok(RollupTransaction::EthBundle(
+            self.open_data
+                .convert_to_rollup_transaction_list(plain_data)?,
+        ))
+    }
 
-        Ok(RollupTransaction::EthBundle)
+    pub fn update_plain_data(&mut self, plain_data: EthBundlePlainData) {
+        self.plain_data = Some(plain_data);
+    }
+
+    /// Returns the sum of the gas limits of the inner transactions.
+    pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
+        self.open_data.gas_limit()
     }
 }
 
+/// Open data of every transaction in the bundle, in execution order.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EthBundleOpenData {
-    pub raw_tx_hash: RawTransactionHash,
+    pub open_data_list: Vec<EthOpenData>,
 }
 
 impl EthBundleOpenData {
-    pub fn raw_tx_hash(&self) -> &RawTransactionHash {
-        &self.raw_tx_hash
+    pub fn new(open_data_list: Vec<EthOpenData>) -> Self {
+        Self { open_data_list }
     }
-}
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct EthBundlePlainData {
-    pub to: Option<eth_types::Address>,
-    pub value: eth_types::U256,
+    /// Returns the bundle hash derived from the inner transaction hashes.
+    pub fn raw_tx_hash(&self) -> Result<RawTransactionHash, Error> {
+        bundle_transaction_hash(&self.raw_tx_hash_list())
+    }
+
+    pub fn raw_tx_hash_list(&self) -> Vec<RawTransactionHash> {
+        self.open_data_list
+            .iter()
+            .map(|open_data| open_data.raw_tx_hash().clone())
+            .collect()
+    }
+
+    pub fn gas_limit(&self) -> Result<u64, Error> {
+        self.open_data_list
+            .iter()
+            .try_fold(0_u64, |gas_limit, open_data| {
+                gas_limit
+                    .checked_add(gas_to_u64(open_data.gas_limit)?)
+                    .ok_or(Error::ExceedMaxGasLimit)
+            })
+    }
 
-    #[serde(rename = "data")]
-    pub input: eth_types::Bytes,
+    pub fn convert_to_rollup_transaction_list(
+        &self,
+        plain_data: &EthBundlePlainData,
+    ) -> Result<Vec<eth_types::Transaction>, Error> {
+        if self.open_data_list.len() != plain_data.0.len() {
+            tracing::error!(
+                "Bundle length mismatch - open data: {:?} / plain data: {:?}",
+                self.open_data_list.len(),
+                plain_data.0.len()
+            );
+            return Err(Error::InvalidTransaction);
+        }
+
+        Ok(self
+            .open_data_list
+            .iter()
+            .zip(plain_data.0.iter())
+            .map(|(open_data, plain_data)| open_data.convert_to_rollup_transaction(plain_data))
+            .collect())
+    }
 }
+
+/// Plain data of every transaction in the bundle, encrypted as a JSON array.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct EthBundlePlainData(pub Vec<EthPlainData>);
@@ -39,7 +39,7 @@ impl EthTransactionData {
     }
 
     pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
-        Ok(self.open_data.gas_limit.as_u64())
+        gas_to_u64(self.open_data.gas_limit)
     }
 }
 
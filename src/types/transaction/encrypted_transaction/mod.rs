@@ -39,7 +39,7 @@ impl EncryptedTransaction {
 }
 
 impl EncryptedTransaction {
-    pub fn raw_transaction_hash(&self) -> RawTransactionHash {
+    pub fn raw_transaction_hash(&self) -> Result<RawTransactionHash, Error> {
         match self {
             Self::Skde(skde_encrypted_transaction) => {
                 return skde_encrypted_transaction
@@ -49,6 +49,12 @@ impl EncryptedTransaction {
         }
     }
 
+    /// Returns the hashes of the transactions carried by this encrypted
+    /// transaction, one per transaction order it takes.
+    pub fn raw_transaction_hash_list(&self) -> Vec<RawTransactionHash> {
+        self.transaction_data().raw_transaction_hash_list()
+    }
+
     pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
         match self {
             Self::Skde(skde_encrypted_transaction) => {
@@ -121,7 +127,7 @@ impl TransactionData {
     pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
         match self {
             Self::Eth(data) => data.get_transaction_gas_limit(),
-            Self::EthBundle(_data) => todo!("eth_bundle max_gas_limit"),
+            Self::EthBundle(data) => data.get_transaction_gas_limit(),
         }
     }
 
@@ -132,13 +138,66 @@ impl TransactionData {
         }
     }
 
-    pub fn update_plain_data(&mut self, plain_data: EthPlainData) {
+    pub fn update_plain_data(&mut self, plain_data: PlainData) -> Result<(), Error> {
+        match (self, plain_data) {
+            (Self::Eth(data), PlainData::Eth(plain_data)) => data.update_plain_data(plain_data),
+            (Self::EthBundle(data), PlainData::EthBundle(plain_data)) => {
+                data.update_plain_data(plain_data)
+            }
+            _others => return Err(Error::InvalidTransaction),
+        }
+
+        Ok(())
+    }
+
+    /// Parses the decrypted plain data and rebuilds the raw transaction.
+    ///
+    /// Bundles decrypt to a JSON array of plain data, one entry per inner
+    /// transaction in the order of the open data list.
+    pub fn decrypted_to_raw_transaction(
+        &self,
+        decrypted_data: &str,
+    ) -> Result<(RawTransaction, PlainData), Error> {
         match self {
             Self::Eth(data) => {
-                data.plain_data = Some(plain_data);
+                let eth_plain_data: EthPlainData =
+                    serde_json::from_str(decrypted_data).map_err(|e| {
+                        tracing::error!("Failed to parse decrypted data: {:?}", e);
+                        Error::Deserialize
+                    })?;
+
+                let rollup_transaction = data
+                    .open_data
+                    .convert_to_rollup_transaction(&eth_plain_data);
+
+                let eth_raw_transaction = EthRawTransaction::from(to_raw_tx(rollup_transaction));
+
+                Ok((
+                    RawTransaction::from(eth_raw_transaction),
+                    PlainData::from(eth_plain_data),
+                ))
             }
-            Self::EthBundle(_data) => {
-                unimplemented!()
+            Self::EthBundle(data) => {
+                let eth_bundle_plain_data: EthBundlePlainData =
+                    serde_json::from_str(decrypted_data).map_err(|e| {
+                        tracing::error!("Failed to parse decrypted bundle data: {:?}", e);
+                        Error::Deserialize
+                    })?;
+
+                let eth_raw_bundle_transaction = EthRawBundleTransaction::from(
+                    data.open_data
+                        .convert_to_rollup_transaction_list(&eth_bundle_plain_data)?
+                        .into_iter()
+                        .map(|rollup_transaction| {
+                            EthRawTransaction::from(to_raw_tx(rollup_transaction))
+                        })
+                        .collect::<Vec<EthRawTransaction>>(),
+                );
+
+                Ok((
+                    RawTransaction::from(eth_raw_bundle_transaction),
+                    PlainData::from(eth_bundle_plain_data),
+                ))
             }
         }
     }
@@ -150,10 +209,19 @@ impl TransactionData {
         }
     }
 
-    pub fn raw_transaction_hash(&self) -> RawTransactionHash {
+    pub fn raw_transaction_hash(&self) -> Result<RawTransactionHash, Error> {
         match self {
-            Self::Eth(data) => data.open_data.raw_tx_hash.clone(),
-            Self::EthBundle(data) => data.open_data.raw_tx_hash.clone(),
+            Self::Eth(data) => Ok(data.open_data.raw_tx_hash.clone()),
+            Self::EthBundle(data) => data.open_data.raw_tx_hash(),
+        }
+    }
+
+    /// Returns the hashes of the transactions carried by this data, which is
+    /// a single hash for `Eth` and the inner hashes for `EthBundle`.
+    pub fn raw_transaction_hash_list(&self) -> Vec<RawTransactionHash> {
+        match self {
+            Self::Eth(data) => vec![data.open_data.raw_tx_hash.clone()],
+            Self::EthBundle(data) => data.open_data.raw_tx_hash_list(),
         }
     }
 }
@@ -184,7 +252,7 @@ impl From<EthBundlePlainData> for PlainData {
 #[serde(rename_all = "snake_case")]
 pub enum RollupTransaction {
     Eth(eth_types::Transaction),
-    EthBundle,
+    EthBundle(Vec<eth_types::Transaction>),
 }
 
 impl RollupTransaction {
@@ -198,10 +266,14 @@ impl RollupTransaction {
                     raw_transaction_string,
                 )))
             }
-            // Todo: implement EthBundle
-            Self::EthBundle => Ok(RawTransaction::EthBundle(EthRawBundleTransaction::from(
-                String::new(),
-            ))),
+            Self::EthBundle(transaction_list) => {
+                Ok(RawTransaction::EthBundle(EthRawBundleTransaction::from(
+                    transaction_list
+                        .iter()
+                        .map(|transaction| EthRawTransaction::from(to_raw_tx(transaction.clone())))
+                        .collect::<Vec<EthRawTransaction>>(),
+                )))
+            }
         }
     }
 }
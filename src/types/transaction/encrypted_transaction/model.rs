@@ -55,4 +55,23 @@ impl EncryptedTransactionModel {
 
         kvstore()?.get_mut(key)
     }
+
+    pub fn delete_with_transaction_hash(
+        rollup_id: &str,
+        transaction_hash: &RawTransactionHash,
+    ) -> Result<(), KvStoreError> {
+        let key = &(Self::ID, rollup_id, transaction_hash);
+
+        kvstore()?.delete(key)
+    }
+
+    pub fn delete(
+        rollup_id: &str,
+        block_height: u64,
+        transaction_order: u64,
+    ) -> Result<(), KvStoreError> {
+        let key = &(Self::ID, rollup_id, block_height, transaction_order);
+
+        kvstore()?.delete(key)
+    }
 }
@@ -2,32 +2,139 @@ use ethers_core::types as eth_types;
 
 use crate::{error::Error, types::prelude::*};
 
+/// Raw transactions of a bundle, kept contiguous and in execution order.
 #[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct EthRawBundleTransaction(pub String);
+pub struct EthRawBundleTransaction(pub Vec<EthRawTransaction>);
 
-impl From<String> for EthRawBundleTransaction {
-    fn from(value: String) -> Self {
+impl From<Vec<EthRawTransaction>> for EthRawBundleTransaction {
+    fn from(value: Vec<EthRawTransaction>) -> Self {
         Self(value)
     }
 }
 
 impl EthRawBundleTransaction {
-    pub fn raw_transaction_hash(&self) -> RawTransactionHash {
-        let raw_transaction_string = serde_json::to_string(&self.0).unwrap();
-        let parsed_raw_transaction_string: String =
-            serde_json::from_str(&raw_transaction_string).unwrap();
-        let decoded_transaction = decode_rlp_transaction(&parsed_raw_transaction_string).unwrap();
-
-        RawTransactionHash::new(const_hex::encode_prefixed(
-            decoded_transaction.hash.as_bytes(),
-        ))
-    }
-
-    pub fn rollup_transaction(&self) -> Result<eth_types::Transaction, Error> {
-        let raw_transaction_string = serde_json::to_string(&self.0).unwrap();
-        let parsed_raw_transaction_string: String =
-            serde_json::from_str(&raw_transaction_string).unwrap();
-        decode_rlp_transaction(&parsed_raw_transaction_string)
-            .map_err(|_| Error::InvalidTransaction)
+    pub fn raw_transaction_hash(&self) -> Result<RawTransactionHash, Error> {
+        bundle_transaction_hash(&self.raw_transaction_hash_list()?)
+    }
+
+    pub fn raw_transaction_hash_list(&self) -> Result<Vec<RawTransactionHash>, Error> {
+        self.0
+            .iter()
+            .map(|raw_transaction| raw_transaction.raw_transaction_hash())
+            .collect()
+    }
+
+    pub fn rollup_transaction_list(&self) -> Result<Vec<eth_types::Transaction>, Error> {
+        self.0
+            .iter()
+            .map(|raw_transaction| raw_transaction.rollup_transaction())
+            .collect()
+    }
+
+    /// Returns the sum of the gas limits of the inner transactions.
+    pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
+        self.rollup_transaction_list()?
+            .iter()
+            .try_fold(0_u64, |gas_limit, transaction| {
+                gas_limit
+                    .checked_add(gas_to_u64(transaction.gas)?)
+                    .ok_or(Error::ExceedMaxGasLimit)
+            })
+    }
+}
+
+/// Converts a user-supplied gas amount, rejecting values that do not fit in a
+/// `u64` instead of panicking like `U256::as_u64`.
+pub fn gas_to_u64(gas: eth_types::U256) -> Result<u64, Error> {
+    u64::try_from(gas).map_err(|_| Error::InvalidTransaction)
+}
+
+/// Computes the bundle hash as `keccak256(hash_0 || hash_1 || ...)` over the
+/// inner transaction hashes. Every hash must be a 32-byte hex string so that
+/// two different bundles cannot produce the same input.
+pub fn bundle_transaction_hash(
+    raw_transaction_hash_list: &[RawTransactionHash],
+) -> Result<RawTransactionHash, Error> {
+    if raw_transaction_hash_list.is_empty() {
+        return Err(Error::EmptyTransactionBundle);
+    }
+
+    let mut concatenated_hash = Vec::with_capacity(raw_transaction_hash_list.len() * 32);
+    for raw_transaction_hash in raw_transaction_hash_list {
+        let raw_transaction_hash =
+            const_hex::decode_to_array::<&str, 32>(raw_transaction_hash.as_ref())
+                .map_err(|_| Error::InvalidTransaction)?;
+
+        concatenated_hash.extend_from_slice(&raw_transaction_hash);
+    }
+
+    Ok(RawTransactionHash::from(MerkleTree::hash(
+        &concatenated_hash,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> RawTransactionHash {
+        RawTransactionHash::from([byte; 32])
+    }
+
+    #[test]
+    fn bundle_transaction_hash_is_deterministic() {
+        let raw_transaction_hash_list = vec![hash(1), hash(2)];
+
+        assert_eq!(
+            bundle_transaction_hash(&raw_transaction_hash_list).unwrap(),
+            bundle_transaction_hash(&raw_transaction_hash_list).unwrap()
+        );
+    }
+
+    #[test]
+    fn different_bundles_have_different_hashes() {
+        let bundle_hash = bundle_transaction_hash(&[hash(1), hash(2)]).unwrap();
+
+        assert_ne!(
+            bundle_hash,
+            bundle_transaction_hash(&[hash(2), hash(1)]).unwrap()
+        );
+        assert_ne!(bundle_hash, bundle_transaction_hash(&[hash(1)]).unwrap());
+        assert_ne!(
+            bundle_hash,
+            bundle_transaction_hash(&[hash(1), hash(2), hash(3)]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_bundle() {
+        assert!(matches!(
+            bundle_transaction_hash(&[]),
+            Err(Error::EmptyTransactionBundle)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_malformed_transaction_hash() {
+        let short_hash = RawTransactionHash::from("0x1234".to_owned());
+        let non_hex_hash = RawTransactionHash::from(format!("0x{}", "zz".repeat(32)));
+
+        assert!(matches!(
+            bundle_transaction_hash(&[hash(1), short_hash]),
+            Err(Error::InvalidTransaction)
+        ));
+        assert!(matches!(
+            bundle_transaction_hash(&[non_hex_hash]),
+            Err(Error::InvalidTransaction)
+        ));
+    }
+
+    #[test]
+    fn rejects_gas_that_does_not_fit_in_u64() {
+        assert_eq!(gas_to_u64(eth_types::U256::from(21_000)).unwrap(), 21_000);
+        assert!(matches!(
+            gas_to_u64(eth_types::U256::from(u64::MAX) + 1),
+            Err(Error::InvalidTransaction)
+        ));
     }
 }
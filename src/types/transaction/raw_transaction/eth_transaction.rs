@@ -18,12 +18,12 @@ impl From<String> for EthRawTransaction {
 }
 
 impl EthRawTransaction {
-    pub fn raw_transaction_hash(&self) -> RawTransactionHash {
-        let decoded_transaction = decode_rlp_transaction(&self.0).unwrap();
+    pub fn raw_transaction_hash(&self) -> Result<RawTransactionHash, Error> {
+        let decoded_transaction = self.rollup_transaction()?;
 
         let transaction_hash = const_hex::encode_prefixed(decoded_transaction.hash);
 
-        RawTransactionHash::from(transaction_hash)
+        Ok(RawTransactionHash::from(transaction_hash))
     }
 
     pub fn rollup_transaction(&self) -> Result<eth_types::Transaction, Error> {
@@ -85,7 +85,7 @@ impl From<EthRawBundleTransaction> for RawTransaction {
 }
 
 impl RawTransaction {
-    pub fn raw_transaction_hash(&self) -> RawTransactionHash {
+    pub fn raw_transaction_hash(&self) -> Result<RawTransactionHash, Error> {
         match self {
             RawTransaction::Eth(eth) => eth.raw_transaction_hash(),
             RawTransaction::EthBundle(eth_bundle) => eth_bundle.raw_transaction_hash(),
@@ -94,8 +94,17 @@ impl RawTransaction {
 
     pub fn get_transaction_gas_limit(&self) -> Result<u64, Error> {
         match self {
-            RawTransaction::Eth(eth) => Ok(eth.rollup_transaction()?.gas.as_u64()),
-            RawTransaction::EthBundle(_eth_bundle) => todo!("eth_bundle max_gas_limit"),
+            RawTransaction::Eth(eth) => gas_to_u64(eth.rollup_transaction()?.gas),
+            RawTransaction::EthBundle(eth_bundle) => eth_bundle.get_transaction_gas_limit(),
+        }
+    }
+
+    /// Returns the hashes of the transactions carried by this raw transaction,
+    /// which is a single hash for `Eth` and the inner hashes for `EthBundle`.
+    pub fn raw_transaction_hash_list(&self) -> Result<Vec<RawTransactionHash>, Error> {
+        match self {
+            RawTransaction::Eth(eth) => Ok(vec![eth.raw_transaction_hash()?]),
+            RawTransaction::EthBundle(eth_bundle) => eth_bundle.raw_transaction_hash_list(),
         }
     }
 }
@@ -43,6 +43,15 @@ pub enum ValidationInfo {
     Symbiotic(SymbioticValidationInfo),
 }
 
+impl ValidationInfo {
+    pub fn validation_rpc_url(&self) -> &str {
+        match self {
+            Self::EigenLayer(validation_info) => &validation_info.validation_rpc_url,
+            Self::Symbiotic(validation_info) => &validation_info.validation_rpc_url,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EigenLayerValidationInfo {
     pub validation_rpc_url: String,
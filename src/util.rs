@@ -1,7 +1,6 @@
-use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use radius_sdk::json_rpc::client::{Id, RpcClient, RpcClientError};
-use reqwest::Client;
 
 use crate::{
     error::{self, Error},
@@ -16,21 +15,12 @@ use crate::{
     types::{Cluster, Config, RawTransaction},
 };
 
-pub async fn health_check(sequencer_rpc_url: impl AsRef<str>) -> Result<(), Error> {
-    let health_check_url = format!("{}/health", sequencer_rpc_url.as_ref());
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(3))
-        .build()
-        .map_err(Error::InvalidURL)?;
-
-    client
-        .get(health_check_url)
-        .send()
-        .await
-        .map_err(Error::HealthCheck)?;
-
-    Ok(())
+/// Returns the current Unix timestamp in seconds.
+pub fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
 }
 
 pub fn initialize_logger(config: &Config) -> Result<(), Error> {